@@ -0,0 +1,35 @@
+// capital_lookup.rs
+//
+// Look up a country's capital without panicking when it is missing.
+//
+// Execute `cargo run -- exercises hint capital_lookup` for a hint!
+
+// I AM NOT DONE
+
+use std::collections::HashMap;
+
+fn capital<'a>(capitals: &HashMap<&str, &'a str>, country: &str) -> Option<&'a str> {
+    Some(capitals[country])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capitals() -> HashMap<&'static str, &'static str> {
+        let mut capitals = HashMap::new();
+        capitals.insert("spain", "madrid");
+        capitals.insert("france", "paris");
+        capitals
+    }
+
+    #[test]
+    fn finds_known_countries() {
+        assert_eq!(capital(&capitals(), "france"), Some("paris"));
+    }
+
+    #[test]
+    fn missing_country_is_none() {
+        assert_eq!(capital(&capitals(), "italy"), None);
+    }
+}
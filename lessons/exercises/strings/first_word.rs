@@ -0,0 +1,33 @@
+// first_word.rs
+//
+// Return the first word of `s` as a slice of the original string.
+// Taking `&str` (not `&String`) means both `String`s and literals work.
+//
+// Execute `cargo run -- exercises hint first_word` for a hint!
+
+// I AM NOT DONE
+
+fn first_word(s: &str) -> &str {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_the_first_word() {
+        assert_eq!(first_word("hello world"), "hello");
+    }
+
+    #[test]
+    fn single_word_is_the_whole_string() {
+        assert_eq!(first_word("anadi"), "anadi");
+    }
+
+    #[test]
+    fn works_with_a_borrowed_string() {
+        let name = String::from("M'21 anadi");
+        assert_eq!(first_word(&name), "M'21");
+    }
+}
@@ -0,0 +1,32 @@
+// shared_counter.rs
+//
+// Ten threads each add their number to a shared counter. Make it compile
+// without changing what the threads do.
+//
+// Execute `cargo run -- exercises hint shared_counter` for a hint!
+
+// I AM NOT DONE
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+fn main() {
+    let counter = Mutex::new(0);
+    let mut handles = vec![];
+
+    for i in 0..10 {
+        let handle = thread::spawn(move || {
+            let mut num = counter.lock().expect("no thread panics while holding the lock");
+            *num += i;
+        });
+        handles.push(handle);
+    }
+
+    for handle in handles {
+        handle.join().expect("counter thread panicked");
+    }
+
+    let total = *counter.lock().expect("all threads have finished");
+    assert_eq!(total, 45);
+    println!("Final counter value: {}", total);
+}
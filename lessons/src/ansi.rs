@@ -0,0 +1,288 @@
+// Styled text for the terminal: spans of text with a style, and fitting a
+// line of spans into a fixed number of columns.
+//
+// Widths are counted in terminal columns: wide East Asian characters and
+// most emoji take two, everything else one. Tabs should be expanded before
+// text gets here.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Bold,
+    Dim,
+    Italic,
+    BoldItalic,
+    Reverse,
+    Keyword,
+    Type,
+    Str,
+    Number,
+    Comment,
+    Macro,
+    Attribute,
+    Heading,
+    Code,
+    Link,
+    Ok,
+    Error,
+}
+
+impl Style {
+    pub fn code(self) -> &'static str {
+        match self {
+            Style::Plain => "\x1b[0m",
+            Style::Bold => "\x1b[0;1m",
+            Style::Dim => "\x1b[0;2m",
+            Style::Italic => "\x1b[0;3m",
+            Style::BoldItalic => "\x1b[0;1;3m",
+            Style::Reverse => "\x1b[0;7m",
+            Style::Keyword => "\x1b[0;35m",
+            Style::Type => "\x1b[0;33m",
+            Style::Str => "\x1b[0;32m",
+            Style::Number => "\x1b[0;36m",
+            Style::Comment => "\x1b[0;2;3m",
+            Style::Macro => "\x1b[0;34m",
+            Style::Attribute => "\x1b[0;2;33m",
+            Style::Heading => "\x1b[0;1;4m",
+            Style::Code => "\x1b[0;36m",
+            Style::Link => "\x1b[0;4;34m",
+            Style::Ok => "\x1b[0;32m",
+            Style::Error => "\x1b[0;31m",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub style: Style,
+    pub text: String,
+}
+
+impl Span {
+    pub fn new(style: Style, text: impl Into<String>) -> Span {
+        Span {
+            style,
+            text: text.into(),
+        }
+    }
+}
+
+pub type Line = Vec<Span>;
+
+pub fn plain(text: impl Into<String>) -> Line {
+    vec![Span::new(Style::Plain, text)]
+}
+
+pub fn char_width(c: char) -> usize {
+    match c as u32 {
+        0..=0x1f | 0x7f..=0x9f | 0x200b..=0x200f | 0xfe00..=0xfe0f => 0,
+        0x1100..=0x115f
+        | 0x231a..=0x231b
+        | 0x23e9..=0x23ec
+        | 0x23f0
+        | 0x23f3
+        | 0x2614..=0x2615
+        | 0x26a1
+        | 0x26bd..=0x26be
+        | 0x26d4
+        | 0x2705
+        | 0x270a..=0x270b
+        | 0x2728
+        | 0x274c
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x2b50
+        | 0x2b55
+        | 0x2e80..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f300..=0x1f64f
+        | 0x1f680..=0x1f6ff
+        | 0x1f900..=0x1faff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+pub fn width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+pub fn line_width(line: &[Span]) -> usize {
+    line.iter().map(|s| width(&s.text)).sum()
+}
+
+pub fn expand_tabs(text: &str) -> String {
+    text.replace('\t', "    ")
+}
+
+// At most `columns` wide, cut with `…` when it does not fit.
+pub fn truncate(line: &[Span], columns: usize) -> Line {
+    if line_width(line) <= columns {
+        return line.to_vec();
+    }
+    let room = columns.saturating_sub(1);
+    let mut out = Vec::new();
+    let mut used = 0;
+    let mut style = Style::Plain;
+    'spans: for span in line {
+        style = span.style;
+        for c in span.text.chars() {
+            let w = char_width(c);
+            if used + w > room {
+                break 'spans;
+            }
+            push(&mut out, span.style, c.encode_utf8(&mut [0; 4]));
+            used += w;
+        }
+    }
+    if columns > 0 {
+        push(&mut out, style, "…");
+    }
+    out
+}
+
+// Exactly `columns` wide: cut with `…` when too long, padded with spaces
+// in the last span's style when too short. Always ends with a reset.
+pub fn fit(line: &[Span], columns: usize) -> String {
+    let mut line = truncate(line, columns);
+    let used = line_width(&line);
+    if used < columns {
+        let style = line.last().map_or(Style::Plain, |s| s.style);
+        push(&mut line, style, &" ".repeat(columns - used));
+    }
+    to_ansi(&line)
+}
+
+// The line with escape codes, ending with a reset.
+pub fn to_ansi(line: &[Span]) -> String {
+    let mut out = String::new();
+    for span in line {
+        out += span.style.code();
+        out += &span.text;
+    }
+    out += Style::Plain.code();
+    out
+}
+
+// Soft-wraps at spaces to `columns`, breaking long words when it must.
+pub fn wrap(line: &[Span], columns: usize) -> Vec<Line> {
+    let columns = columns.max(1);
+    let mut lines = vec![Vec::new()];
+    let mut used = 0;
+    for span in line {
+        for word in span.text.split_inclusive(' ') {
+            if used > 0 && used + width(word.trim_end()) > columns {
+                trim_end(lines.last_mut().unwrap());
+                lines.push(Vec::new());
+                used = 0;
+            }
+            let mut word = if used == 0 { word.trim_start() } else { word };
+            while width(word.trim_end()) > columns.saturating_sub(used) {
+                // As many characters as fit, but at least one.
+                let mut split = 0;
+                let mut w = 0;
+                for (i, c) in word.char_indices() {
+                    w += char_width(c);
+                    if w > columns - used && split > 0 {
+                        break;
+                    }
+                    split = i + c.len_utf8();
+                }
+                push(lines.last_mut().unwrap(), span.style, &word[..split]);
+                lines.push(Vec::new());
+                used = 0;
+                word = &word[split..];
+            }
+            push(lines.last_mut().unwrap(), span.style, word);
+            used += width(word);
+        }
+    }
+    trim_end(lines.last_mut().unwrap());
+    lines
+}
+
+// Drops trailing spaces, which would only push a wrapped line past the
+// edge.
+fn trim_end(line: &mut Line) {
+    while let Some(last) = line.last_mut() {
+        let len = last.text.trim_end_matches(' ').len();
+        last.text.truncate(len);
+        if !last.text.is_empty() {
+            break;
+        }
+        line.pop();
+    }
+}
+
+fn push(line: &mut Line, style: Style, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match line.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(text),
+        _ => line.push(Span::new(style, text)),
+    }
+}
+
+// Concatenated text without styles, for tests and plain output.
+pub fn text(line: &[Span]) -> String {
+    line.iter().map(|s| s.text.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn fits_lines_to_columns() {
+        let line = vec![
+            Span::new(Style::Keyword, "fn"),
+            Span::new(Style::Plain, " main()"),
+        ];
+        assert_eq!(strip(&fit(&line, 12)), "fn main()   ");
+        assert_eq!(strip(&fit(&line, 6)), "fn ma…");
+        assert!(fit(&line, 6).ends_with("\x1b[0m"));
+        // Wide characters are never split across the edge.
+        assert_eq!(strip(&fit(&plain("日本語"), 4)), "日… ");
+        assert_eq!(width("🦀 Rust"), 7);
+        assert_eq!(width("✅ done"), 7);
+    }
+
+    #[test]
+    fn wraps_at_spaces() {
+        let line = vec![
+            Span::new(Style::Plain, "ownership moves "),
+            Span::new(Style::Code, "String"),
+            Span::new(Style::Plain, " values"),
+        ];
+        let lines: Vec<String> = wrap(&line, 16).iter().map(|l| text(l)).collect();
+        assert_eq!(lines, ["ownership moves", "String values"]);
+        let long: Vec<String> = wrap(&plain("abcdefghij"), 4)
+            .iter()
+            .map(|l| text(l))
+            .collect();
+        assert_eq!(long, ["abcd", "efgh", "ij"]);
+    }
+}
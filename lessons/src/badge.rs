@@ -0,0 +1,168 @@
+// Flat-style SVG badges, drawn locally instead of fetched from shields.io.
+//
+// The layout follows shields.io's "flat" style: a grey label, a coloured
+// message, 5px of padding around each text. Text is measured with Verdana's
+// advance widths at 11px, the font the badge asks for, and pinned with
+// `textLength` so a fallback font cannot overflow its box.
+
+const FONT_SIZE: f64 = 11.0;
+const UNITS_PER_EM: f64 = 2048.0;
+const PADDING: f64 = 10.0;
+
+// Verdana advance widths in font units for ' '..='~'.
+#[rustfmt::skip]
+const VERDANA: [u16; 95] = [
+    720, 824, 1007, 1674, 1302, 2224, 1484, 549, 929, 929, 1302, 1674, 745, 862, 745, 1302, // ' '..='/'
+    1302, 1302, 1302, 1302, 1302, 1302, 1302, 1302, 1302, 1302, // '0'..='9'
+    929, 929, 1674, 1674, 1674, 1112, 2048, // ':'..='@'
+    1401, 1405, 1430, 1577, 1294, 1178, 1587, 1540, 860, 929, 1415, 1145, 1722, // 'A'..='M'
+    1532, 1612, 1243, 1612, 1424, 1401, 1257, 1500, 1401, 2025, 1406, 1254, 1405, // 'N'..='Z'
+    929, 1302, 929, 1674, 1302, 1302, // '['..='`'
+    1233, 1276, 1066, 1276, 1219, 720, 1276, 1296, 562, 705, 1207, 562, 1992, // 'a'..='m'
+    1296, 1250, 1276, 1276, 874, 1064, 807, 1296, 1207, 1665, 1203, 1207, 1068, // 'n'..='z'
+    1300, 929, 1300, 1674, // '{'..='~'
+];
+
+// Colours used by shields.io, from "nothing yet" to "all of it".
+pub const RED: &str = "#e05d44";
+pub const ORANGE: &str = "#fe7d37";
+pub const YELLOW: &str = "#dfb317";
+pub const GREEN: &str = "#97ca00";
+pub const BRIGHT_GREEN: &str = "#4c1";
+pub const GREY: &str = "#9f9f9f";
+
+#[derive(Debug, Clone)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    pub color: String,
+}
+
+// Rendered width of `text` in pixels. Characters outside ASCII are counted
+// as a wide letter, which errs on the roomy side.
+pub fn text_width(text: &str) -> f64 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => u32::from(VERDANA[c as usize - 32]),
+            _ => 2048,
+        })
+        .sum();
+    f64::from(units) * FONT_SIZE / UNITS_PER_EM
+}
+
+// The colour for `done` out of `total`.
+pub fn progress_color(done: usize, total: usize) -> &'static str {
+    if total == 0 {
+        return GREY;
+    }
+    match done * 100 / total {
+        100.. => BRIGHT_GREEN,
+        75.. => GREEN,
+        50.. => YELLOW,
+        25.. => ORANGE,
+        _ => RED,
+    }
+}
+
+impl Badge {
+    // A `done/total` badge, coloured by how far along it is.
+    pub fn progress(label: &str, done: usize, total: usize, what: &str) -> Badge {
+        Badge {
+            label: label.to_string(),
+            message: format!("{}/{} {}", done, total, what),
+            color: progress_color(done, total).to_string(),
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let label_text = text_width(&self.label);
+        let message_text = text_width(&self.message);
+        let label_width = (label_text + PADDING).round();
+        let message_width = (message_text + PADDING).round();
+        let width = label_width + message_width;
+        let title = escape(&format!("{}: {}", self.label, self.message));
+        let text = |x: f64, length: f64, s: &str| {
+            let s = escape(s);
+            format!(
+                concat!(
+                    "<text x=\"{x:.1}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\" textLength=\"{len:.1}\">{s}</text>",
+                    "<text x=\"{x:.1}\" y=\"14\" textLength=\"{len:.1}\">{s}</text>"
+                ),
+                x = x,
+                len = length,
+                s = s
+            )
+        };
+        format!(
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"20\" role=\"img\" aria-label=\"{title}\">",
+                "<title>{title}</title>",
+                "<linearGradient id=\"s\" x2=\"0\" y2=\"100%\"><stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/><stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>",
+                "<clipPath id=\"r\"><rect width=\"{w}\" height=\"20\" rx=\"3\" fill=\"#fff\"/></clipPath>",
+                "<g clip-path=\"url(#r)\"><rect width=\"{lw}\" height=\"20\" fill=\"#555\"/><rect x=\"{lw}\" width=\"{mw}\" height=\"20\" fill=\"{color}\"/><rect width=\"{w}\" height=\"20\" fill=\"url(#s)\"/></g>",
+                "<g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">{label}{message}</g>",
+                "</svg>\n"
+            ),
+            w = width,
+            lw = label_width,
+            mw = message_width,
+            title = title,
+            color = escape(&self.color),
+            label = text(label_width / 2.0, label_text, &self.label),
+            message = text(
+                label_width + message_width / 2.0,
+                message_text,
+                &self.message
+            ),
+        )
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_text_like_shields() {
+        // shields.io draws the "build" label 37px wide.
+        assert_eq!((text_width("build") + PADDING).round(), 37.0);
+        assert_eq!(text_width("0123"), text_width("9876"));
+        assert!(text_width("WWW") > text_width("iii"));
+        assert_eq!(text_width(""), 0.0);
+    }
+
+    #[test]
+    fn renders_an_escaped_flat_badge() {
+        let badge = Badge::progress("lessons", 3, 4, "<done>");
+        assert_eq!(badge.color, GREEN);
+        let svg = badge.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\""));
+        assert!(svg.contains("<title>lessons: 3/4 &lt;done&gt;</title>"));
+        assert!(!svg.contains("<done>"));
+        assert_eq!(svg.matches("<text ").count(), 4);
+
+        let wider = Badge::progress("lessons", 30, 40, "<done>").to_svg();
+        let width = |svg: &str| -> f64 {
+            let rest = &svg[svg.find("width=\"").unwrap() + 7..];
+            rest[..rest.find('"').unwrap()].parse().unwrap()
+        };
+        assert!(width(&wider) > width(&svg));
+    }
+
+    #[test]
+    fn colours_by_progress() {
+        assert_eq!(progress_color(0, 0), GREY);
+        assert_eq!(progress_color(0, 10), RED);
+        assert_eq!(progress_color(3, 10), ORANGE);
+        assert_eq!(progress_color(5, 10), YELLOW);
+        assert_eq!(progress_color(10, 10), BRIGHT_GREEN);
+    }
+}
@@ -0,0 +1,98 @@
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use lessons::catalog;
+use lessons::tasks::{self, TaskFile};
+
+const USAGE: &str = "\
+usage: tasks [--root DIR] [-m MESSAGE] [TASK...]
+
+Runs tasks from <root>/tasks.toml; with no TASK, lists them.
+  -m MESSAGE   commit message for `{message}` (otherwise prompted for,
+               defaulting to \"Update: <timestamp>\")
+";
+
+fn main() -> ExitCode {
+    let mut root = catalog::default_root();
+    let mut message = None;
+    let mut names = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" | "-m" => {
+                let Some(value) = args.next() else {
+                    eprintln!("{} needs a value\n\n{}", arg, USAGE);
+                    return ExitCode::from(2);
+                };
+                if arg == "--root" {
+                    root = PathBuf::from(value);
+                } else {
+                    message = Some(value);
+                }
+            }
+            "help" | "--help" | "-h" => {
+                print!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ => names.push(arg),
+        }
+    }
+
+    let file = match TaskFile::load(&TaskFile::default_path(&root)) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if names.is_empty() {
+        for task in &file.tasks {
+            println!("{:<10} {}", task.name, task.description);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut selected = Vec::new();
+    for name in &names {
+        match file.get(name) {
+            Some(task) => selected.push(task),
+            None => {
+                eprintln!("no task named `{}` (run `tasks` to list them)", name);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let mut vars = file.vars.clone();
+    if selected.iter().any(|t| t.uses("message")) {
+        let message = message
+            .or_else(prompt_message)
+            .unwrap_or_else(tasks::default_message);
+        vars.insert("message".to_string(), message);
+    }
+
+    for task in selected {
+        println!("==> {}", task.name);
+        if let Err(e) = tasks::run(task, &vars, &root) {
+            eprintln!("task `{}`: {}", task.name, e);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// Asks on a terminal, as quick-push.sh did. `None` means use the default.
+fn prompt_message() -> Option<String> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+    print!("Enter commit message (or press Enter for default): ");
+    io::stdout().flush().ok()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).ok()?;
+    let line = line.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
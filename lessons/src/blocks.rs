@@ -0,0 +1,131 @@
+// Splits a notes file into prose and code blocks.
+//
+// The notes are plain `.rs` files where explanations and examples are
+// interleaved without markers, so this works line by line: a line that
+// looks like Rust starts a code block, and an open `{` keeps the block
+// going until it is closed. Markdown fences are honoured when present.
+
+use crate::lexer::{self, TokenKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Prose,
+    Code,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub text: String,
+    // 1-based, inclusive.
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+pub fn split(src: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut depth: i32 = 0;
+    // Inside a ``` fence: Some(true) for Rust, Some(false) for other languages.
+    let mut fence: Option<bool> = None;
+
+    for (n, line) in src.lines().enumerate() {
+        let trimmed = line.trim();
+        let kind = if let Some(is_rust) = fence {
+            if trimmed.starts_with("```") {
+                fence = None;
+                if is_rust {
+                    continue;
+                }
+            }
+            if is_rust {
+                BlockKind::Code
+            } else {
+                BlockKind::Prose
+            }
+        } else if let Some(lang) = trimmed.strip_prefix("```") {
+            let is_rust = lang.is_empty() || lang.starts_with("rust");
+            fence = Some(is_rust);
+            if is_rust {
+                continue;
+            }
+            BlockKind::Prose
+        } else if trimmed.is_empty() {
+            // Blank lines belong to whatever block is open.
+            if let Some(last) = blocks.last_mut() {
+                last.text.push('\n');
+            }
+            continue;
+        } else if depth > 0 || looks_like_code(trimmed) {
+            depth = (depth + brace_delta(line)).max(0);
+            BlockKind::Code
+        } else {
+            BlockKind::Prose
+        };
+
+        match blocks.last_mut() {
+            Some(last) if last.kind == kind => {
+                last.text.push_str(line);
+                last.text.push('\n');
+                last.last_line = n + 1;
+            }
+            _ => blocks.push(Block {
+                kind,
+                text: format!("{}\n", line),
+                first_line: n + 1,
+                last_line: n + 1,
+            }),
+        }
+    }
+
+    for block in &mut blocks {
+        let end = block.text.trim_end().len();
+        block.text.truncate(end);
+        block.text.push('\n');
+    }
+    blocks
+}
+
+// Net `{` minus `}` on a line, ignoring braces inside strings and chars.
+fn brace_delta(line: &str) -> i32 {
+    lexer::tokenize(line)
+        .iter()
+        .filter(|t| t.kind == TokenKind::Punct)
+        .map(|t| match t.text(line) {
+            "{" => 1,
+            "}" => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn looks_like_code(line: &str) -> bool {
+    // `/* ...` and `... */` wrap whole sections of prose in some notes.
+    let line = line.trim_start_matches("/*").trim_end_matches("*/").trim();
+    if line.is_empty() {
+        return false;
+    }
+    if line.starts_with("//") || line.starts_with("#[") || line.starts_with("#![") {
+        return true;
+    }
+    if line.starts_with('{') || line.starts_with('}') || line.ends_with(';') {
+        return true;
+    }
+
+    let opens = line.ends_with('{');
+    let first = line
+        .split(|c: char| !lexer::is_ident_continue(c) && c != '!')
+        .next()
+        .unwrap_or("");
+    match first {
+        "fn" => line.contains('('),
+        "use" => line.contains("::"),
+        "let" => line.contains('='),
+        "struct" | "enum" | "impl" | "trait" | "mod" | "match" | "for" | "while" | "loop"
+        | "if" | "else" | "unsafe" => opens,
+        "pub" | "const" | "static" | "type" | "extern" | "macro_rules!" => {
+            opens || line.contains('(')
+        }
+        // A bare macro call such as `println!("{}", x)` missing its `;`.
+        _ => first.ends_with('!') && line[first.len()..].starts_with('('),
+    }
+}
@@ -0,0 +1,612 @@
+// The state behind `lessons browse`: a category tree on the left and the
+// open lesson on the right, prose as text and code highlighted.
+//
+// Keys change the state or come back as an `Action` for the caller to
+// carry out (running a snippet, recording progress). `render` draws a whole
+// frame for whatever size the terminal is now. Nothing here touches the
+// terminal itself; `main` owns the loop.
+
+use std::collections::BTreeSet;
+
+use crate::ansi::{self, Line, Span, Style};
+use crate::blocks::{self, BlockKind};
+use crate::catalog::{Catalog, Lesson};
+use crate::highlight;
+use crate::markdown;
+use crate::snippets::{self, Snippet};
+use crate::term::Key;
+
+// Smaller than this and we only ask for a bigger window.
+const MIN_COLUMNS: usize = 40;
+const MIN_ROWS: usize = 6;
+
+const HELP: &str =
+    "↑↓ move  ⏎ open  tab switch pane  r run  n next snippet  e exercises  d done  ? help  q quit";
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    Quit,
+    Run(Snippet),
+    MarkDone(String),
+    Exercises(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Tree,
+    Page,
+}
+
+enum Row {
+    Category(String),
+    // Index into the catalog's lessons.
+    Lesson(usize),
+}
+
+struct Page {
+    lesson: usize,
+    lines: Vec<Line>,
+    // The notes line each rendered line came from.
+    source_lines: Vec<usize>,
+    snippets: Vec<Snippet>,
+    cursor: usize,
+    top: usize,
+}
+
+struct Overlay {
+    title: String,
+    lines: Vec<Line>,
+    top: usize,
+}
+
+pub struct Browser<'a> {
+    catalog: &'a Catalog,
+    collapsed: BTreeSet<String>,
+    done: BTreeSet<String>,
+    cursor: usize,
+    top: usize,
+    focus: Focus,
+    page: Option<Page>,
+    overlay: Option<Overlay>,
+    status: String,
+    // Pane height in the last frame, for paging.
+    height: usize,
+}
+
+impl<'a> Browser<'a> {
+    // `done` holds the ids of lessons already studied.
+    pub fn new(catalog: &'a Catalog, done: BTreeSet<String>) -> Browser<'a> {
+        Browser {
+            catalog,
+            collapsed: BTreeSet::new(),
+            done,
+            cursor: 0,
+            top: 0,
+            focus: Focus::Tree,
+            page: None,
+            overlay: None,
+            status: String::new(),
+            height: 20,
+        }
+    }
+
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = status.into();
+    }
+
+    pub fn mark_done(&mut self, id: &str) {
+        self.done.insert(id.to_string());
+        self.status = format!("{} marked as done", id);
+    }
+
+    // Shows `lines` over the lesson pane until Esc.
+    pub fn show(&mut self, title: impl Into<String>, lines: Vec<Line>) {
+        self.overlay = Some(Overlay {
+            title: title.into(),
+            lines,
+            top: 0,
+        });
+    }
+
+    // The lesson on screen, or the one under the tree cursor.
+    pub fn current(&self) -> Option<&'a Lesson> {
+        let lessons = self.catalog.lessons();
+        match (&self.page, self.rows().get(self.cursor)) {
+            (Some(page), _) if self.focus == Focus::Page => Some(&lessons[page.lesson]),
+            (_, Some(Row::Lesson(i))) => Some(&lessons[*i]),
+            (Some(page), _) => Some(&lessons[page.lesson]),
+            _ => None,
+        }
+    }
+
+    // Categories in catalog order, each followed by its lessons unless it
+    // is collapsed.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        let mut categories: Vec<&str> = Vec::new();
+        for lesson in self.catalog.lessons() {
+            if !categories.contains(&lesson.category.as_str()) {
+                categories.push(&lesson.category);
+            }
+        }
+        for category in categories {
+            rows.push(Row::Category(category.to_string()));
+            if self.collapsed.contains(category) {
+                continue;
+            }
+            for (i, lesson) in self.catalog.lessons().iter().enumerate() {
+                if lesson.category == category {
+                    rows.push(Row::Lesson(i));
+                }
+            }
+        }
+        rows
+    }
+
+    fn open(&mut self, index: usize) {
+        let lesson = &self.catalog.lessons()[index];
+        let src = match self.catalog.read(lesson) {
+            Ok(src) => src,
+            Err(e) => {
+                self.status = format!("{}: {}", lesson.path.display(), e);
+                return;
+            }
+        };
+        let (lines, source_lines) = page_lines(&src);
+        self.page = Some(Page {
+            lesson: index,
+            lines,
+            source_lines,
+            snippets: snippets::runnable(lesson, &src),
+            cursor: 0,
+            top: 0,
+        });
+        self.focus = Focus::Page;
+        self.status.clear();
+    }
+
+    pub fn key(&mut self, key: Key) -> Option<Action> {
+        if key == Key::Ctrl('c') {
+            return Some(Action::Quit);
+        }
+        if let Some(overlay) = &mut self.overlay {
+            let page = self.height.saturating_sub(1).max(1);
+            match key {
+                Key::Esc | Key::Enter | Key::Char('q') => {
+                    self.overlay = None;
+                    return None;
+                }
+                Key::Up | Key::Char('k') => overlay.top = overlay.top.saturating_sub(1),
+                Key::Down | Key::Char('j') => overlay.top += 1,
+                Key::PageUp => overlay.top = overlay.top.saturating_sub(page),
+                Key::PageDown | Key::Char(' ') => overlay.top += page,
+                _ => {}
+            }
+            let max = overlay.lines.len().saturating_sub(page);
+            overlay.top = overlay.top.min(max);
+            return None;
+        }
+
+        self.status.clear();
+        match key {
+            Key::Char('q') => return Some(Action::Quit),
+            Key::Char('?') => self.show("Keys", HELP.split("  ").map(ansi::plain).collect()),
+            Key::Tab | Key::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Tree if self.page.is_some() => Focus::Page,
+                    _ => Focus::Tree,
+                }
+            }
+            Key::Left | Key::Char('h') if self.focus == Focus::Page => self.focus = Focus::Tree,
+            Key::Right | Key::Char('l') if self.focus == Focus::Tree && self.page.is_some() => {
+                self.focus = Focus::Page
+            }
+            Key::Char('e') => {
+                return self.current().map(|l| Action::Exercises(l.id.clone()));
+            }
+            Key::Char('d') => return self.current().map(|l| Action::MarkDone(l.id.clone())),
+            _ if self.focus == Focus::Tree => self.tree_key(key),
+            _ => return self.page_key(key),
+        }
+        None
+    }
+
+    fn tree_key(&mut self, key: Key) {
+        let rows = self.rows();
+        let last = rows.len().saturating_sub(1);
+        match key {
+            Key::Up | Key::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.cursor = (self.cursor + 1).min(last),
+            Key::PageUp => self.cursor = self.cursor.saturating_sub(self.height),
+            Key::PageDown => self.cursor = (self.cursor + self.height).min(last),
+            Key::Home | Key::Char('g') => self.cursor = 0,
+            Key::End | Key::Char('G') => self.cursor = last,
+            Key::Enter | Key::Char(' ') | Key::Right | Key::Char('l') => {
+                match rows.get(self.cursor) {
+                    // Folds or unfolds the category.
+                    Some(Row::Category(name)) if !self.collapsed.remove(name) => {
+                        self.collapsed.insert(name.clone());
+                    }
+                    Some(Row::Lesson(i)) => self.open(*i),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn page_key(&mut self, key: Key) -> Option<Action> {
+        let height = self.height;
+        let page = self.page.as_mut()?;
+        let last = page.lines.len().saturating_sub(1);
+        match key {
+            Key::Up | Key::Char('k') => page.cursor = page.cursor.saturating_sub(1),
+            Key::Down | Key::Char('j') => page.cursor = (page.cursor + 1).min(last),
+            Key::PageUp => page.cursor = page.cursor.saturating_sub(height),
+            Key::PageDown | Key::Char(' ') => page.cursor = (page.cursor + height).min(last),
+            Key::Home | Key::Char('g') => page.cursor = 0,
+            Key::End | Key::Char('G') => page.cursor = last,
+            Key::Char('n') => {
+                let line = page.source_lines.get(page.cursor).copied().unwrap_or(0);
+                let next = page.snippets.iter().find(|s| s.first_line > line);
+                match next.and_then(|s| page.source_lines.iter().position(|&l| l == s.first_line)) {
+                    Some(i) => page.cursor = i,
+                    None => self.status = "no more snippets below".to_string(),
+                }
+            }
+            Key::Char('r') | Key::Enter => {
+                let line = page.source_lines.get(page.cursor).copied().unwrap_or(0);
+                match page.snippets.iter().find(|s| covers(s, line)) {
+                    Some(snippet) => return Some(Action::Run(snippet.clone())),
+                    None => {
+                        self.status =
+                            "no runnable snippet here: lines marked ▶ are (n jumps to the next)"
+                                .to_string()
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    // The whole screen, `columns` by `rows`, starting from the top left.
+    pub fn render(&mut self, columns: usize, rows: usize) -> String {
+        let mut frame = String::from("\x1b[H");
+        if columns < MIN_COLUMNS || rows < MIN_ROWS {
+            let message = format!("make the window at least {}x{}", MIN_COLUMNS, MIN_ROWS);
+            for row in 0..rows {
+                let line = if row == rows / 2 {
+                    ansi::plain(&message)
+                } else {
+                    Vec::new()
+                };
+                frame += &ansi::fit(&line, columns);
+                if row + 1 < rows {
+                    frame += "\r\n";
+                }
+            }
+            return frame;
+        }
+
+        let height = rows - 2;
+        self.height = height;
+        let tree_width = (columns / 3).clamp(18, 36);
+        let page_width = columns - tree_width - 1;
+
+        let title = match self.current() {
+            Some(lesson) => format!(" lessons · {} ({})", lesson.title, lesson.path.display()),
+            None => " lessons".to_string(),
+        };
+        let mut lines = vec![ansi::fit(&[Span::new(Style::Reverse, title)], columns)];
+
+        let tree = self.tree_lines(height, tree_width);
+        let page = self.page_lines(height, page_width);
+        let border = format!("{}│{}", Style::Dim.code(), Style::Plain.code());
+        for (left, right) in tree.iter().zip(&page) {
+            lines.push(format!("{}{}{}", left, border, right));
+        }
+
+        let footer = if self.status.is_empty() {
+            Span::new(Style::Dim, HELP)
+        } else {
+            Span::new(Style::Bold, self.status.clone())
+        };
+        lines.push(ansi::fit(&[footer], columns));
+        frame += &lines.join("\r\n");
+        frame
+    }
+
+    fn tree_lines(&mut self, height: usize, width: usize) -> Vec<String> {
+        let rows = self.rows();
+        self.cursor = self.cursor.min(rows.len().saturating_sub(1));
+        self.top = scroll(self.top, self.cursor, height, rows.len());
+        let lessons = self.catalog.lessons();
+        (self.top..self.top + height)
+            .map(|i| {
+                let Some(row) = rows.get(i) else {
+                    return ansi::fit(&[], width);
+                };
+                let (style, text) = match row {
+                    Row::Category(name) => {
+                        let open = if self.collapsed.contains(name) {
+                            '▸'
+                        } else {
+                            '▾'
+                        };
+                        (Style::Bold, format!("{} {}", open, name))
+                    }
+                    Row::Lesson(j) => {
+                        let lesson = &lessons[*j];
+                        let mark = if self.done.contains(&lesson.id) {
+                            '✓'
+                        } else {
+                            ' '
+                        };
+                        (Style::Plain, format!("  {} {}", mark, lesson.title))
+                    }
+                };
+                let style = match (i == self.cursor, self.focus) {
+                    (true, Focus::Tree) => Style::Reverse,
+                    (true, Focus::Page) => Style::Bold,
+                    _ => style,
+                };
+                ansi::fit(&[Span::new(style, text)], width)
+            })
+            .collect()
+    }
+
+    fn page_lines(&mut self, height: usize, width: usize) -> Vec<String> {
+        if let Some(overlay) = &mut self.overlay {
+            let body = height - 1;
+            overlay.top = overlay.top.min(overlay.lines.len().saturating_sub(body));
+            let mut out = vec![ansi::fit(
+                &[Span::new(Style::Heading, format!(" {} ", overlay.title))],
+                width,
+            )];
+            for i in overlay.top..overlay.top + body {
+                let mut line = vec![Span::new(Style::Plain, " ")];
+                line.extend(overlay.lines.get(i).cloned().unwrap_or_default());
+                out.push(ansi::fit(&line, width));
+            }
+            return out;
+        }
+
+        let Some(page) = &mut self.page else {
+            let mut out = vec![ansi::fit(&[], width); height];
+            let hint = ansi::plain(" Pick a lesson on the left and press Enter.");
+            out[height / 2] = ansi::fit(&hint, width);
+            return out;
+        };
+        page.cursor = page.cursor.min(page.lines.len().saturating_sub(1));
+        page.top = scroll(page.top, page.cursor, height, page.lines.len());
+        (page.top..page.top + height)
+            .map(|i| {
+                let Some(content) = page.lines.get(i) else {
+                    return ansi::fit(&[], width);
+                };
+                let source = page.source_lines[i];
+                let runnable = page.snippets.iter().any(|s| covers(s, source));
+                let mut line = vec![
+                    Span::new(Style::Dim, format!("{:>4} ", source)),
+                    if runnable {
+                        Span::new(Style::Ok, "▶ ")
+                    } else {
+                        Span::new(Style::Plain, "  ")
+                    },
+                ];
+                if i == page.cursor && self.focus == Focus::Page {
+                    line.push(Span::new(Style::Reverse, ansi::text(content)));
+                    // Highlight the whole row, not just the text.
+                    let used = ansi::line_width(&line);
+                    line.push(Span::new(
+                        Style::Reverse,
+                        " ".repeat(width.saturating_sub(used)),
+                    ));
+                } else {
+                    line.extend(content.iter().cloned());
+                }
+                ansi::fit(&line, width)
+            })
+            .collect()
+    }
+}
+
+// The first row to show so that `cursor` is visible, moving as little as
+// possible from `top`.
+fn scroll(top: usize, cursor: usize, height: usize, len: usize) -> usize {
+    let top = if cursor < top {
+        cursor
+    } else if cursor >= top + height {
+        cursor + 1 - height
+    } else {
+        top
+    };
+    // Do not leave empty rows at the bottom after a resize.
+    top.min(len.saturating_sub(height))
+}
+
+fn covers(snippet: &Snippet, line: usize) -> bool {
+    let len = snippet.source.lines().count();
+    line >= snippet.first_line && line < snippet.first_line + len
+}
+
+// A lesson as screen lines, with the notes line each one came from.
+fn page_lines(src: &str) -> (Vec<Line>, Vec<usize>) {
+    let mut lines = Vec::new();
+    let mut source_lines = Vec::new();
+    for block in blocks::split(src) {
+        // The blank lines between blocks.
+        let next = source_lines
+            .last()
+            .map_or(block.first_line, |&last| last + 1);
+        for line in next..block.first_line {
+            lines.push(Vec::new());
+            source_lines.push(line);
+        }
+        let rendered = match block.kind {
+            BlockKind::Prose => block.text.lines().map(prose_line).collect(),
+            BlockKind::Code => highlight::to_lines(&block.text),
+        };
+        for (i, line) in rendered.into_iter().enumerate() {
+            lines.push(line);
+            source_lines.push(block.first_line + i);
+        }
+    }
+    (lines, source_lines)
+}
+
+// Prose without the comment markers it is wrapped in, with inline
+// markdown styled.
+fn prose_line(line: &str) -> Line {
+    let line = ansi::expand_tabs(line);
+    let trimmed = line.trim_start();
+    let text = ["//!", "///", "//", "/*", "*/"]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+        .unwrap_or(trimmed);
+    let text = text.trim_end_matches("*/").trim_end();
+    let text = text.strip_prefix(' ').unwrap_or(text);
+    let (style, text) = match text.strip_prefix('#') {
+        Some(_) => (Style::Heading, text.trim_start_matches('#').trim()),
+        None => (Style::Plain, text),
+    };
+    // The same inline markup as the README: `code`, **bold**, links.
+    markdown::spans(&markdown::inline(text), style).concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::fs;
+
+    const LESSON: &str = "\
+# Ownership
+Each value has one `owner`.
+
+fn main() {
+    let s = String::from(\"hi\");
+    println!(\"{}\", s);
+}
+
+Borrowing comes next.
+struct Point { x: i32 }
+";
+
+    fn scratch(name: &str) -> TempDir {
+        let root = TempDir::new(&format!("browser-{}", name));
+        let dir = root.path();
+        fs::create_dir_all(dir.join("helloRust/src/concepts")).unwrap();
+        fs::create_dir_all(dir.join("helloRust/src/examples")).unwrap();
+        fs::write(dir.join("helloRust/src/concepts/ownership.rs"), LESSON).unwrap();
+        fs::write(
+            dir.join("helloRust/src/examples/hello.rs"),
+            "fn main() {}\n",
+        )
+        .unwrap();
+        root
+    }
+
+    fn strip(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn highlights_code_and_cleans_prose() {
+        let lines = highlight::to_lines("fn main() {\n    println!(\"hi\"); // greet\n}\n");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0][0], Span::new(Style::Keyword, "fn"));
+        let styles: Vec<Style> = lines[1].iter().map(|s| s.style).collect();
+        assert!(styles.contains(&Style::Macro));
+        assert!(styles.contains(&Style::Str));
+        assert_eq!(lines[1].last().unwrap().style, Style::Comment);
+
+        assert_eq!(
+            prose_line("// # Ownership"),
+            [Span::new(Style::Heading, "Ownership")]
+        );
+        assert_eq!(
+            prose_line("/* Each value has one `owner`."),
+            [
+                Span::new(Style::Plain, "Each value has one "),
+                Span::new(Style::Code, "owner"),
+                Span::new(Style::Plain, "."),
+            ]
+        );
+    }
+
+    #[test]
+    fn navigates_opens_and_runs_the_snippet_under_the_cursor() {
+        let root = scratch("keys");
+        let catalog = Catalog::discover(root.path()).unwrap();
+        let mut browser = Browser::new(&catalog, BTreeSet::new());
+        browser.render(80, 24);
+
+        // concepts, ownership, examples, hello
+        browser.key(Key::Down);
+        assert_eq!(browser.current().unwrap().id, "concepts/ownership");
+        browser.key(Key::Enter);
+        assert!(browser.page.is_some());
+
+        // The cursor starts on the heading, which is not runnable.
+        assert!(browser.key(Key::Char('r')).is_none());
+        assert!(browser.status.contains("no runnable snippet"));
+        browser.key(Key::Char('n'));
+        match browser.key(Key::Char('r')) {
+            Some(Action::Run(snippet)) => assert_eq!(snippet.first_line, 4),
+            other => panic!("{:?}", other),
+        }
+        match browser.key(Key::Char('d')) {
+            Some(Action::MarkDone(id)) => browser.mark_done(&id),
+            other => panic!("{:?}", other),
+        }
+
+        // Collapsing a category hides its lessons.
+        browser.key(Key::Tab);
+        browser.key(Key::Home);
+        browser.key(Key::Enter);
+        browser.key(Key::Down);
+        browser.key(Key::Down);
+        assert_eq!(browser.current().unwrap().id, "examples/hello");
+        assert!(matches!(browser.key(Key::Char('q')), Some(Action::Quit)));
+    }
+
+    #[test]
+    fn renders_any_size() {
+        let root = scratch("render");
+        let catalog = Catalog::discover(root.path()).unwrap();
+        let mut browser =
+            Browser::new(&catalog, BTreeSet::from(["concepts/ownership".to_string()]));
+        browser.key(Key::Down);
+        browser.key(Key::Enter);
+
+        for (columns, rows) in [(80, 24), (120, 40), (40, 6), (200, 8)] {
+            let frame = browser.render(columns, rows);
+            let lines: Vec<String> = frame.split("\r\n").map(strip).collect();
+            assert_eq!(lines.len(), rows);
+            for line in &lines {
+                assert_eq!(ansi::width(line), columns, "{:?}", line);
+            }
+        }
+        let frame = strip(&browser.render(80, 24));
+        assert!(frame.contains("✓ ownership"));
+        assert!(frame.contains("   4 ▶ fn main() {"));
+        assert!(frame.contains("Each value has one owner."));
+
+        assert!(strip(&browser.render(30, 5)).contains("make the window at least"));
+    }
+}
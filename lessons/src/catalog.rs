@@ -0,0 +1,164 @@
+// Finds the lesson files in the repository and gives each one a stable id.
+//
+// Ids are `<category>/<slug>`, e.g. `data_structures/rust_hashmap` or
+// `more-advanced-datastructure/string-vs-slices`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::pack;
+
+#[derive(Debug, Clone)]
+pub struct Lesson {
+    pub id: String,
+    pub category: String,
+    pub title: String,
+    // Relative to the catalog root.
+    pub path: PathBuf,
+    // The installed pack it came from, if it is not one of ours.
+    pub pack: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Catalog {
+    root: PathBuf,
+    lessons: Vec<Lesson>,
+}
+
+// The repository root, assuming the tool lives in `<root>/lessons`.
+pub fn default_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+impl Catalog {
+    pub fn discover(root: &Path) -> io::Result<Catalog> {
+        let mut lessons = Vec::new();
+
+        // helloRust/src/<category>/*.rs
+        let hello = root.join("helloRust").join("src");
+        if hello.is_dir() {
+            for dir in sorted_entries(&hello)? {
+                if dir.is_dir() {
+                    let category = file_name(&dir);
+                    collect_dir(root, &dir, &category, &mut lessons)?;
+                }
+            }
+        }
+
+        // more advanced datastructure/*.rs
+        let advanced = root.join("more advanced datastructure");
+        if advanced.is_dir() {
+            collect_dir(
+                root,
+                &advanced,
+                &slugify(&file_name(&advanced)),
+                &mut lessons,
+            )?;
+        }
+
+        // Loose notes at the repository root
+        collect_dir(root, root, "notes", &mut lessons)?;
+
+        // notespush/src/demos/*.rs, one module per demo
+        let demos = root.join("notespush").join("src").join("demos");
+        if demos.is_dir() {
+            collect_dir(root, &demos, "notespush", &mut lessons)?;
+        }
+
+        // packs/<name>/, imported with `lessons pack import`
+        let packs =
+            pack::installed(root).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        for manifest in packs {
+            let dir = pack::packs_dir(Path::new("")).join(&manifest.name);
+            for lesson in manifest.lessons {
+                lessons.push(Lesson {
+                    id: lesson.id,
+                    category: manifest.name.clone(),
+                    title: lesson.title,
+                    path: dir.join(&lesson.path),
+                    pack: Some(manifest.name.clone()),
+                });
+            }
+        }
+
+        lessons.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(Catalog {
+            root: root.to_path_buf(),
+            lessons,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn lessons(&self) -> &[Lesson] {
+        &self.lessons
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Lesson> {
+        self.lessons.iter().find(|l| l.id == id)
+    }
+
+    pub fn read(&self, lesson: &Lesson) -> io::Result<String> {
+        read_lossy(&self.root.join(&lesson.path))
+    }
+}
+
+// Some notes were pasted from other editors and are not valid UTF-8.
+pub fn read_lossy(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn collect_dir(root: &Path, dir: &Path, category: &str, out: &mut Vec<Lesson>) -> io::Result<()> {
+    for path in sorted_entries(dir)? {
+        if !path.is_file() || path.extension().is_none_or(|e| e != "rs") {
+            continue;
+        }
+        let title = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        out.push(Lesson {
+            id: format!("{}/{}", category, slugify(&title)),
+            category: category.to_string(),
+            title,
+            path: path.strip_prefix(root).unwrap_or(&path).to_path_buf(),
+            pack: None,
+        });
+    }
+    Ok(())
+}
+
+fn sorted_entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
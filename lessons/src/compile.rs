@@ -0,0 +1,229 @@
+// Compiles snippets through a content-addressed cache.
+//
+// The key is the SHA-256 of the snippet source, the edition and the full
+// `rustc -vV` output, so a toolchain update invalidates everything. A hit
+// is `<dir>/<key>` (the binary) or `<dir>/<key>.err` (rustc's diagnostics;
+// broken snippets are cached too). Misses are compiled on `jobs` threads.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::sha256::{self, Sha256};
+use crate::snippets::{EDITION, Snippet};
+
+pub struct Cache {
+    dir: PathBuf,
+    rustc_version: String,
+}
+
+#[derive(Debug)]
+pub struct Build {
+    // The binary, or rustc's diagnostics.
+    pub binary: Result<PathBuf, String>,
+    pub hit: bool,
+    // Time spent on this snippet, lookup included.
+    pub time: Duration,
+}
+
+impl Cache {
+    pub fn default_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("target/snippet-cache")
+    }
+
+    pub fn open(dir: &Path) -> io::Result<Cache> {
+        fs::create_dir_all(dir)?;
+        let out = Command::new("rustc").arg("-vV").output()?;
+        if !out.status.success() {
+            return Err(io::Error::other("`rustc -vV` failed"));
+        }
+        Ok(Cache {
+            dir: dir.to_path_buf(),
+            rustc_version: String::from_utf8_lossy(&out.stdout).into_owned(),
+        })
+    }
+
+    pub fn key(&self, source: &str) -> String {
+        let mut h = Sha256::default();
+        for part in [source, EDITION, &self.rustc_version] {
+            // Length-prefixed so the parts cannot run into each other.
+            h.update(&(part.len() as u64).to_le_bytes());
+            h.update(part.as_bytes());
+        }
+        sha256::hex(&h.finish())
+    }
+
+    fn lookup(&self, key: &str) -> Option<Result<PathBuf, String>> {
+        let binary = self.dir.join(key);
+        if binary.is_file() {
+            return Some(Ok(binary));
+        }
+        fs::read_to_string(self.dir.join(format!("{}.err", key)))
+            .ok()
+            .map(Err)
+    }
+
+    fn compile(&self, key: &str, source: &str) -> io::Result<Result<PathBuf, String>> {
+        // Build under a temporary name and rename, so a concurrent or
+        // interrupted run never sees a half-written binary. The name is new
+        // for every attempt: identical snippets share a key, and two threads
+        // may build one at once.
+        static ATTEMPT: AtomicUsize = AtomicUsize::new(0);
+        let tmp = self.dir.join(format!(
+            "tmp-{}-{}-{}",
+            std::process::id(),
+            ATTEMPT.fetch_add(1, Ordering::Relaxed),
+            key
+        ));
+        let src = tmp.with_extension("rs");
+        fs::write(&src, source)?;
+        let out = Command::new("rustc")
+            .args(["--edition", EDITION, "-A", "warnings", "-o"])
+            .arg(&tmp)
+            .arg(&src)
+            .output();
+        let _ = fs::remove_file(&src);
+        let out = out?;
+
+        if out.status.success() {
+            let binary = self.dir.join(key);
+            fs::rename(&tmp, &binary)?;
+            Ok(Ok(binary))
+        } else {
+            let _ = fs::remove_file(&tmp);
+            let diagnostics = String::from_utf8_lossy(&out.stderr).into_owned();
+            // Says nothing about the snippet, so must not be cached as if
+            // it did.
+            if diagnostics.starts_with("error: couldn't read") {
+                return Err(io::Error::other(diagnostics));
+            }
+            fs::write(self.dir.join(format!("{}.err", key)), &diagnostics)?;
+            Ok(Err(diagnostics))
+        }
+    }
+
+    // Builds every snippet, in order, using up to `jobs` rustc processes.
+    pub fn build_all(&self, snippets: &[Snippet], jobs: usize) -> io::Result<Vec<Build>> {
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<io::Result<Build>>>> =
+            Mutex::new(snippets.iter().map(|_| None).collect());
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.max(1).min(snippets.len()) {
+                scope.spawn(|| {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(snippet) = snippets.get(i) else {
+                            break;
+                        };
+                        let build = self.build(snippet);
+                        results.lock().unwrap()[i] = Some(build);
+                    }
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.expect("every snippet was built"))
+            .collect()
+    }
+
+    fn build(&self, snippet: &Snippet) -> io::Result<Build> {
+        let start = Instant::now();
+        let key = self.key(&snippet.source);
+        let (binary, hit) = match self.lookup(&key) {
+            Some(binary) => (binary, true),
+            None => (self.compile(&key, &snippet.source)?, false),
+        };
+        Ok(Build {
+            binary,
+            hit,
+            time: start.elapsed(),
+        })
+    }
+}
+
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn snippet(source: &str) -> Snippet {
+        Snippet {
+            lesson_id: "test/cache".to_string(),
+            block: 1,
+            first_line: 1,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn second_build_is_a_hit() {
+        let dir = TempDir::new("cache");
+        let cache = Cache::open(dir.path()).unwrap();
+        let snippets = [
+            snippet("fn main() { println!(\"cached\"); }\n"),
+            snippet("fn main() { let x: u8 = \"no\"; }\n"),
+        ];
+
+        let first = cache.build_all(&snippets, 2).unwrap();
+        assert!(first.iter().all(|b| !b.hit));
+        assert!(first[0].binary.is_ok());
+        assert!(
+            first[1]
+                .binary
+                .as_ref()
+                .unwrap_err()
+                .contains("mismatched types")
+        );
+
+        let second = cache.build_all(&snippets, 2).unwrap();
+        assert!(second.iter().all(|b| b.hit));
+        assert_eq!(first[0].binary, second[0].binary);
+        assert_eq!(first[1].binary, second[1].binary);
+    }
+
+    // Duplicates share a key, and so are built by several threads at once.
+    #[test]
+    fn identical_snippets_build_side_by_side() {
+        let dir = TempDir::new("cache-duplicates");
+        let cache = Cache::open(dir.path()).unwrap();
+        let snippets = vec![snippet("fn main() {}\n"); 6];
+
+        let builds = cache.build_all(&snippets, 6).unwrap();
+        for build in &builds {
+            assert_eq!(build.binary, builds[0].binary);
+        }
+        assert!(builds[0].binary.is_ok());
+        let files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(files, [cache.key("fn main() {}\n").as_str()]);
+    }
+
+    #[test]
+    fn key_depends_on_source_and_toolchain() {
+        let mut cache = Cache {
+            dir: PathBuf::new(),
+            rustc_version: "rustc 1.0.0".to_string(),
+        };
+        let a = cache.key("fn main() {}");
+        assert_eq!(a, cache.key("fn main() {}"));
+        assert_ne!(a, cache.key("fn main() { }"));
+        cache.rustc_version = "rustc 1.0.1".to_string();
+        assert_ne!(a, cache.key("fn main() {}"));
+    }
+}
@@ -0,0 +1,808 @@
+// Which Rust features each lesson demonstrates.
+//
+// Detection is token based and deliberately conservative: the notes are
+// half prose, so most detectors require the surrounding punctuation that
+// only real code has (`where T:`, `-> impl`, `<'a>` ...).
+
+use crate::lexer::{Code, TokenKind};
+
+pub struct Feature {
+    // Short column header for the matrix.
+    pub code: &'static str,
+    pub name: &'static str,
+    detect: fn(&Code) -> bool,
+}
+
+pub static FEATURES: &[Feature] = &[
+    Feature {
+        code: "lt",
+        name: "lifetimes",
+        detect: lifetimes,
+    },
+    Feature {
+        code: "gen",
+        name: "generics",
+        detect: generics,
+    },
+    Feature {
+        code: "cg",
+        name: "const generics",
+        detect: const_generics,
+    },
+    Feature {
+        code: "whr",
+        name: "where clauses",
+        detect: where_clauses,
+    },
+    Feature {
+        code: "trt",
+        name: "trait definitions",
+        detect: trait_defs,
+    },
+    Feature {
+        code: "imp",
+        name: "trait impls",
+        detect: trait_impls,
+    },
+    Feature {
+        code: "dyn",
+        name: "trait objects (dyn)",
+        detect: trait_objects,
+    },
+    Feature {
+        code: "ita",
+        name: "impl Trait in argument position",
+        detect: impl_trait_arg,
+    },
+    Feature {
+        code: "itr",
+        name: "impl Trait in return position",
+        detect: impl_trait_return,
+    },
+    Feature {
+        code: "st",
+        name: "structs",
+        detect: structs,
+    },
+    Feature {
+        code: "en",
+        name: "enums",
+        detect: enums,
+    },
+    Feature {
+        code: "cl",
+        name: "closures",
+        detect: closures,
+    },
+    Feature {
+        code: "it",
+        name: "iterator adapters",
+        detect: iterator_adapters,
+    },
+    Feature {
+        code: "mac",
+        name: "macro_rules! macros",
+        detect: macro_rules,
+    },
+    Feature {
+        code: "drv",
+        name: "derive attributes",
+        detect: derives,
+    },
+    Feature {
+        code: "mat",
+        name: "match expressions",
+        detect: match_exprs,
+    },
+    Feature {
+        code: "grd",
+        name: "match guards",
+        detect: match_guards,
+    },
+    Feature {
+        code: "at",
+        name: "@ bindings",
+        detect: at_bindings,
+    },
+    Feature {
+        code: "rng",
+        name: "range patterns",
+        detect: range_patterns,
+    },
+    Feature {
+        code: "il",
+        name: "if let",
+        detect: if_let,
+    },
+    Feature {
+        code: "wl",
+        name: "while let",
+        detect: while_let,
+    },
+    Feature {
+        code: "le",
+        name: "let else",
+        detect: let_else,
+    },
+    Feature {
+        code: "ilc",
+        name: "if let chains",
+        detect: if_let_chains,
+    },
+    Feature {
+        code: "qr",
+        name: "? on Result",
+        detect: question_on_result,
+    },
+    Feature {
+        code: "qo",
+        name: "? on Option",
+        detect: question_on_option,
+    },
+    Feature {
+        code: "mut",
+        name: "mutable borrows",
+        detect: mutable_borrows,
+    },
+    Feature {
+        code: "box",
+        name: "Box / Rc / RefCell",
+        detect: smart_pointers,
+    },
+    Feature {
+        code: "thr",
+        name: "threads",
+        detect: threads,
+    },
+    Feature {
+        code: "arc",
+        name: "Arc / Mutex",
+        detect: arc_mutex,
+    },
+    Feature {
+        code: "ch",
+        name: "channels",
+        detect: channels,
+    },
+    Feature {
+        code: "asy",
+        name: "async / await",
+        detect: async_await,
+    },
+    Feature {
+        code: "uns",
+        name: "unsafe",
+        detect: unsafe_code,
+    },
+    Feature {
+        code: "mod",
+        name: "modules",
+        detect: modules,
+    },
+    Feature {
+        code: "tst",
+        name: "unit tests",
+        detect: unit_tests,
+    },
+    Feature {
+        code: "cst",
+        name: "const and static items",
+        detect: const_items,
+    },
+];
+
+pub struct Matrix {
+    // One row per lesson, one bool per entry in `FEATURES`.
+    pub rows: Vec<(String, Vec<bool>)>,
+}
+
+impl Matrix {
+    pub fn new() -> Matrix {
+        Matrix { rows: Vec::new() }
+    }
+
+    pub fn add(&mut self, lesson: &str, src: &str) {
+        self.rows.push((lesson.to_string(), detect(src)));
+    }
+
+    // How many lessons use each feature.
+    pub fn totals(&self) -> Vec<usize> {
+        (0..FEATURES.len())
+            .map(|f| self.rows.iter().filter(|(_, used)| used[f]).count())
+            .collect()
+    }
+
+    // Features that no lesson demonstrates.
+    pub fn gaps(&self) -> Vec<&'static Feature> {
+        FEATURES
+            .iter()
+            .zip(self.totals())
+            .filter(|(_, n)| *n == 0)
+            .map(|(f, _)| f)
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        let width = self
+            .rows
+            .iter()
+            .map(|(id, _)| id.len())
+            .max()
+            .unwrap_or(0)
+            .max(16);
+        let mut out = format!("{:<width$}", "lesson");
+        for f in FEATURES {
+            out += &format!(" {:>3}", f.code);
+        }
+        out.push('\n');
+
+        for (id, used) in &self.rows {
+            out += &format!("{:<width$}", id);
+            for &u in used {
+                out += if u { "   x" } else { "   ." };
+            }
+            out.push('\n');
+        }
+
+        out += &format!("{:<width$}", "lessons using it");
+        for n in self.totals() {
+            out += &format!(" {:>3}", n);
+        }
+        out += "\n\nlegend:\n";
+        for f in FEATURES {
+            out += &format!("  {:<4} {}\n", f.code, f.name);
+        }
+
+        let gaps = self.gaps();
+        if gaps.is_empty() {
+            out += "\nevery feature is demonstrated at least once\n";
+        } else {
+            out += "\nnever demonstrated:\n";
+            for f in gaps {
+                out += &format!("  - {}\n", f.name);
+            }
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("lesson");
+        for f in FEATURES {
+            out += &format!(",{}", f.name);
+        }
+        out.push('\n');
+        for (id, used) in &self.rows {
+            out += id;
+            for &u in used {
+                out += if u { ",1" } else { ",0" };
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Matrix::new()
+    }
+}
+
+pub fn detect(src: &str) -> Vec<bool> {
+    let code = Code::new(src);
+    FEATURES.iter().map(|f| (f.detect)(&code)).collect()
+}
+
+fn any(code: &Code, pred: impl Fn(usize) -> bool) -> bool {
+    (0..code.tokens.len()).any(pred)
+}
+
+fn is_ident(code: &Code, i: usize) -> bool {
+    code.kind(i) == Some(TokenKind::Ident)
+}
+
+// Index of the first token at or after `from` that is one of `stops`,
+// giving up after `limit` tokens.
+fn find_ahead(code: &Code, from: usize, stops: &[&str], limit: usize) -> Option<usize> {
+    (from..code.tokens.len().min(from + limit)).find(|&j| stops.contains(&code.text(j)))
+}
+
+// Index of the brace closing the one opened at `open`.
+fn matching_brace(code: &Code, open: usize) -> usize {
+    let mut depth = 0;
+    for j in open..code.tokens.len() {
+        match code.text(j) {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return j;
+                }
+            }
+            _ => {}
+        }
+    }
+    code.tokens.len()
+}
+
+// `&'a T` and `<'a>`; a bare `'t` is more likely "don't" in the prose, and
+// `'utf8'` is a single-quoted string from another language.
+fn lifetimes(code: &Code) -> bool {
+    any(code, |i| {
+        code.kind(i) == Some(TokenKind::Lifetime)
+            && i > 0
+            && matches!(code.text(i - 1), "&" | "<" | ",")
+            && !code.src[code.tokens[i].end..].starts_with('\'')
+    })
+}
+
+fn generics(code: &Code) -> bool {
+    any(code, |i| {
+        matches!(code.text(i), "fn" | "struct" | "enum" | "trait")
+            && is_ident(code, i + 1)
+            && code.is(i + 2, "<")
+            || code.matches(i, &["impl", "<"])
+    })
+}
+
+fn const_generics(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "const")
+            && i > 0
+            && matches!(code.text(i - 1), "<" | ",")
+            && is_ident(code, i + 1)
+            && code.is(i + 2, ":")
+    })
+}
+
+fn where_clauses(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "where") && is_ident(code, i + 1) && code.is(i + 2, ":")
+    })
+}
+
+fn trait_defs(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "trait") && is_ident(code, i + 1) && matches!(code.text(i + 2), "{" | "<" | ":")
+    })
+}
+
+fn trait_impls(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "impl")
+            && find_ahead(code, i + 1, &["for", "{", ";"], 20).is_some_and(|j| code.is(j, "for"))
+    })
+}
+
+fn trait_objects(code: &Code) -> bool {
+    any(code, |i| code.is(i, "dyn") && is_ident(code, i + 1))
+}
+
+fn impl_trait_arg(code: &Code) -> bool {
+    any(code, |i| {
+        code.matches(i, &[":", "impl"]) && is_ident(code, i + 2)
+    })
+}
+
+fn impl_trait_return(code: &Code) -> bool {
+    any(code, |i| {
+        code.matches(i, &["->", "impl"]) && is_ident(code, i + 2)
+    })
+}
+
+fn structs(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "struct")
+            && is_ident(code, i + 1)
+            && matches!(code.text(i + 2), "{" | "(" | "<" | ";")
+    })
+}
+
+fn enums(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "enum") && is_ident(code, i + 1) && matches!(code.text(i + 2), "{" | "<")
+    })
+}
+
+// `|x| ...` and `|| ...` where an expression can start.
+fn closures(code: &Code) -> bool {
+    any(code, |i| {
+        matches!(code.text(i), "|" | "||")
+            && i > 0
+            && matches!(code.text(i - 1), "(" | "," | "=" | "move" | "return")
+    })
+}
+
+fn iterator_adapters(code: &Code) -> bool {
+    const ADAPTERS: &[&str] = &[
+        "map",
+        "filter",
+        "filter_map",
+        "flat_map",
+        "fold",
+        "zip",
+        "enumerate",
+        "take",
+        "skip",
+        "rev",
+        "chain",
+        "partition",
+        "any",
+        "all",
+    ];
+    any(code, |i| {
+        code.is(i, ".") && ADAPTERS.contains(&code.text(i + 1)) && code.is(i + 2, "(")
+    })
+}
+
+fn macro_rules(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["macro_rules", "!"]))
+}
+
+fn derives(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["#", "[", "derive", "("]))
+}
+
+// `match <expr> {` followed by at least one arm.
+fn match_exprs(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "match")
+            && find_ahead(code, i + 1, &["{", ";"], 40).is_some_and(|open| {
+                code.is(open, "{") && (open..matching_brace(code, open)).any(|j| code.is(j, "=>"))
+            })
+    })
+}
+
+// An `if` that reaches `=>` before any block or statement end.
+fn match_guards(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "if")
+            && find_ahead(code, i + 1, &["=>", "{", ";"], 40).is_some_and(|j| code.is(j, "=>"))
+    })
+}
+
+// `n @ 1..=9 =>`, or a `let` binding; an address like `me@example.com`
+// in the prose reaches neither.
+fn at_bindings(code: &Code) -> bool {
+    any(code, |i| {
+        is_ident(code, i)
+            && code.is(i + 1, "@")
+            && find_ahead(code, i + 2, &["=>", "=", ";", "{", "}"], 20)
+                .is_some_and(|j| matches!(code.text(j), "=>" | "="))
+    })
+}
+
+fn range_patterns(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "..=") && matches!(code.text(i + 2), "=>" | "|")
+    })
+}
+
+fn if_let(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["if", "let"]))
+}
+
+fn while_let(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["while", "let"]))
+}
+
+fn let_else(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "let")
+            && !(i > 0 && matches!(code.text(i - 1), "if" | "while" | "&&"))
+            && find_ahead(code, i + 1, &["else", ";", "{"], 60)
+                .is_some_and(|j| code.is(j, "else") && code.is(j + 1, "{"))
+    })
+}
+
+// An `if` whose condition has both a `let` and an `&&` outside any
+// brackets, in either order: `if let Some(x) = o && x > 0`,
+// `if ready && let Ok(v) = r`. An `&&` in a call or a closure, as in
+// `if let Some(x) = v.find(|a| a.0 && a.1)`, chains nothing.
+fn if_let_chains(code: &Code) -> bool {
+    any(code, |i| {
+        if !code.is(i, "if") {
+            return false;
+        }
+        let (mut depth, mut has_let, mut has_and) = (0, false, false);
+        for j in i + 1..code.tokens.len().min(i + 200) {
+            match code.text(j) {
+                "(" | "[" => depth += 1,
+                "{" if depth > 0 => depth += 1,
+                ")" | "]" | "}" if depth > 0 => depth -= 1,
+                // The body, or the end of something that was not an `if`.
+                "{" | "}" | ")" | "]" | ";" => break,
+                "let" if depth == 0 => has_let = true,
+                "&&" if depth == 0 => has_and = true,
+                _ => {}
+            }
+        }
+        has_let && has_and
+    })
+}
+
+// For each `?`, the return type name of the innermost enclosing fn.
+fn question_return_types<'a>(code: &Code<'a>) -> Vec<&'a str> {
+    // (body open, body close, return type name)
+    let mut bodies = Vec::new();
+    for i in 0..code.tokens.len() {
+        if !(code.is(i, "fn") && is_ident(code, i + 1)) {
+            continue;
+        }
+        let Some(open) = find_ahead(code, i + 2, &["{", ";"], 80) else {
+            continue;
+        };
+        if code.is(open, ";") {
+            continue;
+        }
+        // The name right before the first `<`, e.g. `io::Result<T>` -> Result.
+        let mut ret = "";
+        if let Some(arrow) = (i + 2..open).find(|&j| code.is(j, "->")) {
+            for j in arrow + 1..open {
+                if matches!(code.text(j), "<" | "where") {
+                    break;
+                }
+                if is_ident(code, j) {
+                    ret = code.text(j);
+                }
+            }
+        }
+        bodies.push((open, matching_brace(code, open), ret));
+    }
+
+    (0..code.tokens.len())
+        .filter(|&q| {
+            code.is(q, "?")
+                && q > 0
+                && (is_ident(code, q - 1) || matches!(code.text(q - 1), ")" | "]"))
+        })
+        .filter_map(|q| {
+            bodies
+                .iter()
+                .filter(|(open, close, _)| *open < q && q < *close)
+                .min_by_key(|(open, close, _)| close - open)
+                .map(|(_, _, ret)| *ret)
+        })
+        .collect()
+}
+
+fn question_on_result(code: &Code) -> bool {
+    question_return_types(code).contains(&"Result")
+}
+
+fn question_on_option(code: &Code) -> bool {
+    question_return_types(code).contains(&"Option")
+}
+
+fn mutable_borrows(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["&", "mut"]))
+}
+
+fn smart_pointers(code: &Code) -> bool {
+    any(code, |i| {
+        matches!(code.text(i), "Box" | "Rc" | "RefCell") && matches!(code.text(i + 1), "::" | "<")
+    })
+}
+
+fn threads(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["thread", "::", "spawn"]))
+}
+
+fn arc_mutex(code: &Code) -> bool {
+    any(code, |i| {
+        matches!(code.text(i), "Arc" | "Mutex") && matches!(code.text(i + 1), "::" | "<")
+    })
+}
+
+fn channels(code: &Code) -> bool {
+    any(code, |i| code.matches(i, &["mpsc", "::"]))
+}
+
+fn async_await(code: &Code) -> bool {
+    any(code, |i| {
+        code.matches(i, &["async", "fn"]) || code.matches(i, &[".", "await"])
+    })
+}
+
+fn unsafe_code(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "unsafe") && matches!(code.text(i + 1), "{" | "fn" | "impl")
+    })
+}
+
+fn modules(code: &Code) -> bool {
+    any(code, |i| {
+        code.is(i, "mod") && is_ident(code, i + 1) && matches!(code.text(i + 2), "{" | ";")
+    })
+}
+
+fn unit_tests(code: &Code) -> bool {
+    any(code, |i| {
+        code.matches(i, &["#", "[", "test", "]"])
+            || code.matches(i, &["#", "[", "cfg", "(", "test"])
+    })
+}
+
+fn const_items(code: &Code) -> bool {
+    any(code, |i| {
+        matches!(code.text(i), "const" | "static")
+            && !(i > 0 && matches!(code.text(i - 1), "<" | ","))
+            && is_ident(code, i + 1)
+            && code.is(i + 2, ":")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Whether the detector with column header `code` fires on `src`.
+    fn found(code: &str, src: &str) -> bool {
+        let i = FEATURES
+            .iter()
+            .position(|f| f.code == code)
+            .unwrap_or_else(|| panic!("no feature {}", code));
+        detect(src)[i]
+    }
+
+    // (feature, a snippet that uses it, a near miss that does not)
+    const CASES: &[(&str, &str, &str)] = &[
+        (
+            "lt",
+            "fn f<'a>(x: &'a str) -> &'a str { x }",
+            "we don't use 'em, it's fine",
+        ),
+        ("gen", "fn max<T: Ord>(a: T) {}", "let v = a < b;"),
+        ("cg", "struct Buf<const N: usize>;", "const N: usize = 3;"),
+        (
+            "whr",
+            "fn f<T>(t: T) where T: Clone {}",
+            "where to go: home",
+        ),
+        (
+            "trt",
+            "trait Shape { fn area(&self); }",
+            "the trait is nice",
+        ),
+        (
+            "imp",
+            "impl Display for Point {}",
+            "impl Point { fn new() {} }",
+        ),
+        ("dyn", "let s: Box<dyn Shape> = b;", "a dynamic value"),
+        (
+            "ita",
+            "fn f(x: impl Display) {}",
+            "fn f() -> impl Display {}",
+        ),
+        (
+            "itr",
+            "fn f() -> impl Iterator<Item = u8> {}",
+            "fn f(x: impl Display) {}",
+        ),
+        ("st", "struct Point { x: i32 }", "a struct is a type"),
+        ("en", "enum Color { Red }", "an enum of colors"),
+        ("cl", "let f = |x| x + 1;", "let y = a | b;"),
+        ("it", "v.iter().map(|x| x * 2)", "let map = 1;"),
+        ("mac", "macro_rules! hi { () => {} }", "a macro_rules call"),
+        (
+            "drv",
+            "#[derive(Debug)] struct A;",
+            "#[allow(dead_code)] struct A;",
+        ),
+        (
+            "mat",
+            "match x { 1 => a, _ => b }",
+            "the match is over; nobody won",
+        ),
+        (
+            "grd",
+            "match x { n if n > 0 => a, _ => b }",
+            "if x > 0 { a }",
+        ),
+        (
+            "at",
+            "match x { n @ 1..=9 => n, _ => 0 }",
+            "email me @ home",
+        ),
+        ("rng", "match x { 1..=9 => a, _ => b }", "for i in 1..=9 {}"),
+        ("il", "if let Some(x) = o {}", "if x == 1 {}"),
+        ("ilc", "if ready && let Ok(v) = r {}", "if a && b {}"),
+        ("wl", "while let Some(x) = s.pop() {}", "while x < 3 {}"),
+        (
+            "le",
+            "let Some(x) = o else { return };",
+            "if let Some(x) = o { a } else { b }",
+        ),
+        (
+            "ilc",
+            "if let Some(x) = o && x > 0 {}",
+            "if let Some(x) = o { if x > 0 && y {} }",
+        ),
+        (
+            "qr",
+            "fn f() -> io::Result<u8> { let n = g()?; Ok(n) }",
+            "fn f() -> Option<u8> { let n = g()?; Some(n) }",
+        ),
+        (
+            "qo",
+            "fn f() -> Option<u8> { let n = g()?; Some(n) }",
+            "fn f() -> Result<u8, E> { let n = g()?; Ok(n) } // is it?",
+        ),
+        ("mut", "push(&mut v);", "let mut v = 1;"),
+        ("box", "let b = Box::new(1);", "a box of rocks"),
+        ("thr", "thread::spawn(|| {});", "a thread of thought"),
+        (
+            "arc",
+            "let a = Arc::new(Mutex::new(0));",
+            "a Mutex guards data",
+        ),
+        ("ch", "let (tx, rx) = mpsc::channel();", "an mpsc channel"),
+        ("asy", "async fn get() { f.await; }", "async code is hard"),
+        ("uns", "unsafe { *p }", "unsafe code is hard"),
+        ("mod", "mod shapes;", "the mod is on"),
+        ("tst", "#[test] fn works() {}", "test it well"),
+        ("cst", "const MAX: u32 = 9;", "struct Buf<const N: usize>;"),
+    ];
+
+    #[test]
+    fn every_feature_has_a_case() {
+        for f in FEATURES {
+            assert!(
+                CASES.iter().any(|(code, ..)| *code == f.code),
+                "no case for {}",
+                f.code
+            );
+        }
+    }
+
+    #[test]
+    fn detectors_fire_on_code_and_not_on_near_misses() {
+        for (code, hit, miss) in CASES {
+            assert!(found(code, hit), "{} missed: {}", code, hit);
+            assert!(!found(code, miss), "{} fired on: {}", code, miss);
+        }
+    }
+
+    #[test]
+    fn let_chains_need_an_and_outside_brackets() {
+        for hit in [
+            "if ready && let Ok(v) = r {}",
+            "if let Some(x) = v.iter().find(|a| a.0) && x.ok {}",
+            "if let Some(x) = o\n    && let Some(y) = x.get(&(a, b))\n{}",
+        ] {
+            assert!(found("ilc", hit), "missed: {}", hit);
+        }
+        for miss in [
+            "if let Some(x) = v.iter().find(|a| a.0 && a.1) {}",
+            "if let Some(x) = v.iter().find(|a| { a.0 && a.1 }) {}",
+            "if let Some(x) = map[&(a && b)] {}",
+            "if let Some(x) = o { y } else if a && b {}",
+        ] {
+            assert!(!found("ilc", miss), "fired on: {}", miss);
+        }
+    }
+
+    #[test]
+    fn question_marks_take_the_innermost_fn() {
+        let src = "fn outer() -> Result<(), E> {\n\
+                   fn inner() -> Option<u8> { a()? }\n\
+                   b()?;\n\
+                   Ok(())\n\
+                   }";
+        assert_eq!(question_return_types(&Code::new(src)), ["Option", "Result"]);
+    }
+
+    #[test]
+    fn matrix_reports_totals_and_gaps() {
+        let mut m = Matrix::new();
+        m.add("a", "struct A;");
+        m.add("b", "struct B; enum E { X }");
+        let st = FEATURES.iter().position(|f| f.code == "st").unwrap();
+        assert_eq!(m.totals()[st], 2);
+        let gaps: Vec<&str> = m.gaps().iter().map(|f| f.code).collect();
+        assert!(!gaps.contains(&"st") && !gaps.contains(&"en"));
+        assert!(gaps.contains(&"thr"));
+        assert!(m.to_csv().starts_with("lesson,lifetimes,generics,"));
+    }
+}
@@ -0,0 +1,358 @@
+// CSV as RFC 4180 has it: records of comma-separated fields, one per line,
+// where a field in double quotes may hold commas, line breaks and quotes
+// (doubled). Lines may end in CRLF or a bare LF; `write_record` uses CRLF.
+//
+// `load` and `dump` map whole files to and from types implementing
+// `FromCsv` and `ToCsv`, with a header row naming the columns. Columns are
+// found by name, so a spreadsheet may reorder them or add its own.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    // Both from 1, counted in records and fields as a spreadsheet shows
+    // them: the header is row 1, and a quoted line break does not start a
+    // new row. Column 0 means the row as a whole.
+    pub row: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.column == 0 {
+            write!(f, "row {}: {}", self.row, self.message)
+        } else {
+            write!(
+                f,
+                "row {}, column {}: {}",
+                self.row, self.column, self.message
+            )
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Every record, the header included. A line break at the very end does not
+// start another record.
+pub fn parse(text: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut records = Vec::new();
+    if text.is_empty() {
+        return Ok(records);
+    }
+    let mut chars = text.chars().peekable();
+    let mut record = Vec::new();
+    loop {
+        let error = |message: String| Error {
+            row: records.len() + 1,
+            column: record.len() + 1,
+            message,
+        };
+        let mut field = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(error("quoted field is not closed".to_string())),
+                }
+            }
+            if let Some(c) = chars.next_if(|c| !matches!(c, ',' | '\r' | '\n')) {
+                return Err(error(format!("`{}` after the closing quote", c)));
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !matches!(c, ',' | '\r' | '\n')) {
+                if c == '"' {
+                    return Err(error(
+                        "quote in an unquoted field (quote the field and double the quote)"
+                            .to_string(),
+                    ));
+                }
+                field.push(c);
+            }
+        }
+        record.push(field);
+
+        match chars.next() {
+            Some(',') => continue,
+            Some('\r') => {
+                chars.next_if_eq(&'\n');
+            }
+            _ => {}
+        }
+        records.push(std::mem::take(&mut record));
+        if chars.peek().is_none() {
+            return Ok(records);
+        }
+    }
+}
+
+// `field` as it goes in a file: quoted if it has to be.
+pub fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn write_record<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&quote(field.as_ref()));
+    }
+    out.push_str("\r\n");
+}
+
+pub trait ToCsv {
+    const HEADER: &'static [&'static str];
+
+    // One field per column of `HEADER`, in order.
+    fn to_record(&self) -> Vec<String>;
+}
+
+pub trait FromCsv: Sized {
+    // Columns the header must have; others may be optional.
+    const REQUIRED: &'static [&'static str];
+
+    fn from_record(record: &Record) -> Result<Self, Error>;
+}
+
+// A row of a file with a header, for `FromCsv` impls.
+pub struct Record<'a> {
+    row: usize,
+    header: &'a [String],
+    fields: &'a [String],
+}
+
+impl<'a> Record<'a> {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    // The field in the column named `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        let column = self.header.iter().position(|h| h == name)?;
+        Some(&self.fields[column])
+    }
+
+    // `name` must be in `FromCsv::REQUIRED`, which `load` checks for.
+    pub fn require(&self, name: &str) -> &'a str {
+        self.get(name)
+            .unwrap_or_else(|| panic!("`{}` is not a required column", name))
+    }
+
+    // The field in `name` parsed, or an error saying it is not `what`.
+    pub fn parse<T: FromStr>(&self, name: &str, what: &str) -> Result<T, Error> {
+        let field = self.require(name);
+        field
+            .trim()
+            .parse()
+            .map_err(|_| self.error(name, format!("expected {}, found `{}`", what, field)))
+    }
+
+    // An error at the column named `name`.
+    pub fn error(&self, name: &str, message: impl Into<String>) -> Error {
+        Error {
+            row: self.row,
+            column: self
+                .header
+                .iter()
+                .position(|h| h == name)
+                .map_or(0, |i| i + 1),
+            message: format!("{}: {}", name, message.into()),
+        }
+    }
+}
+
+// A file with a header row, each row after it decoded as a `T`.
+pub fn load<T: FromCsv>(text: &str) -> Result<Vec<T>, Error> {
+    let records = parse(text)?;
+    let Some((header, rows)) = records.split_first() else {
+        return Err(Error {
+            row: 1,
+            column: 0,
+            message: "no header row".to_string(),
+        });
+    };
+    let header_error = |column: usize, message: String| Error {
+        row: 1,
+        column,
+        message,
+    };
+    for (i, name) in header.iter().enumerate() {
+        if header[..i].contains(name) {
+            return Err(header_error(
+                i + 1,
+                format!("`{}` is in the header twice", name),
+            ));
+        }
+    }
+    if let Some(missing) = T::REQUIRED
+        .iter()
+        .find(|name| !header.contains(&name.to_string()))
+    {
+        return Err(header_error(
+            0,
+            format!(
+                "no `{}` column (the header has {})",
+                missing,
+                header.join(", ")
+            ),
+        ));
+    }
+
+    rows.iter()
+        .enumerate()
+        .map(|(i, fields)| {
+            let row = i + 2;
+            if fields.len() != header.len() {
+                return Err(Error {
+                    row,
+                    column: 0,
+                    message: format!(
+                        "expected {} fields, like the header, found {}",
+                        header.len(),
+                        fields.len()
+                    ),
+                });
+            }
+            T::from_record(&Record {
+                row,
+                header,
+                fields,
+            })
+        })
+        .collect()
+}
+
+// The header, then a row per item.
+pub fn dump<T: ToCsv>(items: &[T]) -> String {
+    let mut out = String::new();
+    write_record(&mut out, T::HEADER);
+    for item in items {
+        write_record(&mut out, &item.to_record());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_fields() {
+        let text = "name,note\r\nAlice,\"says \"\"hi\"\", then\r\nleaves\"\nBob,\n\"\",x\n";
+        assert_eq!(
+            parse(text).unwrap(),
+            [
+                vec!["name", "note"],
+                vec!["Alice", "says \"hi\", then\r\nleaves"],
+                vec!["Bob", ""],
+                vec!["", "x"],
+            ]
+        );
+        assert_eq!(parse("a,b").unwrap(), [["a", "b"]]);
+        assert!(parse("").unwrap().is_empty());
+
+        let mut out = String::new();
+        write_record(&mut out, &["plain", "a,b", "say \"hi\"", "two\nlines", ""]);
+        assert_eq!(out, "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\r\n");
+        assert_eq!(parse(&out).unwrap()[0][3], "two\nlines");
+    }
+
+    #[test]
+    fn reports_malformed_input_by_row_and_column() {
+        let error = |text: &str| parse(text).unwrap_err().to_string();
+        assert_eq!(
+            error("a,b\nc,\"d\ne"),
+            "row 2, column 2: quoted field is not closed"
+        );
+        assert_eq!(
+            error("a,\"b\"c"),
+            "row 1, column 2: `c` after the closing quote"
+        );
+        assert_eq!(
+            error("a\nb\nsay \"hi\""),
+            "row 3, column 1: quote in an unquoted field (quote the field and double the quote)"
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+        label: String,
+    }
+
+    impl ToCsv for Point {
+        const HEADER: &'static [&'static str] = &["x", "y", "label"];
+
+        fn to_record(&self) -> Vec<String> {
+            vec![self.x.to_string(), self.y.to_string(), self.label.clone()]
+        }
+    }
+
+    impl FromCsv for Point {
+        const REQUIRED: &'static [&'static str] = &["x", "y"];
+
+        fn from_record(record: &Record) -> Result<Point, Error> {
+            Ok(Point {
+                x: record.parse("x", "a whole number")?,
+                y: record.parse("y", "a whole number")?,
+                label: record.get("label").unwrap_or_default().to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn maps_rows_by_column_name() {
+        let points = vec![
+            Point {
+                x: 1,
+                y: -2,
+                label: "a, b".to_string(),
+            },
+            Point {
+                x: 0,
+                y: 0,
+                label: String::new(),
+            },
+        ];
+        assert_eq!(load::<Point>(&dump(&points)).unwrap(), points);
+
+        let points: Vec<Point> = load("y,extra,x\n5,,4\n").unwrap();
+        assert_eq!(
+            points,
+            [Point {
+                x: 4,
+                y: 5,
+                label: String::new()
+            }]
+        );
+
+        let error = |text: &str| load::<Point>(text).unwrap_err().to_string();
+        assert_eq!(error(""), "row 1: no header row");
+        assert_eq!(
+            error("x,label\n"),
+            "row 1: no `y` column (the header has x, label)"
+        );
+        assert_eq!(
+            error("x,y,x\n"),
+            "row 1, column 3: `x` is in the header twice"
+        );
+        assert_eq!(
+            error("x,y\n1,2\n3\n"),
+            "row 3: expected 2 fields, like the header, found 1"
+        );
+        assert_eq!(
+            error("label,x,y\nok,1,2\n\"a\nb\",3,four\n"),
+            "row 3, column 3: y: expected a whole number, found `four`"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+// Calendar dates and timestamps, in UTC, without a time-zone database;
+// the local time comes from the system's `date` command.
+//
+// Conversions use Howard Hinnant's days-from-civil algorithms.
+
+use std::fmt;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    // Days since 1970-01-01.
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        Date {
+            year: yoe + era * 400 + i64::from(month <= 2),
+            month,
+            day,
+        }
+    }
+
+    pub fn days(self) -> i64 {
+        let y = self.year - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let m = i64::from(self.month);
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    // Today in UTC.
+    pub fn today() -> Date {
+        Date::from_days((unix_now() / 86400) as i64)
+    }
+
+    // Today where the user is, if `local_timestamp` can tell.
+    pub fn local_today() -> Option<Date> {
+        Date::parse(&local_timestamp()?[..10]).ok()
+    }
+
+    // `YYYY-MM-DD`, with the year from 1 to 9999 as `Display` writes it.
+    // Outside that, `days` would overflow long before the year means
+    // anything.
+    pub fn parse(s: &str) -> Result<Date, String> {
+        let mut parts = s.splitn(3, '-');
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) = (
+            parts.next().map(str::parse::<i64>),
+            parts.next().map(str::parse::<u32>),
+            parts.next().map(str::parse::<u32>),
+        ) else {
+            return Err(format!("`{}` is not a date (YYYY-MM-DD)", s));
+        };
+        if !(1..=9999).contains(&year) {
+            return Err(format!("`{}`: the year must be from 1 to 9999", s));
+        }
+        let date = Date { year, month, day };
+        // Rejects 2025-02-30 and friends.
+        if Date::from_days(date.days()) == date {
+            Ok(date)
+        } else {
+            Err(format!("`{}` is not a day of the calendar", s))
+        }
+    }
+
+    pub fn add_days(self, n: i64) -> Date {
+        Date::from_days(self.days() + n)
+    }
+
+    // 0 is Monday.
+    pub fn weekday(self) -> usize {
+        // 1970-01-01 was a Thursday.
+        (self.days() + 3).rem_euclid(7) as usize
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+// `%Y-%m-%d %H:%M:%S` for a Unix time.
+pub fn timestamp(unix: u64) -> String {
+    let secs = unix % 86400;
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        Date::from_days((unix / 86400) as i64),
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+// `%Y-%m-%d %H:%M:%S` in the local time zone, which std cannot tell: like
+// the old push scripts, ask `date` (PowerShell on Windows). None where
+// that fails.
+pub fn local_timestamp() -> Option<String> {
+    let output = if cfg!(windows) {
+        Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-Command",
+                "Get-Date -Format 'yyyy-MM-dd HH:mm:ss'",
+            ])
+            .output()
+    } else {
+        Command::new("date").arg("+%Y-%m-%d %H:%M:%S").output()
+    }
+    .ok()?;
+    let stamp = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && is_timestamp(&stamp)).then_some(stamp)
+}
+
+// `YYYY-MM-DD HH:MM:SS`.
+pub fn is_timestamp(s: &str) -> bool {
+    s.len() == 19
+        && s.char_indices().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            10 => c == ' ',
+            13 | 16 => c == ':',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_weekdays() {
+        for days in [-1000, 0, 11016, 20000, 100000] {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+        let leap = Date::parse("2000-02-29").unwrap();
+        assert_eq!(leap.add_days(1).to_string(), "2000-03-01");
+        assert_eq!(Date::parse("2025-10-20").unwrap().weekday(), 0);
+        assert_eq!(Date::parse("2025-10-26").unwrap().weekday(), 6);
+    }
+
+    #[test]
+    fn parse_rejects_what_is_not_a_date() {
+        let error = |s: &str| Date::parse(s).unwrap_err();
+        assert_eq!(
+            error("2025-02-29"),
+            "`2025-02-29` is not a day of the calendar"
+        );
+        assert_eq!(
+            error("2025-13-01"),
+            "`2025-13-01` is not a day of the calendar"
+        );
+        assert_eq!(error("2025-10"), "`2025-10` is not a date (YYYY-MM-DD)");
+        assert_eq!(error("2025-x-01"), "`2025-x-01` is not a date (YYYY-MM-DD)");
+        assert_eq!(
+            error("0-01-01"),
+            "`0-01-01`: the year must be from 1 to 9999"
+        );
+        assert_eq!(
+            error("9223372036854775807-01-01"),
+            "`9223372036854775807-01-01`: the year must be from 1 to 9999"
+        );
+        assert_eq!(Date::parse("0001-01-01").unwrap().days(), -719162);
+        assert_eq!(Date::parse("9999-12-31").unwrap().to_string(), "9999-12-31");
+    }
+
+    #[test]
+    fn formats_timestamps_like_date() {
+        assert_eq!(timestamp(0), "1970-01-01 00:00:00");
+        // date -u -d @951782400 '+%Y-%m-%d %H:%M:%S' (a leap day)
+        assert_eq!(timestamp(951782400), "2000-02-29 00:00:00");
+        assert_eq!(timestamp(1760000000), "2025-10-09 08:53:20");
+    }
+
+    #[test]
+    fn reads_the_local_time_from_date() {
+        assert!(!is_timestamp("2025-09-02T10:00:00"));
+        assert!(!is_timestamp("2025-09-02 10:00"));
+        assert!(is_timestamp("2025-09-02 10:00:00"));
+        #[cfg(unix)]
+        {
+            assert!(local_timestamp().is_some_and(|s| is_timestamp(&s)));
+            assert!(Date::local_today().is_some());
+        }
+    }
+}
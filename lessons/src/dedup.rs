@@ -0,0 +1,434 @@
+// Near-duplicate passages across the notes, found by shingling and
+// MinHash.
+//
+// Each file is cut into overlapping windows of non-blank lines. A window's
+// words become 4-word shingles, and the shingles become a MinHash
+// signature. Windows whose signatures land in the same LSH bucket are
+// compared. Runs of matching windows are merged into passages, and each
+// passage is scored by the exact Jaccard similarity of its shingles.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SHINGLE: usize = 4;
+const WINDOW: usize = 8;
+const STEP: usize = 4;
+const HASHES: usize = 64;
+const BANDS: usize = 16;
+const ROWS: usize = HASHES / BANDS;
+// Windows with fewer shingles than this are mostly braces and boilerplate.
+const MIN_SHINGLES: usize = 16;
+
+pub struct Document {
+    pub name: String,
+    pub text: String,
+    // False for copies outside the catalog, such as files under `target/`.
+    pub lesson: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passage {
+    pub doc: usize,
+    // 1-based, inclusive.
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub similarity: f64,
+    pub keep: Passage,
+    pub drop: Passage,
+    pub reason: &'static str,
+}
+
+struct Window {
+    doc: usize,
+    first_line: usize,
+    last_line: usize,
+    signature: [u64; HASHES],
+}
+
+// Every passage that appears, near enough, in two places.
+pub fn find(docs: &[Document], threshold: f64) -> Vec<Duplicate> {
+    let lines: Vec<Vec<(usize, Vec<String>)>> = docs.iter().map(|d| word_lines(&d.text)).collect();
+    let mut windows = Vec::new();
+    for (doc, lines) in lines.iter().enumerate() {
+        let mut start = 0;
+        loop {
+            let end = (start + WINDOW).min(lines.len());
+            let chunk = &lines[start..end];
+            let shingles = shingles(chunk);
+            if shingles.len() >= MIN_SHINGLES {
+                windows.push(Window {
+                    doc,
+                    first_line: chunk[0].0,
+                    last_line: chunk[chunk.len() - 1].0,
+                    signature: signature(&shingles),
+                });
+            }
+            if end == lines.len() {
+                break;
+            }
+            start += STEP;
+        }
+    }
+
+    // Locality-sensitive hashing: windows agreeing on a whole band of the
+    // signature are candidates, which skips comparing every pair.
+    let mut buckets: HashMap<(usize, &[u64]), Vec<usize>> = HashMap::new();
+    for (i, window) in windows.iter().enumerate() {
+        for band in 0..BANDS {
+            let rows = &window.signature[band * ROWS..(band + 1) * ROWS];
+            buckets.entry((band, rows)).or_default().push(i);
+        }
+    }
+    let mut pairs = BTreeSet::new();
+    for members in buckets.values() {
+        for (n, &a) in members.iter().enumerate() {
+            for &b in &members[n + 1..] {
+                let (wa, wb) = (&windows[a], &windows[b]);
+                let overlapping = wa.doc == wb.doc
+                    && wa.first_line <= wb.last_line
+                    && wb.first_line <= wa.last_line;
+                if !overlapping && estimate(&wa.signature, &wb.signature) >= threshold {
+                    pairs.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+    }
+
+    // Consecutive windows matching consecutive windows are one passage.
+    // Windows are numbered in file order, so a run moves by one on both
+    // sides.
+    let mut runs: Vec<Vec<(usize, usize)>> = Vec::new();
+    for &(a, b) in &pairs {
+        let extends = |run: &Vec<(usize, usize)>| {
+            let &(la, lb) = run.last().unwrap();
+            la + 1 == a
+                && windows[la].doc == windows[a].doc
+                && windows[lb].doc == windows[b].doc
+                && lb.abs_diff(b) <= 1
+        };
+        match runs.iter_mut().find(|run| extends(run)) {
+            Some(run) => run.push((a, b)),
+            None => runs.push(vec![(a, b)]),
+        }
+    }
+
+    let mut spans: Vec<(Passage, Passage)> = runs
+        .iter()
+        .map(|run| {
+            let (first_a, first_b) = run[0];
+            let (last_a, last_b) = run[run.len() - 1];
+            let span = |first: usize, last: usize| Passage {
+                doc: windows[first].doc,
+                first_line: windows[first].first_line.min(windows[last].first_line),
+                last_line: windows[first].last_line.max(windows[last].last_line),
+            };
+            (
+                span(first_a, last_a),
+                span(first_b.min(last_b), first_b.max(last_b)),
+            )
+        })
+        .collect();
+    spans.sort_by_key(|(a, b)| (a.doc, b.doc, a.first_line));
+
+    // A window that falls short of the threshold, say over an edited line,
+    // splits one copy into several runs. Runs a few lines apart at the same
+    // offset between the two files are joined back together.
+    let mut merged: Vec<(Passage, Passage)> = Vec::new();
+    for (a, b) in spans {
+        let offset = |a: &Passage, b: &Passage| b.first_line as isize - a.first_line as isize;
+        match merged.last_mut() {
+            Some((ma, mb))
+                if ma.doc == a.doc
+                    && mb.doc == b.doc
+                    && a.first_line <= ma.last_line + 2 * WINDOW
+                    && offset(ma, mb).abs_diff(offset(&a, &b)) <= WINDOW =>
+            {
+                ma.last_line = ma.last_line.max(a.last_line);
+                mb.first_line = mb.first_line.min(b.first_line);
+                mb.last_line = mb.last_line.max(b.last_line);
+            }
+            _ => merged.push((a, b)),
+        }
+    }
+
+    let mut found: Vec<Duplicate> = Vec::new();
+    for (a, b) in merged {
+        let (a, b) = (trim(&lines, &a, &b), trim(&lines, &b, &a));
+        if a.doc == b.doc && a.first_line <= b.last_line && b.first_line <= a.last_line {
+            continue;
+        }
+        let similarity = jaccard(
+            &shingles_in(&lines[a.doc], &a),
+            &shingles_in(&lines[b.doc], &b),
+        );
+        if similarity < threshold {
+            continue;
+        }
+        let (keep, drop, reason) = choose(docs, &lines, a, b);
+        found.push(Duplicate {
+            similarity,
+            keep,
+            drop,
+            reason,
+        });
+    }
+    found.sort_by(|x, y| {
+        y.similarity
+            .total_cmp(&x.similarity)
+            .then((x.keep.doc, x.keep.first_line).cmp(&(y.keep.doc, y.keep.first_line)))
+    });
+    found
+}
+
+// `passage` without the lines at either end that are not in `other`:
+// windows are cut on a fixed grid, so a run usually starts and ends with
+// a few lines of whatever surrounds the copy.
+fn trim(lines: &[Vec<(usize, Vec<String>)>], passage: &Passage, other: &Passage) -> Passage {
+    let inside = |p: &Passage| -> Vec<(usize, String)> {
+        lines[p.doc]
+            .iter()
+            .filter(|(n, _)| (p.first_line..=p.last_line).contains(n))
+            .map(|(n, words)| (*n, words.join(" ")))
+            .collect()
+    };
+    let theirs: BTreeSet<String> = inside(other).into_iter().map(|(_, text)| text).collect();
+    let ours = inside(passage);
+    let shared: Vec<usize> = ours
+        .iter()
+        .filter(|(_, text)| theirs.contains(text))
+        .map(|(n, _)| *n)
+        .collect();
+    match (shared.first(), shared.last()) {
+        (Some(&first_line), Some(&last_line)) => Passage {
+            doc: passage.doc,
+            first_line,
+            last_line,
+        },
+        _ => passage.clone(),
+    }
+}
+
+// Which copy to keep, and why: the one in the catalog over a stray copy,
+// the earlier one within a file, and otherwise the one that makes up more
+// of its file, since that file is about this material.
+fn choose(
+    docs: &[Document],
+    lines: &[Vec<(usize, Vec<String>)>],
+    a: Passage,
+    b: Passage,
+) -> (Passage, Passage, &'static str) {
+    let share = |c: &Passage| {
+        let inside = lines[c.doc]
+            .iter()
+            .filter(|(n, _)| (c.first_line..=c.last_line).contains(n))
+            .count();
+        inside as f64 / lines[c.doc].len().max(1) as f64
+    };
+    if docs[a.doc].lesson != docs[b.doc].lesson {
+        let (keep, drop) = if docs[a.doc].lesson { (a, b) } else { (b, a) };
+        (keep, drop, "the other copy is outside the lessons")
+    } else if a.doc == b.doc {
+        let (keep, drop) = if a.first_line < b.first_line {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        (keep, drop, "repeated later in the same file")
+    } else if share(&b) > share(&a) {
+        (b, a, "more of that file is about this")
+    } else {
+        (a, b, "more of that file is about this")
+    }
+}
+
+// Non-blank lines as (1-based line number, lowercase words).
+fn word_lines(text: &str) -> Vec<(usize, Vec<String>)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let words = line
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|w| !w.is_empty())
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+            (i + 1, words)
+        })
+        .filter(|(_, words)| !words.is_empty())
+        .collect()
+}
+
+fn shingles(lines: &[(usize, Vec<String>)]) -> BTreeSet<u64> {
+    let words: Vec<&str> = lines
+        .iter()
+        .flat_map(|(_, words)| words.iter().map(String::as_str))
+        .collect();
+    words.windows(SHINGLE).map(|w| fnv(&w.join(" "))).collect()
+}
+
+fn shingles_in(lines: &[(usize, Vec<String>)], passage: &Passage) -> BTreeSet<u64> {
+    let inside: Vec<(usize, Vec<String>)> = lines
+        .iter()
+        .filter(|(n, _)| (passage.first_line..=passage.last_line).contains(n))
+        .cloned()
+        .collect();
+    shingles(&inside)
+}
+
+fn signature(shingles: &BTreeSet<u64>) -> [u64; HASHES] {
+    let mut sig = [u64::MAX; HASHES];
+    for &shingle in shingles {
+        for (i, min) in sig.iter_mut().enumerate() {
+            *min = (*min).min(mix(shingle ^ (i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)));
+        }
+    }
+    sig
+}
+
+// Fraction of signature slots that agree, which estimates the Jaccard
+// similarity of the shingle sets.
+fn estimate(a: &[u64; HASHES], b: &[u64; HASHES]) -> f64 {
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / HASHES as f64
+}
+
+fn jaccard(a: &BTreeSet<u64>, b: &BTreeSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn fnv(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// The splitmix64 finaliser: a cheap, well-mixed hash family when seeded
+// with a different constant per slot.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+// `.rs` files under `root` that are not the tooling's own, including
+// stray copies the catalog does not know about.
+pub fn sources(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default();
+            if path.is_dir() {
+                if name != ".git" && path != root.join("lessons") {
+                    dirs.push(path);
+                }
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                out.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TUTORIAL: &str = "\
+A HashMap is a collection of key value pairs and is great for lookups.
+To use HashMap you must import it from the standard library first.
+use std::collections::HashMap;
+let mut capital_cities = HashMap::new();
+capital_cities.insert(\"England\", \"London\");
+capital_cities.insert(\"Germany\", \"Berlin\");
+capital_cities.insert(\"Norway\", \"Oslo\");
+if let Some(city) = capital_cities.get(\"England\") { println!(\"{}\", city); }
+Inserting with a key that already exists replaces the old value with the new one.
+To remove a key from a HashMap use the remove method and pass the key.
+";
+
+    fn doc(name: &str, text: String, lesson: bool) -> Document {
+        Document {
+            name: name.to_string(),
+            text,
+            lesson,
+        }
+    }
+
+    fn filler(topic: &str, lines: usize) -> String {
+        (0..lines)
+            .map(|i| {
+                format!(
+                    "{} note {} covers {} case number {} in detail\n",
+                    topic,
+                    i,
+                    topic,
+                    i * 7
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_a_copied_passage_and_keeps_the_home_copy() {
+        let errors = format!(
+            "{}\n{}{}",
+            filler("errors", 12),
+            TUTORIAL,
+            filler("results", 12)
+        );
+        // A copy with an edited line still matches.
+        let edited = TUTORIAL.replace("\"Germany\", \"Berlin\"", "\"France\", \"Paris\"");
+        let docs = [
+            doc("errorhandling.rs", errors, true),
+            doc(
+                "rust_hashmap.rs",
+                format!("{}{}", edited, filler("maps", 3)),
+                true,
+            ),
+            doc("iterator.rs", filler("iterators", 30), true),
+        ];
+        let found = find(&docs, 0.5);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        let dup = &found[0];
+        assert!(dup.similarity > 0.7 && dup.similarity < 1.0);
+        assert_eq!(dup.keep.doc, 1);
+        assert_eq!(dup.drop.doc, 0);
+        assert_eq!((dup.keep.first_line, dup.keep.last_line), (1, 10));
+        // After twelve lines of filler and a blank line.
+        assert_eq!((dup.drop.first_line, dup.drop.last_line), (14, 23));
+    }
+
+    #[test]
+    fn prefers_lessons_and_the_first_copy_in_a_file() {
+        let twice = format!("{}{}{}", TUTORIAL, filler("lifetimes", 10), TUTORIAL);
+        let found = find(&[doc("lifetime.rs", twice, true)], 0.8);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].keep.first_line, 1);
+        assert!(found[0].drop.first_line > 10);
+        assert_eq!(found[0].reason, "repeated later in the same file");
+
+        let docs = [
+            doc("target/debug/copy.rs", TUTORIAL.to_string(), false),
+            doc(
+                "data_structures/rust_hashmap.rs",
+                TUTORIAL.to_string(),
+                true,
+            ),
+        ];
+        let found = find(&docs, 0.8);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].keep.doc, found[0].drop.doc), (1, 0));
+        assert_eq!(found[0].similarity, 1.0);
+    }
+}
@@ -0,0 +1,299 @@
+// Exercises and the checker that runs them.
+//
+// Our own exercises are listed in `lessons/exercises/exercises.toml`. A
+// Rustlings checkout (v5 layout: `info.toml` with `[[exercises]]` entries
+// and files under `exercises/`) can be loaded the same way, so both kinds
+// run through one checker and land in one progress store.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::sandbox::{self, Limits};
+use crate::snippets::EDITION;
+use crate::toml;
+
+// Rustlings' convention: the learner deletes this line when done.
+pub const NOT_DONE_MARKER: &str = "I AM NOT DONE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // Must compile and run without panicking.
+    Compile,
+    // Must pass its `#[test]`s.
+    Test,
+    // Rustlings' clippy mode. We have no clippy for single files, so it is
+    // checked like `Compile`.
+    Clippy,
+}
+
+impl Mode {
+    fn parse(s: &str) -> Option<Mode> {
+        match s {
+            "compile" => Some(Mode::Compile),
+            "test" => Some(Mode::Test),
+            "clippy" => Some(Mode::Clippy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mode::Compile => "compile",
+            Mode::Test => "test",
+            Mode::Clippy => "clippy",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Exercise {
+    pub name: String,
+    // Relative to the set's directory.
+    pub path: PathBuf,
+    pub mode: Mode,
+    pub hint: String,
+    // Catalog id of the lesson the exercise practises (ours only).
+    pub lesson: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ExerciseSet {
+    // "ours" or "rustlings"; used as the progress key prefix.
+    pub source: &'static str,
+    pub dir: PathBuf,
+    pub exercises: Vec<Exercise>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    // Works, but still has the "I AM NOT DONE" marker.
+    NotDone,
+    CompileError(String),
+    Failed(String),
+}
+
+impl Outcome {
+    // The status stored in the progress file.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "passed",
+            Outcome::NotDone => "not-done",
+            Outcome::CompileError(_) => "compile-error",
+            Outcome::Failed(_) => "failed",
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "passed"),
+            Outcome::NotDone => write!(f, "works, remove the `{}` line to finish", NOT_DONE_MARKER),
+            Outcome::CompileError(out) => write!(f, "does not compile\n{}", out.trim_end()),
+            Outcome::Failed(out) => write!(f, "failed\n{}", out.trim_end()),
+        }
+    }
+}
+
+pub fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("exercises")
+}
+
+impl ExerciseSet {
+    pub fn progress_key(&self, exercise: &Exercise) -> String {
+        format!("exercise:{}/{}", self.source, exercise.name)
+    }
+
+    // Our own `exercises.toml`.
+    pub fn load_ours(dir: &Path) -> Result<ExerciseSet, String> {
+        let exercises = load_entries(&dir.join("exercises.toml"), "exercise")?;
+        Ok(ExerciseSet {
+            source: "ours",
+            dir: dir.to_path_buf(),
+            exercises,
+        })
+    }
+
+    // A Rustlings checkout with `info.toml` at its root.
+    pub fn load_rustlings(dir: &Path) -> Result<ExerciseSet, String> {
+        let exercises = load_entries(&dir.join("info.toml"), "exercises")?;
+        Ok(ExerciseSet {
+            source: "rustlings",
+            dir: dir.to_path_buf(),
+            exercises,
+        })
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Exercise> {
+        self.exercises.iter().find(|e| e.name == name)
+    }
+
+    // Writes the set in Rustlings layout: `<out>/info.toml` plus the files
+    // under `<out>/exercises/`.
+    pub fn export_rustlings(&self, out: &Path) -> io::Result<()> {
+        let mut info = String::from("# Exported from the Rust-1-to-100 notes.\n");
+        for ex in &self.exercises {
+            let rel = Path::new("exercises").join(&ex.path);
+            let dest = out.join(&rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(self.dir.join(&ex.path), &dest)?;
+
+            let mut hint = ex.hint.trim_end().to_string();
+            if let Some(lesson) = &ex.lesson {
+                hint += &format!("\n\nRelated lesson: {}", lesson);
+            }
+            info += &format!(
+                "\n[[exercises]]\nname = {}\npath = {}\nmode = {}\nhint = {}\n",
+                toml::quote(&ex.name),
+                toml::quote(&rel.to_string_lossy().replace('\\', "/")),
+                toml::quote(ex.mode.as_str()),
+                toml::quote(&format!("{}\n", hint)),
+            );
+        }
+        fs::create_dir_all(out)?;
+        fs::write(out.join("info.toml"), info)
+    }
+}
+
+fn load_entries(file: &Path, array: &str) -> Result<Vec<Exercise>, String> {
+    let text = fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let doc = toml::parse(&text).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let entries = doc
+        .get(array)
+        .and_then(|v| v.as_array())
+        .unwrap_or_default();
+
+    let mut exercises = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let table = entry
+            .as_table()
+            .ok_or_else(|| format!("{}: entry {} is not a table", file.display(), i + 1))?;
+        let field = |key: &str| table.get(key).and_then(|v| v.as_str());
+        let entry_err =
+            |msg: &str| format!("{}: [[{}]] #{}: {}", file.display(), array, i + 1, msg);
+
+        let name = field("name").ok_or_else(|| entry_err("missing `name`"))?;
+        let path = field("path").ok_or_else(|| entry_err("missing `path`"))?;
+        let mode = field("mode").ok_or_else(|| entry_err("missing `mode`"))?;
+        let mode =
+            Mode::parse(mode).ok_or_else(|| entry_err(&format!("unknown mode `{}`", mode)))?;
+        exercises.push(Exercise {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            mode,
+            hint: field("hint").unwrap_or_default().to_string(),
+            lesson: field("lesson").map(str::to_string),
+        });
+    }
+    Ok(exercises)
+}
+
+// Compiles the exercise with rustc into `work` and runs it in the sandbox.
+pub fn check(set: &ExerciseSet, exercise: &Exercise, work: &Path) -> io::Result<Outcome> {
+    let source = set.dir.join(&exercise.path);
+    let text = fs::read_to_string(&source)?;
+    fs::create_dir_all(work)?;
+    let binary = work.join(format!("{}-{}", set.source, exercise.name));
+
+    let mut rustc = Command::new("rustc");
+    rustc.arg("--edition").arg(EDITION).arg("-o").arg(&binary);
+    if exercise.mode == Mode::Test {
+        rustc.arg("--test");
+    }
+    let compiled = rustc.arg(&source).output()?;
+    if !compiled.status.success() {
+        return Ok(Outcome::CompileError(
+            String::from_utf8_lossy(&compiled.stderr).into_owned(),
+        ));
+    }
+
+    let run = sandbox::run(&binary, &Limits::default())?;
+    if run.outcome != sandbox::Outcome::Success {
+        return Ok(Outcome::Failed(format!(
+            "{}{}({})",
+            run.stdout, run.stderr, run.outcome
+        )));
+    }
+
+    if text.lines().any(|l| l.contains(NOT_DONE_MARKER)) {
+        Ok(Outcome::NotDone)
+    } else {
+        Ok(Outcome::Passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const INFO: &str = r#"
+# INFO ABOUT EXERCISES
+
+[[exercises]]
+name = "intro1"
+path = "exercises/intro/intro1.rs"
+mode = "compile"
+hint = """
+Remove the I AM NOT DONE comment in the exercises/intro/intro1.rs file
+to move on to the next exercise."""
+
+[[exercises]]
+name = "tests1"
+path = "exercises/tests/tests1.rs"
+mode = "test"
+hint = "You don't even need to write any code to test -- you can just test values."
+"#;
+
+    #[test]
+    fn reads_rustlings_info_toml() {
+        let tmp = TempDir::new("rustlings-info");
+        let dir = tmp.path();
+        fs::write(dir.join("info.toml"), INFO).unwrap();
+        let set = ExerciseSet::load_rustlings(dir).unwrap();
+
+        assert_eq!(set.exercises.len(), 2);
+        let intro = set.find("intro1").unwrap();
+        assert_eq!(intro.mode, Mode::Compile);
+        assert_eq!(intro.path, Path::new("exercises/intro/intro1.rs"));
+        assert!(intro.hint.starts_with("Remove the I AM NOT DONE"));
+        assert_eq!(set.find("tests1").unwrap().mode, Mode::Test);
+    }
+
+    #[test]
+    fn rejects_unknown_modes() {
+        let tmp = TempDir::new("rustlings-mode");
+        let dir = tmp.path();
+        fs::write(
+            dir.join("info.toml"),
+            "[[exercises]]\nname = \"x\"\npath = \"x.rs\"\nmode = \"run\"\n",
+        )
+        .unwrap();
+        let err = ExerciseSet::load_rustlings(dir).unwrap_err();
+        assert!(err.contains("unknown mode `run`"), "{}", err);
+    }
+
+    #[test]
+    fn our_exercises_round_trip_through_rustlings_layout() {
+        let ours = ExerciseSet::load_ours(&default_dir()).unwrap();
+        let tmp = TempDir::new("rustlings-export");
+        let out = tmp.path();
+        ours.export_rustlings(out).unwrap();
+        let back = ExerciseSet::load_rustlings(out).unwrap();
+
+        assert_eq!(back.exercises.len(), ours.exercises.len());
+        for (a, b) in ours.exercises.iter().zip(&back.exercises) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.mode, b.mode);
+            assert_eq!(Path::new("exercises").join(&a.path), b.path);
+            assert!(back.dir.join(&b.path).is_file());
+            assert!(b.hint.starts_with(a.hint.trim_end()));
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Recorded ("golden") stdout for code blocks in the notes.
+//
+// Stored as `lessons/golden/<lesson id>/<n>.stdout`, where `n` is the
+// 1-based position of the code block within the lesson.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct Golden {
+    dir: PathBuf,
+}
+
+impl Golden {
+    pub fn new(dir: &Path) -> Golden {
+        Golden {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    pub fn default_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("golden")
+    }
+
+    pub fn path(&self, lesson_id: &str, block: usize) -> PathBuf {
+        self.dir.join(lesson_id).join(format!("{}.stdout", block))
+    }
+
+    pub fn stdout(&self, lesson_id: &str, block: usize) -> Option<String> {
+        fs::read_to_string(self.path(lesson_id, block)).ok()
+    }
+}
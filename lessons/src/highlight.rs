@@ -0,0 +1,231 @@
+// Syntax highlighting for Rust code in the notes, from the lexer's tokens:
+// styled lines for the terminal and `<span>`s for HTML.
+//
+// Like the lexer this never fails. Code it cannot make sense of comes out
+// plain, and every byte of the input comes out exactly once.
+
+use crate::ansi::{self, Line, Span, Style};
+use crate::lexer::{self, TokenKind};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+    "u128", "usize", "f32", "f64",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Plain,
+    Keyword,
+    Type,
+    Str,
+    Number,
+    Comment,
+    Macro,
+    Attribute,
+    Lifetime,
+}
+
+impl Class {
+    pub fn style(self) -> Style {
+        match self {
+            Class::Plain => Style::Plain,
+            Class::Keyword | Class::Lifetime => Style::Keyword,
+            Class::Type => Style::Type,
+            Class::Str => Style::Str,
+            Class::Number => Style::Number,
+            Class::Comment => Style::Comment,
+            Class::Macro => Style::Macro,
+            Class::Attribute => Style::Attribute,
+        }
+    }
+
+    // The CSS class of the `<span>`; plain text gets no span.
+    pub fn css(self) -> &'static str {
+        match self {
+            Class::Plain => "",
+            Class::Keyword => "kw",
+            Class::Type => "ty",
+            Class::Str => "str",
+            Class::Number => "num",
+            Class::Comment => "com",
+            Class::Macro => "mac",
+            Class::Attribute => "attr",
+            Class::Lifetime => "life",
+        }
+    }
+}
+
+// `src` cut into classified pieces which, joined, give back `src`.
+// Neighbouring pieces of the same class are merged.
+pub fn classify(src: &str) -> Vec<(Class, &str)> {
+    let tokens = lexer::tokenize(src);
+    let text = |i: usize| tokens.get(i).map_or("", |t| t.text(src));
+    let mut pieces: Vec<(Class, usize, usize)> = Vec::new();
+    let mut push = |class: Class, start: usize, end: usize| match pieces.last_mut() {
+        _ if start == end => {}
+        Some((last, _, last_end)) if *last == class => *last_end = end,
+        _ => pieces.push((class, start, end)),
+    };
+
+    let mut pos = 0;
+    // Open brackets of the attribute we are in, if any.
+    let mut attribute: Option<usize> = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let class = if attribute.is_some() {
+            Class::Attribute
+        } else {
+            Class::Plain
+        };
+        push(class, pos, token.start);
+        let mut end = token.end;
+        let class = if let Some(depth) = attribute.as_mut() {
+            match token.text(src) {
+                "[" => *depth += 1,
+                "]" => *depth -= 1,
+                _ => {}
+            }
+            if *depth == 0 {
+                attribute = None;
+            }
+            Class::Attribute
+        } else {
+            match token.kind {
+                TokenKind::Punct
+                    if token.text(src) == "#"
+                        && (text(i + 1) == "[" || text(i + 1) == "!" && text(i + 2) == "[") =>
+                {
+                    attribute = Some(0);
+                    Class::Attribute
+                }
+                TokenKind::Ident if KEYWORDS.contains(&token.text(src)) => Class::Keyword,
+                TokenKind::Ident if text(i + 1) == "!" && tokens[i + 1].start == token.end => {
+                    // The `!` is part of the macro's name.
+                    end = tokens[i + 1].end;
+                    i += 1;
+                    Class::Macro
+                }
+                TokenKind::Ident
+                    if PRIMITIVES.contains(&token.text(src))
+                        || token
+                            .text(src)
+                            .starts_with(|c: char| c.is_ascii_uppercase()) =>
+                {
+                    Class::Type
+                }
+                TokenKind::Lifetime => Class::Lifetime,
+                TokenKind::Str | TokenKind::Char => Class::Str,
+                TokenKind::Number => Class::Number,
+                TokenKind::LineComment | TokenKind::BlockComment => Class::Comment,
+                _ => Class::Plain,
+            }
+        };
+        push(class, token.start, end);
+        pos = end;
+        i += 1;
+    }
+    push(Class::Plain, pos, src.len());
+    pieces
+        .into_iter()
+        .map(|(class, start, end)| (class, &src[start..end]))
+        .collect()
+}
+
+// One styled `Line` per source line, tabs expanded.
+pub fn to_lines(code: &str) -> Vec<Line> {
+    let code = ansi::expand_tabs(code.trim_end_matches('\n'));
+    let mut lines: Vec<Line> = vec![Vec::new()];
+    for (class, text) in classify(&code) {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Vec::new());
+            }
+            if !part.is_empty() {
+                lines
+                    .last_mut()
+                    .unwrap()
+                    .push(Span::new(class.style(), part));
+            }
+        }
+    }
+    lines
+}
+
+// A `<pre><code>` block with a `<span class="…">` per highlighted piece,
+// to be styled by the page's CSS.
+pub fn to_html(code: &str) -> String {
+    let mut out = String::from("<pre class=\"rust\"><code>");
+    for (class, text) in classify(code) {
+        match class {
+            Class::Plain => out += &escape(text),
+            _ => out += &format!("<span class=\"{}\">{}</span>", class.css(), escape(text)),
+        }
+    }
+    out += "</code></pre>\n";
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out += "&amp;",
+            '<' => out += "&lt;",
+            '>' => out += "&gt;",
+            '"' => out += "&quot;",
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_macros_attributes_and_lifetimes() {
+        let src = "#[derive(Debug)]\nfn f<'a>(s: &'a str) { println!(\"{}\", s) }";
+        let pieces: Vec<(Class, &str)> = classify(src)
+            .into_iter()
+            .filter(|(class, _)| *class != Class::Plain)
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                (Class::Attribute, "#[derive(Debug)]"),
+                (Class::Keyword, "fn"),
+                (Class::Lifetime, "'a"),
+                (Class::Lifetime, "'a"),
+                (Class::Type, "str"),
+                (Class::Macro, "println!"),
+                (Class::Str, "\"{}\""),
+            ]
+        );
+        let joined: String = classify(src).iter().map(|(_, text)| *text).collect();
+        assert_eq!(joined, src);
+    }
+
+    #[test]
+    fn writes_html_and_terminal_lines() {
+        let src = "let x = a < b; // \"why\"";
+        assert_eq!(
+            to_html(src),
+            "<pre class=\"rust\"><code><span class=\"kw\">let</span> x = a &lt; b; \
+             <span class=\"com\">// &quot;why&quot;</span></code></pre>\n"
+        );
+        // Invalid code from errorhandling.rs still comes out whole.
+        let lines = to_lines("enum<T , T>\n/* unclosed");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(ansi::text(&lines[0]), "enum<T , T>");
+        assert_eq!(lines[1], [Span::new(Style::Comment, "/* unclosed")]);
+    }
+}
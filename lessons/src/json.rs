@@ -0,0 +1,904 @@
+// Minimal JSON values, writer and parser. We cannot pull serde from the
+// network, and the formats we emit (notebooks, reports) are small.
+//
+// `ToJson` and `FromJson` convert Rust values to and from `Value`; errors
+// from `FromJson` carry the path to the bad field, e.g. `skills[1]`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write as _};
+use std::io::{BufRead, BufReader, Read};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    // Keeps insertion order so output is stable and diffable.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn object<K: Into<String>>(pairs: impl IntoIterator<Item = (K, Value)>) -> Value {
+        Value::Object(pairs.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(pairs) => pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    pub fn to_compact(&self) -> String {
+        let mut out = String::new();
+        write_value(&mut out, self, None, 0);
+        out
+    }
+
+    // Pretty output with `indent` spaces per level.
+    pub fn to_pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        write_value(&mut out, self, Some(indent), 0);
+        out
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Value {
+        Value::String(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Value {
+        Value::Bool(b)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Value {
+        Value::Number(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Value {
+        Value::Number(n as f64)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Value {
+        Value::Array(items.into_iter().map(Into::into).collect())
+    }
+}
+
+fn write_value(out: &mut String, value: &Value, indent: Option<usize>, level: usize) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, *n),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Object(pairs) if pairs.is_empty() => out.push_str("{}"),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, indent, level + 1);
+                write_value(out, item, indent, level + 1);
+            }
+            newline(out, indent, level);
+            out.push(']');
+        }
+        Value::Object(pairs) => {
+            out.push('{');
+            for (i, (key, item)) in pairs.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, indent, level + 1);
+                write_string(out, key);
+                out.push(':');
+                if indent.is_some() {
+                    out.push(' ');
+                }
+                write_value(out, item, indent, level + 1);
+            }
+            newline(out, indent, level);
+            out.push('}');
+        }
+    }
+}
+
+fn newline(out: &mut String, indent: Option<usize>, level: usize) {
+    if let Some(width) = indent {
+        out.push('\n');
+        out.extend(std::iter::repeat_n(' ', width * level));
+    }
+}
+
+fn write_number(out: &mut String, n: f64) {
+    if !n.is_finite() {
+        // JSON has no NaN or infinity.
+        out.push_str("null");
+    } else if n.fract() == 0.0 && n.abs() < 1e15 {
+        let _ = write!(out, "{}", n as i64);
+    } else {
+        let _ = write!(out, "{}", n);
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    // Both from 1; the column counts characters, not bytes.
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Exactly one value, with nothing but whitespace around it.
+pub fn parse(text: &str) -> Result<Value, Error> {
+    let mut reader = Reader::new(text.as_bytes());
+    let value = reader.value()?;
+    reader.end()?;
+    Ok(value)
+}
+
+// Like `parse`, for a file or socket.
+pub fn from_reader(input: impl Read) -> Result<Value, Error> {
+    let mut reader = Reader::new(BufReader::new(input));
+    let value = reader.value()?;
+    reader.end()?;
+    Ok(value)
+}
+
+// Values one after another, as in JSON Lines, read a byte at a time so a
+// large stream is never held in memory whole. As an iterator it stops
+// after the first error.
+pub struct Reader<R: BufRead> {
+    input: R,
+    line: usize,
+    column: usize,
+    // Arrays and objects open around the current position.
+    depth: usize,
+    failed: bool,
+}
+
+// Deeper nesting is an error rather than a stack overflow.
+pub const MAX_DEPTH: usize = 128;
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(input: R) -> Reader<R> {
+        Reader {
+            input,
+            line: 1,
+            column: 1,
+            depth: 0,
+            failed: false,
+        }
+    }
+
+    // The next value, or `None` at the end of the input.
+    pub fn next_value(&mut self) -> Result<Option<Value>, Error> {
+        self.skip_ws()?;
+        if self.peek()?.is_none() {
+            return Ok(None);
+        }
+        self.value().map(Some)
+    }
+
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Error> {
+        match self.input.fill_buf() {
+            Ok(buf) => Ok(buf.first().copied()),
+            Err(e) => Err(self.error(e.to_string())),
+        }
+    }
+
+    fn bump(&mut self) -> Result<Option<u8>, Error> {
+        let b = self.peek()?;
+        if let Some(b) = b {
+            self.input.consume(1);
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if b & 0xC0 != 0x80 {
+                // Not a UTF-8 continuation byte: a new character.
+                self.column += 1;
+            }
+        }
+        Ok(b)
+    }
+
+    fn skip_ws(&mut self) -> Result<(), Error> {
+        while self.peek()?.is_some_and(|b| b.is_ascii_whitespace()) {
+            self.bump()?;
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<(), Error> {
+        self.skip_ws()?;
+        match self.peek()? {
+            None => Ok(()),
+            Some(_) => Err(self.error("trailing characters")),
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        self.skip_ws()?;
+        if self.peek()? == Some(b) {
+            self.bump()?;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", b as char)))
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, Error> {
+        let start = self.error(format!("expected `{}`", word));
+        for &b in word.as_bytes() {
+            if self.peek()? != Some(b) {
+                return Err(start);
+            }
+            self.bump()?;
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip_ws()?;
+        match self.peek()? {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, Error>) -> Result<Value, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("nested more than {} deep", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.bump()?;
+        let mut items = Vec::new();
+        self.skip_ws()?;
+        if self.peek()? == Some(b']') {
+            self.bump()?;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_ws()?;
+            match self.peek()? {
+                Some(b',') => {
+                    self.bump()?;
+                }
+                Some(b']') => {
+                    self.bump()?;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        self.bump()?;
+        let mut pairs = Vec::new();
+        self.skip_ws()?;
+        if self.peek()? == Some(b'}') {
+            self.bump()?;
+            return Ok(Value::Object(pairs));
+        }
+        loop {
+            self.skip_ws()?;
+            if self.peek()? != Some(b'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.string()?;
+            self.expect(b':')?;
+            pairs.push((key, self.value()?));
+            self.skip_ws()?;
+            match self.peek()? {
+                Some(b',') => {
+                    self.bump()?;
+                }
+                Some(b'}') => {
+                    self.bump()?;
+                    return Ok(Value::Object(pairs));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.error("");
+        let mut text = String::new();
+        while let Some(b) = self
+            .peek()?
+            .filter(|b| matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+        {
+            text.push(b as char);
+            self.bump()?;
+        }
+        match text.parse::<f64>() {
+            // `1e999` would come back as infinity, which JSON cannot write.
+            Ok(n) if is_number(&text) && !n.is_finite() => Err(Error {
+                message: format!("number `{}` is out of range", text),
+                ..start
+            }),
+            Ok(n) if is_number(&text) => Ok(Value::Number(n)),
+            _ => Err(Error {
+                message: format!("invalid number `{}`", text),
+                ..start
+            }),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.bump()?;
+        let mut out = Vec::new();
+        loop {
+            let Some(b) = self.bump()? else {
+                return Err(self.error("unterminated string"));
+            };
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let Some(esc) = self.bump()? else {
+                        return Err(self.error("unterminated string"));
+                    };
+                    let c = match esc {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    let mut buf = [0; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                b if b < 0x20 => return Err(self.error("control character in string")),
+                b => out.push(b),
+            }
+        }
+        String::from_utf8(out).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .peek()?
+                .and_then(|b| (b as char).to_digit(16))
+                .ok_or_else(|| self.error("invalid \\u escape"))?;
+            self.bump()?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, Error> {
+        let first = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&first) {
+            // Surrogate pair, e.g. `\ud83e\udd80` for a crab emoji.
+            if self.bump()? != Some(b'\\') || self.bump()? != Some(b'u') {
+                return Err(self.error("unpaired surrogate"));
+            }
+            let second = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&second) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((first - 0xD800) << 10) + (second - 0xDC00)
+        } else {
+            first
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid \\u escape"))
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Result<Value, Error>> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_value().transpose();
+        self.failed = matches!(next, Some(Err(_)));
+        next
+    }
+}
+
+// JSON's grammar, which is stricter than Rust's: no `1.`, `01` or `+1`.
+fn is_number(text: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let rest = text.strip_prefix('-').unwrap_or(text);
+    let (mantissa, exponent) = match rest.find(['e', 'E']) {
+        Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+        None => (rest, None),
+    };
+    let (int, frac) = match mantissa.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (mantissa, None),
+    };
+    digits(int)
+        && (int == "0" || !int.starts_with('0'))
+        && frac.is_none_or(digits)
+        && exponent.is_none_or(|e| digits(e.strip_prefix(['+', '-']).unwrap_or(e)))
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, DecodeError>;
+}
+
+// Why a `Value` did not fit, and where: `path` is empty for the value
+// itself, `skills[1]` for an element of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub path: String,
+    pub message: String,
+}
+
+impl DecodeError {
+    pub fn new(message: impl Into<String>) -> DecodeError {
+        DecodeError {
+            path: String::new(),
+            message: message.into(),
+        }
+    }
+
+    // `expected a string, found a number`.
+    pub fn expected(what: &str, found: &Value) -> DecodeError {
+        DecodeError::new(format!("expected {}, found {}", what, found.kind()))
+    }
+
+    // The same error one level further out, under `segment` (a key, or
+    // `[i]` for an index).
+    pub fn at(mut self, segment: &str) -> DecodeError {
+        let dot = !self.path.is_empty() && !self.path.starts_with('[');
+        self.path = format!("{}{}{}", segment, if dot { "." } else { "" }, self.path);
+        self
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Value {
+    // For error messages: `a string`, `an object`, ...
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        }
+    }
+}
+
+// `value[key]` decoded, for `FromJson` impls of structs. A missing key
+// reads as `null`, so `Option` fields may be left out.
+pub fn field<T: FromJson>(value: &Value, key: &str) -> Result<T, DecodeError> {
+    if !value.is_object() {
+        return Err(DecodeError::expected("an object", value));
+    }
+    match value.get(key) {
+        Some(item) => T::from_json(item).map_err(|e| e.at(key)),
+        None => T::from_json(&Value::Null).map_err(|_| DecodeError::new("missing").at(key)),
+    }
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Value, DecodeError> {
+        Ok(value.clone())
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<bool, DecodeError> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(DecodeError::expected("a boolean", value)),
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<String, DecodeError> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            _ => Err(DecodeError::expected("a string", value)),
+        }
+    }
+}
+
+// Integers must be whole and in range; JSON numbers are all f64, so
+// anything beyond 2^53 has already lost precision. `MAX as f64` rounds up
+// to a power of two for 64-bit types, so the upper bound is checked as
+// `< MAX + 1`, which is exact for every width.
+macro_rules! integers {
+    ($($t:ty),*) => {$(
+        impl ToJson for $t {
+            fn to_json(&self) -> Value {
+                Value::Number(*self as f64)
+            }
+        }
+
+        impl FromJson for $t {
+            fn from_json(value: &Value) -> Result<$t, DecodeError> {
+                match value {
+                    Value::Number(n)
+                        if n.fract() == 0.0
+                            && *n >= <$t>::MIN as f64
+                            && *n < <$t>::MAX as f64 + 1.0 =>
+                    {
+                        Ok(*n as $t)
+                    }
+                    Value::Number(n) => Err(DecodeError::new(format!(
+                        "expected a whole number from {} to {}, found {}",
+                        <$t>::MIN,
+                        <$t>::MAX,
+                        n
+                    ))),
+                    _ => Err(DecodeError::expected("a number", value)),
+                }
+            }
+        }
+    )*};
+}
+
+integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! floats {
+    ($($t:ty),*) => {$(
+        impl ToJson for $t {
+            fn to_json(&self) -> Value {
+                Value::Number(*self as f64)
+            }
+        }
+
+        impl FromJson for $t {
+            fn from_json(value: &Value) -> Result<$t, DecodeError> {
+                match value {
+                    Value::Number(n) => Ok(*n as $t),
+                    _ => Err(DecodeError::expected("a number", value)),
+                }
+            }
+        }
+    )*};
+}
+
+floats!(f32, f64);
+
+// `None` is `null`.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        match self {
+            Some(value) => value.to_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Option<T>, DecodeError> {
+        match value {
+            Value::Null => Ok(None),
+            _ => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Vec<T>, DecodeError> {
+        let items = value
+            .as_array()
+            .ok_or_else(|| DecodeError::expected("an array", value))?;
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| T::from_json(item).map_err(|e| e.at(&format!("[{}]", i))))
+            .collect()
+    }
+}
+
+// Keys sorted, so the same map always gives the same text.
+impl<T: ToJson> ToJson for HashMap<String, T> {
+    fn to_json(&self) -> Value {
+        let mut pairs: Vec<(&String, &T)> = self.iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(b.0));
+        Value::object(pairs.into_iter().map(|(k, v)| (k.clone(), v.to_json())))
+    }
+}
+
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &Value) -> Result<HashMap<String, T>, DecodeError> {
+        decode_map(value).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> Value {
+        Value::object(self.iter().map(|(k, v)| (k.clone(), v.to_json())))
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &Value) -> Result<BTreeMap<String, T>, DecodeError> {
+        decode_map(value).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
+fn decode_map<T: FromJson>(value: &Value) -> Result<Vec<(String, T)>, DecodeError> {
+    let Value::Object(pairs) = value else {
+        return Err(DecodeError::expected("an object", value));
+    };
+    pairs
+        .iter()
+        .map(|(k, v)| Ok((k.clone(), T::from_json(v).map_err(|e| e.at(k))?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_compact_and_pretty_text() {
+        let value = Value::object([
+            ("name", Value::from("Ferris 🦀 \"the crab\"\n")),
+            ("age", Value::Number(9.0)),
+            ("pi", Value::Number(3.25)),
+            ("tags", Value::from(vec![Value::Null, Value::Bool(false)])),
+            ("empty", Value::object::<&str>([])),
+        ]);
+        assert_eq!(parse(&value.to_compact()).unwrap(), value);
+        assert_eq!(parse(&value.to_pretty(2)).unwrap(), value);
+        assert_eq!(
+            parse("\"\\ud83e\\udd80\\u00e9\"").unwrap(),
+            Value::from("🦀é")
+        );
+    }
+
+    #[test]
+    fn reports_malformed_input_by_line_and_column() {
+        let cases = [
+            ("", "line 1, column 1: unexpected end of input"),
+            ("[1, 2", "line 1, column 6: expected `,` or `]`"),
+            ("{\"a\" 1}", "line 1, column 6: expected `:`"),
+            ("{\n  \"a\": tru\n}", "line 2, column 8: expected `true`"),
+            ("[01]", "line 1, column 2: invalid number `01`"),
+            ("[1.]", "line 1, column 2: invalid number `1.`"),
+            ("\"é\\x\"", "line 1, column 5: invalid escape"),
+            ("\"\\ud83e\"", "line 1, column 9: unpaired surrogate"),
+            ("{} x", "line 1, column 4: trailing characters"),
+            ("\"open", "line 1, column 6: unterminated string"),
+            (
+                "[1e999]",
+                "line 1, column 2: number `1e999` is out of range",
+            ),
+            (
+                "-1e400",
+                "line 1, column 1: number `-1e400` is out of range",
+            ),
+        ];
+        for (text, error) in cases {
+            assert_eq!(parse(text).unwrap_err().to_string(), error, "{:?}", text);
+        }
+
+        let deep = "[".repeat(100_000);
+        assert_eq!(
+            parse(&deep).unwrap_err().to_string(),
+            "line 1, column 129: nested more than 128 deep"
+        );
+        let ok = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse(&ok).is_ok());
+    }
+
+    #[test]
+    fn streams_values_and_stops_at_the_first_error() {
+        let input = "{\"n\": 1}\n{\"n\": 2}\n\n[3]\n{oops}\n{\"n\": 5}\n";
+        let results: Vec<Result<Value, Error>> = Reader::new(input.as_bytes()).collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1], Ok(Value::object([("n", Value::Number(2.0))])));
+        assert_eq!(
+            results[3].as_ref().unwrap_err().to_string(),
+            "line 5, column 2: expected a string key"
+        );
+        assert_eq!(
+            from_reader("  [true]  ".as_bytes()),
+            Ok(Value::from(vec![true]))
+        );
+    }
+
+    #[test]
+    fn converts_collections_and_reports_paths() {
+        let mut scores: HashMap<String, Vec<Option<u32>>> = HashMap::new();
+        scores.insert("bob".to_string(), vec![Some(87), None]);
+        scores.insert("alice".to_string(), vec![Some(95)]);
+        let value = scores.to_json();
+        assert_eq!(value.to_compact(), r#"{"alice":[95],"bob":[87,null]}"#);
+        assert_eq!(HashMap::from_json(&value), Ok(scores));
+
+        let bad = parse(r#"{"alice": [95, 1.5], "bob": []}"#).unwrap();
+        let err = HashMap::<String, Vec<u32>>::from_json(&bad).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "alice[1]: expected a whole number from 0 to 4294967295, found 1.5"
+        );
+        // 2^63 and 2^64 are one past the end, though `MAX as f64` equals them.
+        assert!(i64::from_json(&Value::Number(9_223_372_036_854_775_808.0)).is_err());
+        assert!(u64::from_json(&Value::Number(18_446_744_073_709_551_616.0)).is_err());
+        assert_eq!(
+            i64::from_json(&Value::Number(-9_223_372_036_854_775_808.0)),
+            Ok(i64::MIN)
+        );
+        assert_eq!(
+            u64::from_json(&Value::Number(9_007_199_254_740_992.0)),
+            Ok(1 << 53)
+        );
+        let err = u8::from_json(&Value::Number(300.0)).unwrap_err();
+        assert_eq!(
+            err.message,
+            "expected a whole number from 0 to 255, found 300"
+        );
+
+        let person = parse(r#"{"name": "Ferris"}"#).unwrap();
+        assert_eq!(field::<String>(&person, "name"), Ok("Ferris".to_string()));
+        assert_eq!(field::<Option<u32>>(&person, "age"), Ok(None));
+        assert_eq!(
+            field::<u32>(&person, "age").unwrap_err().to_string(),
+            "age: missing"
+        );
+    }
+}
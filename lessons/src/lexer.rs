@@ -0,0 +1,383 @@
+// A small Rust tokenizer for the notes.
+//
+// The lesson files mix English prose with code, and the code is not always
+// valid Rust, so the lexer never fails: anything it does not recognise
+// becomes an `Unknown` token and scanning carries on from the next
+// character, and an unterminated string or comment runs to the end of the
+// input. Raw and byte strings, raw identifiers and nested block comments
+// are read the way rustc reads them.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident,
+    Lifetime,
+    Char,
+    Str,
+    Number,
+    LineComment,
+    BlockComment,
+    Punct,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    // Byte offsets into the source, `start..end`.
+    pub start: usize,
+    pub end: usize,
+    // 1-based line of the first character.
+    pub line: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, src: &'a str) -> &'a str {
+        &src[self.start..self.end]
+    }
+
+    pub fn is_comment(&self) -> bool {
+        matches!(self.kind, TokenKind::LineComment | TokenKind::BlockComment)
+    }
+}
+
+// A source file's tokens with comments stripped, for pattern matching over
+// code. Out-of-range indexes read as empty text rather than panicking.
+pub struct Code<'a> {
+    pub src: &'a str,
+    pub tokens: Vec<Token>,
+}
+
+impl<'a> Code<'a> {
+    pub fn new(src: &'a str) -> Code<'a> {
+        let tokens = tokenize(src)
+            .into_iter()
+            .filter(|t| !t.is_comment())
+            .collect();
+        Code { src, tokens }
+    }
+
+    pub fn text(&self, i: usize) -> &'a str {
+        self.tokens.get(i).map_or("", |t| t.text(self.src))
+    }
+
+    pub fn kind(&self, i: usize) -> Option<TokenKind> {
+        self.tokens.get(i).map(|t| t.kind)
+    }
+
+    pub fn is(&self, i: usize, text: &str) -> bool {
+        self.text(i) == text
+    }
+
+    // True if the tokens starting at `i` read `texts` in order.
+    pub fn matches(&self, i: usize, texts: &[&str]) -> bool {
+        texts.iter().enumerate().all(|(n, t)| self.is(i + n, t))
+    }
+}
+
+// Longest operators first so `..=` wins over `..`.
+const PUNCTS: &[&str] = &[
+    "..=", "...", "<<=", ">>=", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "^=", "&=", "|=", "<<", ">>", "..",
+];
+
+struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.src[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn eat_while(&mut self, pred: impl Fn(char) -> bool) {
+        while let Some(c) = self.peek() {
+            if !pred(c) {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+}
+
+pub fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+pub fn is_ident_continue(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+pub fn tokenize(src: &str) -> Vec<Token> {
+    let mut cur = Cursor {
+        src,
+        pos: 0,
+        line: 1,
+    };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = cur.peek() {
+        if c.is_whitespace() {
+            cur.bump();
+            continue;
+        }
+
+        let start = cur.pos;
+        let line = cur.line;
+        let kind = if cur.rest().starts_with("//") {
+            cur.eat_while(|c| c != '\n');
+            TokenKind::LineComment
+        } else if cur.rest().starts_with("/*") {
+            lex_block_comment(&mut cur);
+            TokenKind::BlockComment
+        } else if let Some(kind) = lex_prefixed(&mut cur) {
+            kind
+        } else if is_ident_start(c) {
+            cur.eat_while(is_ident_continue);
+            TokenKind::Ident
+        } else if c.is_ascii_digit() {
+            lex_number(&mut cur);
+            TokenKind::Number
+        } else if c == '"' {
+            cur.bump();
+            lex_quoted(&mut cur, '"');
+            TokenKind::Str
+        } else if c == '\'' {
+            lex_quote(&mut cur)
+        } else if c.is_ascii_punctuation() {
+            match PUNCTS.iter().find(|p| cur.rest().starts_with(**p)) {
+                Some(p) => cur.pos += p.len(),
+                None => {
+                    cur.bump();
+                }
+            }
+            TokenKind::Punct
+        } else {
+            cur.bump();
+            TokenKind::Unknown
+        };
+
+        tokens.push(Token {
+            kind,
+            start,
+            end: cur.pos,
+            line,
+        });
+    }
+
+    tokens
+}
+
+fn lex_number(cur: &mut Cursor) {
+    cur.eat_while(|c| c.is_ascii_alphanumeric() || c == '_');
+    // `1.5` continues the number, `1..10` and `x.0.len()` do not.
+    if cur.peek() == Some('.') && cur.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+        cur.bump();
+        cur.eat_while(|c| c.is_ascii_alphanumeric() || c == '_');
+    }
+}
+
+// `/* /* */ */` is one comment, as in rustc.
+fn lex_block_comment(cur: &mut Cursor) {
+    let mut depth = 0;
+    while !cur.rest().is_empty() {
+        if cur.rest().starts_with("/*") {
+            depth += 1;
+            cur.pos += 2;
+        } else if cur.rest().starts_with("*/") {
+            depth -= 1;
+            cur.pos += 2;
+            if depth == 0 {
+                break;
+            }
+        } else {
+            cur.bump();
+        }
+    }
+}
+
+// Literals and identifiers that start with a letter prefix: `r"…"`,
+// `r#"…"#`, `b"…"`, `br"…"`, `c"…"`, `b'x'` and `r#match`. `None` leaves
+// the cursor where it was, for plain identifiers.
+fn lex_prefixed(cur: &mut Cursor) -> Option<TokenKind> {
+    let rest = cur.rest();
+    let prefix = ["br", "cr", "b", "c", "r"]
+        .into_iter()
+        .find(|p| rest.starts_with(p))?;
+    let after = &rest[prefix.len()..];
+    if prefix.ends_with('r') {
+        let hashes = after.len() - after.trim_start_matches('#').len();
+        if after[hashes..].starts_with('"') {
+            cur.pos += prefix.len() + hashes + 1;
+            lex_raw(cur, hashes);
+            return Some(TokenKind::Str);
+        }
+        if prefix == "r" && hashes == 1 && after[1..].starts_with(is_ident_start) {
+            cur.pos += 2;
+            cur.eat_while(is_ident_continue);
+            return Some(TokenKind::Ident);
+        }
+        None
+    } else if after.starts_with('"') {
+        cur.pos += prefix.len() + 1;
+        lex_quoted(cur, '"');
+        Some(TokenKind::Str)
+    } else if prefix == "b" && after.starts_with('\'') {
+        cur.pos += 1;
+        Some(lex_quote(cur))
+    } else {
+        None
+    }
+}
+
+// The body of a raw string after its opening quote, through the quote and
+// `hashes` hashes that close it.
+fn lex_raw(cur: &mut Cursor, hashes: usize) {
+    let close = format!("\"{}", "#".repeat(hashes));
+    match cur.rest().find(&close) {
+        Some(i) => {
+            let end = cur.pos + i + close.len();
+            while cur.pos < end {
+                cur.bump();
+            }
+        }
+        None => while cur.bump().is_some() {},
+    }
+}
+
+// Consumes up to and including the closing quote, or to the end of input.
+fn lex_quoted(cur: &mut Cursor, quote: char) {
+    while let Some(c) = cur.bump() {
+        if c == '\\' {
+            cur.bump();
+        } else if c == quote {
+            break;
+        }
+    }
+}
+
+// `'a'`, `'\n'` and `' '` are chars; `'a` on its own is a lifetime.
+fn lex_quote(cur: &mut Cursor) -> TokenKind {
+    cur.bump();
+    match (cur.peek(), cur.peek_nth(1)) {
+        (Some('\\'), _) => {
+            lex_quoted(cur, '\'');
+            TokenKind::Char
+        }
+        (Some(c), Some('\'')) if c != '\n' => {
+            cur.bump();
+            cur.bump();
+            TokenKind::Char
+        }
+        (Some(c), _) if is_ident_start(c) => {
+            cur.eat_while(is_ident_continue);
+            TokenKind::Lifetime
+        }
+        _ => TokenKind::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(src: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(src)
+            .iter()
+            .map(|t| (t.kind, t.text(src)))
+            .collect()
+    }
+
+    #[test]
+    fn reads_strings_chars_and_lifetimes() {
+        use TokenKind::*;
+        let src = r####"let s: &'static str = r#"say "hi""#; let c = '\''; b'x' b"y" br"z""####;
+        assert_eq!(
+            kinds(src),
+            [
+                (Ident, "let"),
+                (Ident, "s"),
+                (Punct, ":"),
+                (Punct, "&"),
+                (Lifetime, "'static"),
+                (Ident, "str"),
+                (Punct, "="),
+                (Str, r##"r#"say "hi""#"##),
+                (Punct, ";"),
+                (Ident, "let"),
+                (Ident, "c"),
+                (Punct, "="),
+                (Char, r"'\''"),
+                (Punct, ";"),
+                (Char, "b'x'"),
+                (Str, "b\"y\""),
+                (Str, "br\"z\""),
+            ]
+        );
+        assert_eq!(
+            kinds("fn f<'a>(x: &'a u8) -> char { 'a' }")[3..5],
+            [(Lifetime, "'a"), (Punct, ">")]
+        );
+        assert_eq!(kinds("'a'"), [(Char, "'a'")]);
+        assert_eq!(kinds("r#match"), [(Ident, "r#match")]);
+    }
+
+    #[test]
+    fn nests_block_comments() {
+        let src = "/* outer /* inner */ still outer */ x";
+        assert_eq!(
+            kinds(src),
+            [
+                (
+                    TokenKind::BlockComment,
+                    "/* outer /* inner */ still outer */"
+                ),
+                (TokenKind::Ident, "x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn never_fails_on_broken_input() {
+        // From errorhandling.rs, plus unterminated literals and comments.
+        for src in [
+            "enum<T , T>",
+            "\"never closed",
+            "r##\"never closed\"#",
+            "/* /* */",
+            "'",
+            "b'",
+            "§ ¶ 🦀",
+        ] {
+            let tokens = tokenize(src);
+            assert_eq!(
+                tokens.last().map_or(0, |t| t.end),
+                src.trim_end().len(),
+                "{}",
+                src
+            );
+            for pair in tokens.windows(2) {
+                assert!(pair[0].end <= pair[1].start, "{}", src);
+            }
+        }
+        assert_eq!(kinds("/* /* */"), [(TokenKind::BlockComment, "/* /* */")]);
+    }
+}
@@ -0,0 +1,36 @@
+// Tooling for the Rust notes in this repository.
+
+pub mod ansi;
+pub mod badge;
+pub mod blocks;
+pub mod browser;
+pub mod catalog;
+pub mod compile;
+pub mod coverage;
+pub mod csv;
+pub mod date;
+pub mod dedup;
+pub mod exercises;
+pub mod golden;
+pub mod highlight;
+pub mod json;
+pub mod lexer;
+pub mod lint;
+pub mod markdown;
+pub mod notebook;
+pub mod pack;
+pub mod panics;
+pub mod planner;
+pub mod progress;
+pub mod readme;
+pub mod sandbox;
+pub mod sha256;
+pub mod snippets;
+pub mod spell;
+pub mod table;
+pub mod tar;
+pub mod tasks;
+pub mod term;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod toml;
@@ -0,0 +1,210 @@
+// Learner-focused lints over the notes.
+//
+// These are not clippy: each rule targets a habit that shows up in the
+// lessons and points back at the lesson that explains the better way.
+
+use crate::lexer::{Code, TokenKind};
+
+pub struct Rule {
+    pub id: &'static str,
+    pub summary: &'static str,
+    pub explanation: &'static str,
+    // Catalog id of the lesson to read next.
+    pub lesson: &'static str,
+    check: fn(&Code) -> Vec<usize>,
+}
+
+pub struct Finding {
+    pub rule: &'static Rule,
+    pub line: usize,
+}
+
+pub static RULES: &[Rule] = &[
+    Rule {
+        id: "ref-string-param",
+        summary: "prefer `&str` over `&String`",
+        explanation: "A `&String` only accepts a borrowed `String`. A `&str` accepts that too \
+                      (it derefs automatically) as well as string literals and slices, so the \
+                      function is easier to call and does the same work.",
+        lesson: "more-advanced-datastructure/string-vs-slices",
+        check: ref_string_param,
+    },
+    Rule {
+        id: "unwrap-on-lock",
+        summary: "`.lock().unwrap()` panics if another thread panicked",
+        explanation: "When a thread panics while holding a `Mutex`, the lock becomes poisoned \
+                      and every later `.lock().unwrap()` panics as well. Use `.expect(\"...\")` \
+                      to say why that cannot happen, or recover the data with \
+                      `.unwrap_or_else(|e| e.into_inner())`.",
+        lesson: "more-advanced-datastructure/multithreading",
+        check: unwrap_on_lock,
+    },
+    Rule {
+        id: "non-snake-case-local",
+        summary: "local variable names should be snake_case",
+        explanation: "Rust names variables like `capital_cities`, not `capitalCities`. The \
+                      compiler warns about it, and sticking to the convention makes code from \
+                      different people look the same.",
+        lesson: "concepts/rust_datatypes",
+        check: non_snake_case_local,
+    },
+    Rule {
+        id: "cloned-collect-after-filter",
+        summary: "`.cloned().collect()` after `.filter()` copies every kept element",
+        explanation: "Filtering `.iter()` gives references, and `.cloned()` turns each one into \
+                      a full copy. For numbers use `.copied()`; otherwise keep the references \
+                      (`Vec<&T>`) or filter `.into_iter()` when the original is not needed.",
+        lesson: "more-advanced-datastructure/iterator",
+        check: cloned_collect_after_filter,
+    },
+];
+
+pub fn lint_source(src: &str) -> Vec<Finding> {
+    let code = Code::new(src);
+
+    let mut findings: Vec<Finding> = RULES
+        .iter()
+        .flat_map(|rule| {
+            (rule.check)(&code)
+                .into_iter()
+                .map(move |line| Finding { rule, line })
+        })
+        .collect();
+    findings.sort_by_key(|f| f.line);
+    findings
+}
+
+// `: &String` or `-> &String`, with an optional lifetime. `&mut String` is
+// fine because the callee may need to grow it.
+fn ref_string_param(code: &Code) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for i in 0..code.tokens.len() {
+        if !(code.is(i, ":") || code.is(i, "->")) || !code.is(i + 1, "&") {
+            continue;
+        }
+        let mut j = i + 2;
+        if code.kind(j) == Some(TokenKind::Lifetime) {
+            j += 1;
+        }
+        if code.is(j, "String") {
+            lines.push(code.tokens[i + 1].line);
+        }
+    }
+    lines
+}
+
+fn unwrap_on_lock(code: &Code) -> Vec<usize> {
+    (0..code.tokens.len())
+        .filter(|&i| code.matches(i, &[".", "lock", "(", ")", ".", "unwrap", "(", ")"]))
+        .map(|i| code.tokens[i + 1].line)
+        .collect()
+}
+
+// `let [mut] name =` / `let [mut] name:` where name has an uppercase letter.
+// Requiring the `=` or `:` keeps English sentences like "let Rust decide"
+// out of the report.
+fn non_snake_case_local(code: &Code) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for i in 0..code.tokens.len() {
+        if !code.is(i, "let") {
+            continue;
+        }
+        let j = if code.is(i + 1, "mut") { i + 2 } else { i + 1 };
+        let Some(name) = code.tokens.get(j).filter(|t| t.kind == TokenKind::Ident) else {
+            continue;
+        };
+        let text = name.text(code.src);
+        if text.chars().any(char::is_uppercase) && (code.is(j + 1, "=") || code.is(j + 1, ":")) {
+            lines.push(name.line);
+        }
+    }
+    lines
+}
+
+fn cloned_collect_after_filter(code: &Code) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for i in 0..code.tokens.len() {
+        if !code.matches(i, &[".", "cloned", "(", ")", ".", "collect"]) {
+            continue;
+        }
+        // Walk back through the method chain, skipping over argument lists,
+        // until the start of the statement.
+        let mut depth = 0usize;
+        let mut j = i;
+        while j > 0 {
+            j -= 1;
+            match code.text(j) {
+                ")" | "]" => depth += 1,
+                "(" | "[" if depth > 0 => depth -= 1,
+                ";" | "{" | "}" | "(" | "[" | "=" if depth == 0 => break,
+                "filter" if depth == 0 && j > 0 && code.is(j - 1, ".") => {
+                    lines.push(code.tokens[i + 1].line);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (rule, line) for each finding in `src`.
+    fn lint(src: &str) -> Vec<(&'static str, usize)> {
+        lint_source(src)
+            .iter()
+            .map(|f| (f.rule.id, f.line))
+            .collect()
+    }
+
+    #[test]
+    fn flags_ref_string_but_not_str() {
+        let src = "fn greet(name: &String) {}\n\
+                   fn first<'a>(s: &'a str) -> &'a String { s }\n\
+                   fn ok(name: &str, buf: &mut String) -> &str { name }\n";
+        assert_eq!(
+            lint(src),
+            [("ref-string-param", 1), ("ref-string-param", 2)]
+        );
+    }
+
+    #[test]
+    fn flags_unwrap_on_lock_but_not_question_mark() {
+        let src = "fn f(m: &Mutex<i32>) -> Result<(), E> {\n\
+                   let a = m.lock()?;\n\
+                   let b = m.lock().expect(\"not poisoned\");\n\
+                   let c = m.lock().unwrap();\n\
+                   Ok(())\n\
+                   }\n";
+        assert_eq!(lint(src), [("unwrap-on-lock", 4)]);
+    }
+
+    #[test]
+    fn flags_camel_case_locals_but_not_consts() {
+        let src = "const MAX_SIZE: usize = 3;\n\
+                   static GREETING: &str = \"hi\";\n\
+                   let capital_cities = 1;\n\
+                   let mut capitalCities = 2;\n\
+                   let Total: u32 = 3;\n\
+                   // let Rust decide the type\n";
+        assert_eq!(
+            lint(src),
+            [("non-snake-case-local", 4), ("non-snake-case-local", 5)]
+        );
+    }
+
+    #[test]
+    fn flags_cloned_after_filter_but_not_copied() {
+        let src = "let a: Vec<i32> = v.iter().filter(|x| **x > 1).copied().collect();\n\
+                   let b: Vec<String> = v\n\
+                       .iter()\n\
+                       .filter(|s| s.len() > (1))\n\
+                       .cloned()\n\
+                       .collect();\n\
+                   let c: Vec<String> = v.iter().cloned().collect();\n";
+        assert_eq!(lint(src), [("cloned-collect-after-filter", 5)]);
+    }
+}
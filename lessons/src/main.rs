@@ -0,0 +1,1348 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use lessons::ansi::{self, Line, Span, Style};
+use lessons::badge::Badge;
+use lessons::browser::{Action, Browser};
+use lessons::catalog::{self, Catalog, Lesson};
+use lessons::compile::{self, Cache};
+use lessons::coverage::Matrix;
+use lessons::date::{self, Date};
+use lessons::dedup::{self, Document};
+use lessons::exercises::{self, ExerciseSet};
+use lessons::golden::Golden;
+use lessons::pack::{self, Pack, Version};
+use lessons::panics::{self, Hints};
+use lessons::planner::Curriculum;
+use lessons::progress::Progress;
+use lessons::sandbox::{self, Limits};
+use lessons::snippets::{self, Snippet};
+use lessons::spell::{self, Dictionary};
+use lessons::table::{Border, Table};
+use lessons::term::{self, RawMode};
+use lessons::{highlight, lint, markdown, notebook, readme};
+
+const USAGE: &str = "\
+usage: lessons [--root DIR] <command> [args]
+
+commands:
+  list              list every lesson with its id
+  browse            read lessons in a terminal UI; run snippets, see
+                    exercises and mark lessons done from there
+  dedup [--threshold F]
+                    near-duplicate passages across the notes (and stray
+                    copies of them), with which copy to keep
+  doc [FILE] [--width N]
+                    render a markdown file (default: README.md) for the
+                    terminal
+  highlight FILE [--html]
+                    Rust source highlighted for the terminal, or as an HTML
+                    <pre> block
+  lint [FILE...]    check lessons for habits we want to avoid
+  coverage [--csv]  lesson-by-feature matrix and features never shown
+  spell [FILE...]   misspelt words in the prose of lessons and the docs,
+                    with suggestions (allowlist: lessons/allowlist.txt)
+  notebook [--out DIR] [ID...]
+                    export lessons as .ipynb files for the evcxr kernel
+  run [--jobs N] [--timeout SECS] [ID...]
+                    compile (cached, in parallel) and run every snippet with
+                    `fn main` under limits
+  plan [--start YYYY-MM-DD] [--ics FILE]
+                    day-by-day study plan for what is not done yet
+  done ID...        mark lessons as studied (the plan moves up)
+  readme            regenerate the generated sections of README.md
+  badges [--out DIR]
+                    SVG badges for lessons done, exercises passing and
+                    snippets compiling (default: badges/), linked from the
+                    README
+  exercises [list]  our exercises and their progress
+  exercises check [NAME...]
+                    run our exercises through the checker
+  exercises hint NAME
+  exercises import DIR
+                    check a Rustlings checkout (info.toml) and record results
+  exercises export DIR
+                    write our exercises in Rustlings layout
+  pack export FILE [--name N --version V] [--requires NAME=REQ] [ID...]
+                    write lessons (default: ours) to a .lpack archive
+  pack import FILE  install a pack under packs/ after checking it
+  pack [list]       installed packs
+  pack check        dependencies and lesson ids of installed packs
+";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    let mut root = catalog::default_root();
+    if let Some(i) = args.iter().position(|a| a == "--root") {
+        if i + 1 >= args.len() {
+            eprintln!("--root needs a directory");
+            return ExitCode::from(2);
+        }
+        root = PathBuf::from(args.remove(i + 1));
+        args.remove(i);
+    }
+
+    let catalog = match Catalog::discover(&root) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            eprintln!("could not read lessons under {}: {}", root.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match args.first().map(String::as_str) {
+        Some("list") => list(&catalog),
+        Some("browse") => browse(&catalog),
+        Some("dedup") => dedup(&catalog, &args[1..]),
+        Some("doc") => doc(&catalog, &args[1..]),
+        Some("highlight") => highlight_file(&args[1..]),
+        Some("lint") => run_lint(&catalog, &args[1..]),
+        Some("coverage") => coverage(&catalog, &args[1..]),
+        Some("spell") => spell(&catalog, &args[1..]),
+        Some("notebook") => export_notebooks(&catalog, &args[1..]),
+        Some("run") => run_snippets(&catalog, &args[1..]),
+        Some("plan") => plan(&catalog, &args[1..]),
+        Some("done") => mark_done(&catalog, &args[1..]),
+        Some("readme") => update_readme(&catalog),
+        Some("badges") => badges(&catalog, &args[1..]),
+        Some("exercises") => run_exercises(&catalog, &args[1..]),
+        Some("pack") => run_pack(&catalog, &args[1..]),
+        Some("help" | "--help" | "-h") => {
+            print!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Some(other) => {
+            eprintln!("unknown command `{}`\n\n{}", other, USAGE);
+            ExitCode::from(2)
+        }
+        None => {
+            eprint!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn list(catalog: &Catalog) -> ExitCode {
+    for lesson in catalog.lessons() {
+        println!("{:<55} {}", lesson.id, lesson.path.display());
+    }
+    ExitCode::SUCCESS
+}
+
+fn browse(catalog: &Catalog) -> ExitCode {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        eprintln!("browse needs a terminal");
+        return ExitCode::from(2);
+    }
+    let mut progress = match Progress::load(&Progress::default_path(catalog.root())) {
+        Ok(progress) => progress,
+        Err(e) => {
+            eprintln!("could not read progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let done: BTreeSet<String> = progress
+        .with_prefix("lesson:")
+        .filter(|(_, entry)| entry.status == "done")
+        .map(|(key, _)| key["lesson:".len()..].to_string())
+        .collect();
+    let mut browser = Browser::new(catalog, done);
+
+    let raw = match RawMode::enter() {
+        Ok(raw) => raw,
+        Err(e) => {
+            eprintln!("could not switch the terminal to raw mode: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let draw = |browser: &mut Browser| {
+        let (columns, rows) = term::size().unwrap_or((80, 24));
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(browser.render(columns, rows).as_bytes());
+        let _ = out.flush();
+        (columns, rows)
+    };
+    let mut size = draw(&mut browser);
+    loop {
+        let keys = match raw.read_keys() {
+            Ok(keys) => keys,
+            Err(e) => {
+                drop(raw);
+                eprintln!("could not read the keyboard: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        for key in &keys {
+            match browser.key(*key) {
+                None => {}
+                Some(Action::Quit) => return ExitCode::SUCCESS,
+                Some(Action::Run(snippet)) => {
+                    browser.set_status(format!("building {}…", snippet.name()));
+                    draw(&mut browser);
+                    let lesson = catalog
+                        .get(&snippet.lesson_id)
+                        .expect("snippet of a catalog lesson");
+                    let lines = run_in_browser(&snippet, &lesson.path);
+                    browser.set_status("");
+                    browser.show(
+                        format!("{} (block {})", snippet.lesson_id, snippet.block),
+                        lines,
+                    );
+                }
+                Some(Action::MarkDone(id)) => {
+                    match progress.record(&format!("lesson:{}", id), "done") {
+                        Ok(()) => browser.mark_done(&id),
+                        Err(e) => browser.set_status(format!("could not record progress: {}", e)),
+                    }
+                }
+                Some(Action::Exercises(id)) => {
+                    let lines = exercises_for(&id, &progress);
+                    browser.show(format!("Exercises for {}", id), lines);
+                }
+            }
+        }
+        // Redraw after input, and when the window changes size.
+        if !keys.is_empty() || term::size().is_some_and(|now| now != size) {
+            size = draw(&mut browser);
+        }
+    }
+}
+
+fn dedup(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let threshold = match args {
+        [] => 0.8,
+        [flag, value] if flag == "--threshold" => match value.parse::<f64>() {
+            Ok(t) if (0.0..=1.0).contains(&t) => t,
+            _ => {
+                eprintln!("--threshold needs a number between 0 and 1");
+                return ExitCode::from(2);
+            }
+        },
+        _ => {
+            eprintln!("usage: lessons dedup [--threshold F]");
+            return ExitCode::from(2);
+        }
+    };
+    let lessons: BTreeSet<&PathBuf> = catalog.lessons().iter().map(|l| &l.path).collect();
+    let paths = match dedup::sources(catalog.root()) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!(
+                "could not list files under {}: {}",
+                catalog.root().display(),
+                e
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut docs = Vec::new();
+    for path in paths {
+        match catalog::read_lossy(&catalog.root().join(&path)) {
+            Ok(text) => docs.push(Document {
+                name: path.display().to_string(),
+                lesson: lessons.contains(&path),
+                text,
+            }),
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let found = dedup::find(&docs, threshold);
+    for dup in &found {
+        let lines = |p: &dedup::Passage| p.last_line - p.first_line + 1;
+        println!(
+            "{:.0}% similar, {} and {} lines ({})",
+            dup.similarity * 100.0,
+            lines(&dup.keep),
+            lines(&dup.drop),
+            dup.reason
+        );
+        for (verb, p) in [("keep", &dup.keep), ("drop", &dup.drop)] {
+            println!(
+                "  {} {}:{}-{}",
+                verb, docs[p.doc].name, p.first_line, p.last_line
+            );
+        }
+        println!();
+    }
+    println!(
+        "{} near-duplicate passage(s) in {} file(s)",
+        found.len(),
+        docs.len()
+    );
+    ExitCode::SUCCESS
+}
+
+fn doc(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let mut path = catalog.root().join("README.md");
+    let mut width = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--width" => match args.next().and_then(|w| w.parse::<usize>().ok()) {
+                Some(w) if w > 0 => width = Some(w),
+                _ => {
+                    eprintln!("--width needs a number of columns");
+                    return ExitCode::from(2);
+                }
+            },
+            other if other.starts_with("--") => {
+                eprintln!("unknown option `{}`\n\n{}", other, USAGE);
+                return ExitCode::from(2);
+            }
+            file => path = PathBuf::from(file),
+        }
+    }
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let color = io::stdout().is_terminal();
+    let width = width
+        .or_else(|| term::size().filter(|_| color).map(|(columns, _)| columns))
+        .unwrap_or(80);
+    let lines = markdown::render(&markdown::parse(&text), width);
+    print!("{}", markdown::to_string(&lines, color));
+    ExitCode::SUCCESS
+}
+
+fn highlight_file(args: &[String]) -> ExitCode {
+    let html = args.iter().any(|a| a == "--html");
+    let files: Vec<&String> = args.iter().filter(|a| *a != "--html").collect();
+    let [file] = files[..] else {
+        eprintln!("usage: lessons highlight FILE [--html]");
+        return ExitCode::from(2);
+    };
+    let code = match std::fs::read_to_string(file) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}: {}", file, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if html {
+        print!("{}", highlight::to_html(&code));
+    } else {
+        let color = io::stdout().is_terminal();
+        for line in highlight::to_lines(&code) {
+            if color {
+                println!("{}", ansi::to_ansi(&line));
+            } else {
+                println!("{}", ansi::text(&line));
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// Builds and runs one snippet for the browser, as `lessons run` would.
+fn run_in_browser(snippet: &Snippet, path: &Path) -> Vec<Line> {
+    let error = |text: String| {
+        text.lines()
+            .map(|l| vec![Span::new(Style::Error, l)])
+            .collect()
+    };
+    let build = Cache::open(&Cache::default_dir())
+        .and_then(|cache| cache.build_all(std::slice::from_ref(snippet), 1));
+    let binary = match build.map(|mut builds| builds.remove(0).binary) {
+        Ok(Ok(binary)) => binary,
+        Ok(Err(diagnostics)) => return error(format!("does not compile\n\n{}", diagnostics)),
+        Err(e) => return error(e.to_string()),
+    };
+    let run = match sandbox::run(&binary, &Limits::default()) {
+        Ok(run) => run,
+        Err(e) => return error(e.to_string()),
+    };
+
+    let style = if run.outcome == sandbox::Outcome::Success {
+        Style::Ok
+    } else {
+        Style::Error
+    };
+    let mut lines = vec![
+        vec![Span::new(
+            style,
+            format!("{} in {:.2?}", run.outcome, run.elapsed),
+        )],
+        Vec::new(),
+    ];
+    lines.extend(
+        run.stdout
+            .lines()
+            .map(|l| ansi::plain(ansi::expand_tabs(l))),
+    );
+    lines.extend(
+        run.stderr
+            .lines()
+            .map(|l| vec![Span::new(Style::Error, ansi::expand_tabs(l))]),
+    );
+    if run.outcome != sandbox::Outcome::Success
+        && let Ok(hints) = Hints::load(&Hints::default_path())
+        && let Some(report) = panics::report(snippet, path, &run.stderr, &hints)
+    {
+        lines.push(Vec::new());
+        lines.extend(report.to_string().lines().map(ansi::plain));
+    }
+    lines
+}
+
+// Our exercises that practise lesson `id`, with their status and hint.
+fn exercises_for(id: &str, progress: &Progress) -> Vec<Line> {
+    let ours = match ExerciseSet::load_ours(&exercises::default_dir()) {
+        Ok(set) => set,
+        Err(e) => return vec![vec![Span::new(Style::Error, e)]],
+    };
+    let mut lines = Vec::new();
+    for ex in ours
+        .exercises
+        .iter()
+        .filter(|ex| ex.lesson.as_deref() == Some(id))
+    {
+        let status = progress
+            .get(&ours.progress_key(ex))
+            .map_or("not started", |e| e.status.as_str());
+        lines.push(vec![
+            Span::new(Style::Bold, ex.name.clone()),
+            Span::new(Style::Dim, format!("  {}  {}", ex.path.display(), status)),
+        ]);
+        lines.extend(ex.hint.lines().map(|l| ansi::plain(format!("  {}", l))));
+        lines.push(Vec::new());
+    }
+    if lines.is_empty() {
+        lines.push(ansi::plain("No exercises practise this lesson yet."));
+    } else {
+        lines.push(vec![Span::new(
+            Style::Dim,
+            "Check them with `lessons exercises check NAME`.",
+        )]);
+    }
+    lines
+}
+
+fn run_lint(catalog: &Catalog, files: &[String]) -> ExitCode {
+    // Explicit files are reported as given; otherwise lint every lesson.
+    let targets: Vec<(PathBuf, PathBuf)> = if files.is_empty() {
+        catalog
+            .lessons()
+            .iter()
+            .map(|l| (catalog.root().join(&l.path), l.path.clone()))
+            .collect()
+    } else {
+        files
+            .iter()
+            .map(|f| (PathBuf::from(f), PathBuf::from(f)))
+            .collect()
+    };
+
+    let mut total = 0;
+    for (path, shown) in &targets {
+        let src = match catalog::read_lossy(path) {
+            Ok(src) => src,
+            Err(e) => {
+                eprintln!("{}: {}", shown.display(), e);
+                return ExitCode::FAILURE;
+            }
+        };
+        for finding in lint::lint_source(&src) {
+            total += 1;
+            let rule = finding.rule;
+            println!(
+                "{}:{}: [{}] {}",
+                shown.display(),
+                finding.line,
+                rule.id,
+                rule.summary
+            );
+            println!("    {}", rule.explanation);
+            println!("    lesson: {}", lesson_link(catalog, rule.lesson));
+            println!();
+        }
+    }
+
+    if total == 0 {
+        println!("no findings");
+        ExitCode::SUCCESS
+    } else {
+        println!("{} finding(s) in {} file(s) checked", total, targets.len());
+        ExitCode::FAILURE
+    }
+}
+
+fn spell(catalog: &Catalog, files: &[String]) -> ExitCode {
+    let dict = match Dictionary::load(&spell::default_allowlist()) {
+        Ok(dict) => dict,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    // Explicit files are reported as given; otherwise our lessons and docs.
+    let targets: Vec<(PathBuf, PathBuf)> = if files.is_empty() {
+        catalog
+            .lessons()
+            .iter()
+            .filter(|l| l.pack.is_none())
+            .map(|l| l.path.clone())
+            .chain(["README.md", "LEARNING_LOG.md"].map(PathBuf::from))
+            .map(|p| (catalog.root().join(&p), p))
+            .collect()
+    } else {
+        files
+            .iter()
+            .map(|f| (PathBuf::from(f), PathBuf::from(f)))
+            .collect()
+    };
+
+    let mut total = 0;
+    for (path, shown) in &targets {
+        let src = match catalog::read_lossy(path) {
+            Ok(src) => src,
+            Err(e) => {
+                eprintln!("{}: {}", shown.display(), e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let found = if path.extension().is_some_and(|e| e == "md") {
+            spell::check_markdown(&src, &dict)
+        } else {
+            spell::check_notes(&src, &dict)
+        };
+        for m in found {
+            total += 1;
+            let suggestions = if m.suggestions.is_empty() {
+                String::new()
+            } else {
+                format!(" (did you mean {}?)", m.suggestions.join(", "))
+            };
+            println!(
+                "{}:{}:{}: {}{}",
+                shown.display(),
+                m.line,
+                m.column,
+                m.word,
+                suggestions
+            );
+        }
+    }
+
+    if total == 0 {
+        println!("no misspellings");
+        ExitCode::SUCCESS
+    } else {
+        println!(
+            "{} misspelling(s) in {} file(s) checked; add deliberate words to {}",
+            total,
+            targets.len(),
+            spell::default_allowlist().display()
+        );
+        ExitCode::FAILURE
+    }
+}
+
+fn coverage(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let csv = args.iter().any(|a| a == "--csv");
+    let mut matrix = Matrix::new();
+    for lesson in catalog.lessons() {
+        match catalog.read(lesson) {
+            Ok(src) => matrix.add(&lesson.id, &src),
+            Err(e) => {
+                eprintln!("{}: {}", lesson.path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    print!(
+        "{}",
+        if csv {
+            matrix.to_csv()
+        } else {
+            matrix.to_text()
+        }
+    );
+    ExitCode::SUCCESS
+}
+
+fn export_notebooks(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let mut out = catalog.root().join("notebooks");
+    let mut ids = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--out" {
+            match args.next() {
+                Some(dir) => out = PathBuf::from(dir),
+                None => {
+                    eprintln!("--out needs a directory");
+                    return ExitCode::from(2);
+                }
+            }
+        } else {
+            ids.push(arg.as_str());
+        }
+    }
+
+    let Some(lessons) = select(catalog, &ids) else {
+        return ExitCode::from(2);
+    };
+
+    let golden = Golden::new(&Golden::default_dir());
+    for lesson in lessons {
+        match notebook::export(catalog, lesson, &golden, &out) {
+            Ok(path) => println!("wrote {}", path.display()),
+            Err(e) => {
+                eprintln!("{}: {}", lesson.path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn run_snippets(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let mut limits = Limits::default();
+    let mut jobs = compile::default_jobs();
+    let mut ids = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--timeout" => match args
+                .next()
+                .and_then(|s| s.parse().ok())
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .filter(|timeout| !timeout.is_zero())
+            {
+                Some(timeout) => limits.timeout = timeout,
+                None => {
+                    eprintln!("--timeout needs a positive number of seconds");
+                    return ExitCode::from(2);
+                }
+            },
+            "--jobs" | "-j" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(n) => jobs = n,
+                None => {
+                    eprintln!("--jobs needs a number");
+                    return ExitCode::from(2);
+                }
+            },
+            _ => ids.push(arg.as_str()),
+        }
+    }
+    let Some(lessons) = select(catalog, &ids) else {
+        return ExitCode::from(2);
+    };
+
+    let Some((snippets, paths)) = runnable_snippets(catalog, &lessons) else {
+        return ExitCode::FAILURE;
+    };
+
+    let hints = match Hints::load(&Hints::default_path()) {
+        Ok(hints) => hints,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let cache_dir = Cache::default_dir();
+    let started = Instant::now();
+    let builds = match Cache::open(&cache_dir).and_then(|c| c.build_all(&snippets, jobs)) {
+        Ok(builds) => builds,
+        Err(e) => {
+            eprintln!("{}: {}", cache_dir.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let build_time = started.elapsed();
+
+    let mut tally: Vec<(String, usize)> = Vec::new();
+    for ((snippet, path), build) in snippets.iter().zip(paths).zip(&builds) {
+        let label = format!(
+            "{}:{} (block {})",
+            path.display(),
+            snippet.first_line,
+            snippet.block
+        );
+        let cached = if build.hit { "cached" } else { "built" };
+        let status = match &build.binary {
+            Ok(binary) => match sandbox::run(binary, &limits) {
+                Ok(run) => {
+                    println!(
+                        "{:<60} {} in {:.2?} ({} in {:.2?})",
+                        label, run.outcome, run.elapsed, cached, build.time
+                    );
+                    if run.outcome != sandbox::Outcome::Success
+                        && let Some(report) = panics::report(snippet, path, &run.stderr, &hints)
+                    {
+                        print!("{}", report);
+                    }
+                    run.outcome.to_string()
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", label, e);
+                    return ExitCode::FAILURE;
+                }
+            },
+            Err(_) => {
+                println!(
+                    "{:<60} does not compile ({} in {:.2?})",
+                    label, cached, build.time
+                );
+                "does not compile".to_string()
+            }
+        };
+        match tally.iter_mut().find(|(s, _)| *s == status) {
+            Some((_, n)) => *n += 1,
+            None => tally.push((status, 1)),
+        }
+    }
+
+    let hits = builds.iter().filter(|b| b.hit).count();
+    let summary: Vec<String> = tally.iter().map(|(s, n)| format!("{} {}", n, s)).collect();
+    println!("\n{}", summary.join(", "));
+    println!(
+        "cache: {}/{} hits ({:.0}%), build took {:.2?} with {} job(s)",
+        hits,
+        builds.len(),
+        100.0 * hits as f64 / builds.len().max(1) as f64,
+        build_time,
+        jobs
+    );
+    if tally.iter().all(|(s, _)| s == "success") {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn plan(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let mut start = Date::today();
+    let mut ics = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start" => match args.next().map(|s| Date::parse(s)) {
+                Some(Ok(date)) => start = date,
+                Some(Err(e)) => {
+                    eprintln!("--start: {}", e);
+                    return ExitCode::from(2);
+                }
+                None => {
+                    eprintln!("--start needs a date (YYYY-MM-DD)");
+                    return ExitCode::from(2);
+                }
+            },
+            "--ics" => match args.next() {
+                Some(path) => ics = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--ics needs a file");
+                    return ExitCode::from(2);
+                }
+            },
+            other => {
+                eprintln!("unknown option `{}`\n\n{}", other, USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let curriculum = match Curriculum::load(&Curriculum::default_path(), catalog) {
+        Ok(curriculum) => curriculum,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let progress = match Progress::load(&Progress::default_path(catalog.root())) {
+        Ok(progress) => progress,
+        Err(e) => {
+            eprintln!("could not read progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let done = progress
+        .with_prefix("lesson:")
+        .filter(|(_, entry)| entry.status == "done")
+        .map(|(key, _)| key["lesson:".len()..].to_string())
+        .collect();
+    let sessions = match curriculum.schedule(&done, start) {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            eprintln!("{}: {}", Curriculum::default_path().display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if sessions.is_empty() {
+        println!("Everything in the curriculum is done.");
+        return ExitCode::SUCCESS;
+    }
+    print!("{}", curriculum.to_text(&sessions));
+    let minutes: u32 = sessions.iter().map(|s| s.minutes).sum();
+    let lessons = sessions.iter().filter(|s| s.part == 1).count();
+    println!(
+        "\n{} lesson(s), {}h{:02} of study, done on {}",
+        lessons,
+        minutes / 60,
+        minutes % 60,
+        sessions[sessions.len() - 1].date
+    );
+
+    if let Some(path) = ics {
+        let text = curriculum.to_ics(&sessions, date::unix_now());
+        if let Err(e) = std::fs::write(&path, text) {
+            eprintln!("{}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        println!("wrote {}", path.display());
+    }
+    ExitCode::SUCCESS
+}
+
+fn mark_done(catalog: &Catalog, ids: &[String]) -> ExitCode {
+    let curriculum = match Curriculum::load(&Curriculum::default_path(), catalog) {
+        Ok(curriculum) => curriculum,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if ids.is_empty() {
+        eprintln!("usage: lessons done ID...");
+        return ExitCode::from(2);
+    }
+    for id in ids {
+        if !curriculum.items.iter().any(|i| &i.id == id) {
+            eprintln!(
+                "`{}` is not in {}",
+                id,
+                Curriculum::default_path().display()
+            );
+            return ExitCode::from(2);
+        }
+    }
+
+    let mut progress = match Progress::load(&Progress::default_path(catalog.root())) {
+        Ok(progress) => progress,
+        Err(e) => {
+            eprintln!("could not read progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    for id in ids {
+        if let Err(e) = progress.record(&format!("lesson:{}", id), "done") {
+            eprintln!("could not record progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn update_readme(catalog: &Catalog) -> ExitCode {
+    let path = catalog.root().join("README.md");
+    let result = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| Ok((readme::update(&text, catalog)?, text)))
+        .and_then(|(new, old)| {
+            if new != old {
+                std::fs::write(&path, &new).map_err(|e| e.to_string())?;
+            }
+            Ok(new != old)
+        });
+    match result {
+        Ok(true) => {
+            println!("updated {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Ok(false) => {
+            println!("{} is up to date", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Every snippet with `fn main` in `lessons`, with the file it came from.
+fn runnable_snippets<'a>(
+    catalog: &Catalog,
+    lessons: &[&'a Lesson],
+) -> Option<(Vec<Snippet>, Vec<&'a PathBuf>)> {
+    let mut snippets = Vec::new();
+    let mut paths = Vec::new();
+    for lesson in lessons {
+        match catalog.read(lesson) {
+            Ok(src) => {
+                for snippet in snippets::runnable(lesson, &src) {
+                    snippets.push(snippet);
+                    paths.push(&lesson.path);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", lesson.path.display(), e);
+                return None;
+            }
+        }
+    }
+    Some((snippets, paths))
+}
+
+fn badges(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let mut out = catalog.root().join("badges");
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--out", Some(dir)) => out = PathBuf::from(dir),
+            _ => {
+                eprintln!("usage: lessons badges [--out DIR]");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let progress = match Progress::load(&Progress::default_path(catalog.root())) {
+        Ok(progress) => progress,
+        Err(e) => {
+            eprintln!("could not read progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let ours = match ExerciseSet::load_ours(&exercises::default_dir()) {
+        Ok(set) => set,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let lessons: Vec<&Lesson> = catalog
+        .lessons()
+        .iter()
+        .filter(|l| l.pack.is_none())
+        .collect();
+    let studied = lessons
+        .iter()
+        .filter(|l| {
+            progress
+                .get(&format!("lesson:{}", l.id))
+                .is_some_and(|e| e.status == "done")
+        })
+        .count();
+    let passing = ours
+        .exercises
+        .iter()
+        .filter(|ex| {
+            progress
+                .get(&ours.progress_key(ex))
+                .is_some_and(|e| e.status == "passed")
+        })
+        .count();
+
+    // Compiling goes through the snippet cache, so this is quick after a
+    // `lessons run`.
+    let Some((snippets, _)) = runnable_snippets(catalog, &lessons) else {
+        return ExitCode::FAILURE;
+    };
+    let cache_dir = Cache::default_dir();
+    let builds = match Cache::open(&cache_dir)
+        .and_then(|c| c.build_all(&snippets, compile::default_jobs()))
+    {
+        Ok(builds) => builds,
+        Err(e) => {
+            eprintln!("{}: {}", cache_dir.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let compiling = builds.iter().filter(|b| b.binary.is_ok()).count();
+
+    let badges = [
+        (
+            "lessons",
+            Badge::progress("lessons", studied, lessons.len(), "done"),
+        ),
+        (
+            "exercises",
+            Badge::progress("exercises", passing, ours.exercises.len(), "passing"),
+        ),
+        (
+            "snippets",
+            Badge::progress("snippets", compiling, snippets.len(), "compiling"),
+        ),
+    ];
+    if let Err(e) = std::fs::create_dir_all(&out) {
+        eprintln!("{}: {}", out.display(), e);
+        return ExitCode::FAILURE;
+    }
+    for (name, badge) in &badges {
+        let path = out.join(format!("{}.svg", name));
+        if let Err(e) = std::fs::write(&path, badge.to_svg()) {
+            eprintln!("{}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        println!("{:<40} {}: {}", path.display(), badge.label, badge.message);
+    }
+
+    // Only badges inside the repository can be linked from the README.
+    let Ok(dir) = out.strip_prefix(catalog.root()) else {
+        return ExitCode::SUCCESS;
+    };
+    let path = catalog.root().join("README.md");
+    let links = readme::badge_links(&badges, &dir.to_string_lossy());
+    let result = std::fs::read_to_string(&path).and_then(|text| {
+        match readme::replace_section(&text, "badges", &links) {
+            Some(new) if new != text => std::fs::write(&path, new).map(|()| true),
+            _ => Ok(false),
+        }
+    });
+    match result {
+        Ok(true) => {
+            println!("updated {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Ok(false) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// The lessons named by `ids`, or all of them when none are given.
+fn select<'a>(catalog: &'a Catalog, ids: &[&str]) -> Option<Vec<&'a Lesson>> {
+    if ids.is_empty() {
+        return Some(catalog.lessons().iter().collect());
+    }
+    let mut found = Vec::new();
+    for id in ids {
+        match catalog.get(id) {
+            Some(lesson) => found.push(lesson),
+            None => {
+                eprintln!("no lesson with id `{}` (see `lessons list`)", id);
+                return None;
+            }
+        }
+    }
+    Some(found)
+}
+
+fn run_exercises(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let ours = match ExerciseSet::load_ours(&exercises::default_dir()) {
+        Ok(set) => set,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut progress = match Progress::load(&Progress::default_path(catalog.root())) {
+        Ok(progress) => progress,
+        Err(e) => {
+            eprintln!("could not read progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let rest = args.get(1..).unwrap_or_default();
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            let mut table = Table::new(&["Exercise", "Mode", "Status", "Lesson"]);
+            table.set_border(Border::from_env());
+            for ex in &ours.exercises {
+                let status = progress
+                    .get(&ours.progress_key(ex))
+                    .map_or("not started", |e| e.status.as_str());
+                table.push((&ex.name, ex.mode.as_str(), status, &ex.lesson));
+            }
+            print!("{}", table);
+            ExitCode::SUCCESS
+        }
+        Some("hint") => match rest.first().and_then(|name| ours.find(name)) {
+            Some(ex) => {
+                println!("{}", ex.hint.trim_end());
+                if let Some(lesson) = &ex.lesson {
+                    println!("\nlesson: {}", lesson_link(catalog, lesson));
+                }
+                ExitCode::SUCCESS
+            }
+            None => {
+                eprintln!("usage: lessons exercises hint NAME (see `lessons exercises list`)");
+                ExitCode::from(2)
+            }
+        },
+        Some("check") => {
+            for name in rest {
+                if ours.find(name).is_none() {
+                    eprintln!("no exercise named `{}`", name);
+                    return ExitCode::from(2);
+                }
+            }
+            let names: Vec<&str> = rest.iter().map(String::as_str).collect();
+            check_set(&ours, &names, &mut progress)
+        }
+        Some("import") => match rest.first() {
+            Some(dir) => match ExerciseSet::load_rustlings(Path::new(dir)) {
+                Ok(set) => check_set(&set, &[], &mut progress),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    ExitCode::FAILURE
+                }
+            },
+            None => {
+                eprintln!("usage: lessons exercises import DIR");
+                ExitCode::from(2)
+            }
+        },
+        Some("export") => match rest.first() {
+            Some(dir) => match ours.export_rustlings(Path::new(dir)) {
+                Ok(()) => {
+                    println!("wrote {} exercise(s) to {}", ours.exercises.len(), dir);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", dir, e);
+                    ExitCode::FAILURE
+                }
+            },
+            None => {
+                eprintln!("usage: lessons exercises export DIR");
+                ExitCode::from(2)
+            }
+        },
+        Some(other) => {
+            eprintln!("unknown exercises command `{}`\n\n{}", other, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run_pack(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let rest = args.get(1..).unwrap_or_default();
+    let installed = match pack::installed(catalog.root()) {
+        Ok(installed) => installed,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            let mut table = Table::new(&["Pack", "Version", "Lessons", "Description"]);
+            table.set_border(Border::from_env());
+            table.push((
+                pack::OWN_NAME,
+                pack::OWN_VERSION,
+                None::<usize>,
+                "(this repository)",
+            ));
+            for manifest in &installed {
+                table.push((
+                    &manifest.name,
+                    manifest.version.to_string(),
+                    Some(manifest.lessons.len()),
+                    &manifest.description,
+                ));
+            }
+            print!("{}", table);
+            ExitCode::SUCCESS
+        }
+        Some("check") => {
+            let mut failed = false;
+            for manifest in &installed {
+                let others: Vec<_> = installed
+                    .iter()
+                    .filter(|m| m.name != manifest.name)
+                    .cloned()
+                    .collect();
+                for problem in pack::problems(manifest, catalog, &others) {
+                    println!("{}: {}", manifest.name, problem);
+                    failed = true;
+                }
+            }
+            if failed {
+                ExitCode::FAILURE
+            } else {
+                println!("{} pack(s) ok", installed.len());
+                ExitCode::SUCCESS
+            }
+        }
+        Some("import") => {
+            let Some(file) = rest.first() else {
+                eprintln!("usage: lessons pack import FILE");
+                return ExitCode::from(2);
+            };
+            let result = std::fs::read(file)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| Pack::from_bytes(&bytes))
+                .and_then(|pack| {
+                    let dir = pack::install(catalog.root(), &pack, catalog)?;
+                    Ok((pack, dir))
+                });
+            match result {
+                Ok((pack, dir)) => {
+                    println!(
+                        "installed {} {} ({} lesson(s)) in {}",
+                        pack.manifest.name,
+                        pack.manifest.version,
+                        pack.files.len(),
+                        dir.display()
+                    );
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", file, e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Some("export") => export_pack(catalog, rest),
+        Some(other) => {
+            eprintln!("unknown pack command `{}`\n\n{}", other, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn export_pack(catalog: &Catalog, args: &[String]) -> ExitCode {
+    let mut name = pack::OWN_NAME.to_string();
+    let mut version = pack::OWN_VERSION.to_string();
+    let mut description = String::new();
+    let mut dependencies = Vec::new();
+    let mut file = None;
+    let mut ids = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            let value = args.next().cloned();
+            if value.is_none() {
+                eprintln!("{} needs a value", flag);
+            }
+            value
+        };
+        match arg.as_str() {
+            "--name" => match value("--name") {
+                Some(v) => name = v,
+                None => return ExitCode::from(2),
+            },
+            "--version" => match value("--version") {
+                Some(v) => version = v,
+                None => return ExitCode::from(2),
+            },
+            "--description" => match value("--description") {
+                Some(v) => description = v,
+                None => return ExitCode::from(2),
+            },
+            "--requires" => match value("--requires")
+                .as_deref()
+                .and_then(|v| v.split_once('='))
+            {
+                Some((dep, req)) => dependencies.push((dep.to_string(), req.to_string())),
+                None => {
+                    eprintln!("--requires needs NAME=REQUIREMENT, e.g. rust-1-to-100=^0.1");
+                    return ExitCode::from(2);
+                }
+            },
+            other if file.is_none() => file = Some(other.to_string()),
+            other => ids.push(other),
+        }
+    }
+    let Some(file) = file else {
+        eprintln!("usage: lessons pack export FILE [--name N --version V] [ID...]");
+        return ExitCode::from(2);
+    };
+    let Some(version) = Version::parse(&version) else {
+        eprintln!("--version needs a version like 1.0.0");
+        return ExitCode::from(2);
+    };
+    let lessons = if ids.is_empty() {
+        catalog
+            .lessons()
+            .iter()
+            .filter(|l| l.pack.is_none())
+            .collect()
+    } else {
+        match select(catalog, &ids) {
+            Some(lessons) => lessons,
+            None => return ExitCode::from(2),
+        }
+    };
+
+    let result = Pack::from_lessons(
+        catalog,
+        &lessons,
+        &name,
+        version,
+        &description,
+        dependencies,
+    )
+    .and_then(|pack| pack.to_bytes())
+    .and_then(|bytes| std::fs::write(&file, bytes).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => {
+            println!(
+                "wrote {} {} ({} lesson(s)) to {}",
+                name,
+                version,
+                lessons.len(),
+                file
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {}", file, e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Checks the named exercises (all when `names` is empty) and records each
+// outcome in the progress store.
+fn check_set(set: &ExerciseSet, names: &[&str], progress: &mut Progress) -> ExitCode {
+    let work = env::temp_dir().join(format!("lessons-check-{}", std::process::id()));
+    let mut passed = 0;
+    let mut checked = 0;
+    for ex in &set.exercises {
+        if !names.is_empty() && !names.contains(&ex.name.as_str()) {
+            continue;
+        }
+        let outcome = match exercises::check(set, ex, &work) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                eprintln!("{}: {}", set.dir.join(&ex.path).display(), e);
+                return ExitCode::FAILURE;
+            }
+        };
+        checked += 1;
+        if outcome == exercises::Outcome::Passed {
+            passed += 1;
+        }
+        println!("{}/{}: {}", set.source, ex.name, outcome);
+        if let Err(e) = progress.record(&set.progress_key(ex), outcome.status()) {
+            eprintln!("could not record progress: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    let _ = std::fs::remove_dir_all(&work);
+
+    println!("{}/{} passed", passed, checked);
+    if passed == checked {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn lesson_link(catalog: &Catalog, id: &str) -> String {
+    match catalog.get(id) {
+        Some(lesson) => format!("{} ({})", id, lesson.path.display()),
+        None => id.to_string(),
+    }
+}
@@ -0,0 +1,1054 @@
+// Markdown for the terminal: a CommonMark subset parser and a renderer to
+// styled lines.
+//
+// Supported: ATX headings, paragraphs, emphasis, code spans, links and
+// images, bullet and ordered lists (nested by indentation), block quotes,
+// fenced code, GFM tables and thematic breaks. Raw HTML is not rendered:
+// tags are dropped and any text inside them is kept, and
+// `<div align="center">` centres what follows until its `</div>`, which is
+// how the README lays out its header.
+
+use crate::ansi::{self, Line, Span, Style};
+use crate::highlight;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Strong(Vec<Inline>),
+    Emph(Vec<Inline>),
+    Code(String),
+    Link { text: Vec<Inline>, url: String },
+    Image { alt: String, url: String },
+    Break,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading(usize, Vec<Inline>),
+    Paragraph(Vec<Inline>),
+    Quote(Vec<Block>),
+    List {
+        // The first number for ordered lists.
+        start: Option<u64>,
+        items: Vec<Vec<Block>>,
+    },
+    Code {
+        lang: String,
+        text: String,
+    },
+    Table {
+        align: Vec<Align>,
+        header: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+    Rule,
+    Html(String),
+}
+
+pub fn parse(text: &str) -> Vec<Block> {
+    let lines: Vec<String> = text.lines().map(ansi::expand_tabs).collect();
+    parse_lines(&lines)
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+// Removes up to `n` leading spaces.
+fn dedent(line: &str, n: usize) -> String {
+    line[indent(line).min(n)..].to_string()
+}
+
+fn fence(line: &str) -> Option<(char, usize)> {
+    let t = line.trim_start();
+    let c = t.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let n = t.chars().take_while(|&x| x == c).count();
+    (n >= 3).then_some((c, n))
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let t = line.trim_start();
+    let level = t.chars().take_while(|&c| c == '#').count();
+    let rest = &t[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    let text = rest.trim();
+    // A closing run of `#`s is not part of the text.
+    let stripped = text.trim_end_matches('#');
+    let text = if stripped.is_empty() || stripped.ends_with(' ') {
+        stripped.trim_end()
+    } else {
+        text
+    };
+    Some((level, text))
+}
+
+fn is_rule(line: &str) -> bool {
+    let t: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    t.len() >= 3 && ['-', '*', '_'].iter().any(|&c| t.chars().all(|x| x == c))
+}
+
+// (ordered start, width of the marker and the space after it).
+fn list_marker(line: &str) -> Option<(Option<u64>, usize)> {
+    let t = line.trim_start();
+    let after = |n: usize| t[n..].is_empty() || t[n..].starts_with(' ');
+    if t.starts_with(['-', '*', '+']) && after(1) {
+        return Some((None, 2));
+    }
+    let digits = t.chars().take_while(char::is_ascii_digit).count();
+    if (1..=9).contains(&digits) && t[digits..].starts_with(['.', ')']) && after(digits + 1) {
+        return Some((t[..digits].parse().ok(), digits + 2));
+    }
+    None
+}
+
+fn is_html(line: &str) -> bool {
+    let t = line.trim_start();
+    t.starts_with('<')
+        && t[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let t = line.trim();
+    let t = t.strip_prefix('|').unwrap_or(t);
+    let t = t.strip_suffix('|').unwrap_or(t);
+    let mut cells = vec![String::new()];
+    let mut chars = t.chars().peekable();
+    let mut in_code = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push(chars.next().unwrap())
+            }
+            '`' => {
+                in_code = !in_code;
+                cells.last_mut().unwrap().push(c);
+            }
+            '|' if !in_code => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|c| c.trim().to_string()).collect()
+}
+
+fn delimiter_row(line: &str) -> Option<Vec<Align>> {
+    if !line.contains('-') || !line.contains(['|', ':']) && !line.trim().starts_with('-') {
+        return None;
+    }
+    split_row(line)
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Align::Center,
+                (false, true) => Align::Right,
+                _ => Align::Left,
+            })
+        })
+        .collect()
+}
+
+// Whether `line` would start a block other than a paragraph, which ends a
+// paragraph without a blank line in between.
+fn interrupts(line: &str) -> bool {
+    fence(line).is_some()
+        || heading(line).is_some()
+        || line.trim_start().starts_with('>')
+        || is_rule(line)
+        || list_marker(line).is_some_and(|(start, _)| start.is_none_or(|n| n == 1))
+        || is_html(line)
+}
+
+fn parse_lines(lines: &[String]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        let t = line.trim_start();
+        if t.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if let Some((c, n)) = fence(line) {
+            let lang = t.trim_start_matches(c).trim().to_string();
+            let strip = indent(line);
+            let mut text = String::new();
+            i += 1;
+            while i < lines.len() {
+                if fence(&lines[i]).is_some_and(|(d, m)| d == c && m >= n) {
+                    i += 1;
+                    break;
+                }
+                text += &dedent(&lines[i], strip);
+                text.push('\n');
+                i += 1;
+            }
+            blocks.push(Block::Code { lang, text });
+        } else if let Some((level, text)) = heading(line) {
+            blocks.push(Block::Heading(level, inline(text)));
+            i += 1;
+        } else if is_rule(line) {
+            blocks.push(Block::Rule);
+            i += 1;
+        } else if t.starts_with('>') {
+            let mut inner = Vec::new();
+            while i < lines.len() && lines[i].trim_start().starts_with('>') {
+                let rest = &lines[i].trim_start()[1..];
+                inner.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_lines(&inner)));
+        } else if let Some(align) = lines
+            .get(i + 1)
+            .filter(|_| t.contains('|'))
+            .and_then(|next| delimiter_row(next))
+            .filter(|align| align.len() == split_row(line).len())
+        {
+            let header = split_row(line).iter().map(|c| inline(c)).collect();
+            let mut rows = Vec::new();
+            i += 2;
+            while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                let mut cells: Vec<Vec<Inline>> =
+                    split_row(&lines[i]).iter().map(|c| inline(c)).collect();
+                cells.resize(align.len(), Vec::new());
+                rows.push(cells);
+                i += 1;
+            }
+            blocks.push(Block::Table {
+                align,
+                header,
+                rows,
+            });
+        } else if is_html(line) {
+            let mut html = String::new();
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                html += &lines[i];
+                html.push('\n');
+                i += 1;
+            }
+            blocks.push(Block::Html(html));
+        } else if let Some((start, _)) = list_marker(line) {
+            let (block, next) = parse_list(lines, i, start.is_some());
+            blocks.push(block);
+            i = next;
+        } else {
+            let mut text = String::from(t);
+            i += 1;
+            while i < lines.len() && !lines[i].trim().is_empty() && !interrupts(&lines[i]) {
+                text.push('\n');
+                text += lines[i].trim_start();
+                i += 1;
+            }
+            blocks.push(Block::Paragraph(inline(&text)));
+        }
+    }
+    blocks
+}
+
+// A list starting at `lines[i]`; returns it and the index after it.
+fn parse_list(lines: &[String], mut i: usize, ordered: bool) -> (Block, usize) {
+    let start = list_marker(&lines[i]).and_then(|(start, _)| start);
+    let base = indent(&lines[i]);
+    let mut items = Vec::new();
+    while i < lines.len() {
+        let line = &lines[i];
+        let Some((marker, width)) = list_marker(line) else {
+            break;
+        };
+        if marker.is_some() != ordered || indent(line) < base || indent(line) >= base + width.max(2)
+        {
+            break;
+        }
+        let content = indent(line) + width;
+        let mut item = vec![line.get(content..).unwrap_or("").to_string()];
+        i += 1;
+        while i < lines.len() {
+            let next = &lines[i];
+            if next.trim().is_empty() {
+                // A blank line continues the item only if indented content
+                // follows it.
+                let more = lines[i..]
+                    .iter()
+                    .find(|l| !l.trim().is_empty())
+                    .is_some_and(|l| indent(l) >= content);
+                if !more {
+                    break;
+                }
+                item.push(String::new());
+            } else if indent(next) >= content {
+                item.push(dedent(next, content));
+            } else if list_marker(next).is_none()
+                && !interrupts(next)
+                && item.last().is_some_and(|l| !l.trim().is_empty())
+            {
+                // Lazy continuation of the item's paragraph.
+                item.push(next.trim_start().to_string());
+            } else {
+                break;
+            }
+            i += 1;
+        }
+        items.push(parse_lines(&item));
+        // Blank lines between items keep the list going.
+        let mut j = i;
+        while j < lines.len() && lines[j].trim().is_empty() {
+            j += 1;
+        }
+        if j > i && j < lines.len() && list_marker(&lines[j]).is_some() && indent(&lines[j]) >= base
+        {
+            i = j;
+        }
+    }
+    (Block::List { start, items }, i)
+}
+
+// Parses inline markup in `text`. Unmatched delimiters stay as text.
+pub fn inline(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = Vec::new();
+    let mut buf = String::new();
+    let mut i = 0;
+    let flush = |buf: &mut String, out: &mut Vec<Inline>| {
+        if !buf.is_empty() {
+            out.push(Inline::Text(std::mem::take(buf)));
+        }
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if chars.get(i + 1).is_some_and(char::is_ascii_punctuation) => {
+                buf.push(chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            '\n' => {
+                flush(&mut buf, &mut out);
+                // Two trailing spaces or a backslash make a hard break.
+                if text_ends_with(&out, "  ") {
+                    trim_last(&mut out);
+                    out.push(Inline::Break);
+                } else {
+                    trim_last(&mut out);
+                    out.push(Inline::Text(" ".to_string()));
+                }
+                i += 1;
+                while chars.get(i) == Some(&' ') {
+                    i += 1;
+                }
+                continue;
+            }
+            '`' => {
+                let n = run(&chars, i, '`');
+                if let Some(end) = find_run(&chars, i + n, '`', n) {
+                    flush(&mut buf, &mut out);
+                    let code: String = chars[i + n..end].iter().collect();
+                    let code = code.replace('\n', " ");
+                    let code = match code.strip_prefix(' ').and_then(|c| c.strip_suffix(' ')) {
+                        Some(inner) if !inner.trim().is_empty() => inner.to_string(),
+                        _ => code,
+                    };
+                    out.push(Inline::Code(code));
+                    i = end + n;
+                    continue;
+                }
+                buf.extend(&chars[i..i + n]);
+                i += n;
+                continue;
+            }
+            '!' if chars.get(i + 1) == Some(&'[') => {
+                if let Some((label, url, end)) = link(&chars, i + 1) {
+                    flush(&mut buf, &mut out);
+                    out.push(Inline::Image {
+                        alt: plain_text(&inline(&label)),
+                        url,
+                    });
+                    i = end;
+                    continue;
+                }
+            }
+            '[' => {
+                if let Some((label, url, end)) = link(&chars, i) {
+                    flush(&mut buf, &mut out);
+                    out.push(Inline::Link {
+                        text: inline(&label),
+                        url,
+                    });
+                    i = end;
+                    continue;
+                }
+            }
+            '*' | '_' => {
+                let n = run(&chars, i, c).min(2);
+                let opens = chars.get(i + n).is_some_and(|c| !c.is_whitespace())
+                    // `snake_case` is not emphasis.
+                    && (c == '*' || i == 0 || !chars[i - 1].is_alphanumeric());
+                if opens && let Some(end) = closing(&chars, i + n, c, n) {
+                    flush(&mut buf, &mut out);
+                    let inner: String = chars[i + n..end].iter().collect();
+                    out.push(if n == 2 {
+                        Inline::Strong(inline(&inner))
+                    } else {
+                        Inline::Emph(inline(&inner))
+                    });
+                    i = end + n;
+                    continue;
+                }
+                buf.extend(&chars[i..i + n]);
+                i += n;
+                continue;
+            }
+            '<' => {
+                if let Some(len) = chars[i..].iter().position(|&c| c == '>') {
+                    let tag: String = chars[i + 1..i + len].iter().collect();
+                    if tag.starts_with("http://") || tag.starts_with("https://") {
+                        flush(&mut buf, &mut out);
+                        out.push(Inline::Link {
+                            text: vec![Inline::Text(tag.clone())],
+                            url: tag,
+                        });
+                        i += len + 1;
+                        continue;
+                    }
+                    let name = tag.trim_start_matches('/');
+                    if name.starts_with(|c: char| c.is_ascii_alphabetic()) && !tag.contains('<') {
+                        // Inline HTML: `<br>` breaks the line, other tags
+                        // are dropped and their text kept.
+                        flush(&mut buf, &mut out);
+                        if name.starts_with("br") {
+                            out.push(Inline::Break);
+                        }
+                        i += len + 1;
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        buf.push(c);
+        i += 1;
+    }
+    flush(&mut buf, &mut out);
+    if text.ends_with('\\') {
+        trim_last(&mut out);
+    }
+    out
+}
+
+fn run(chars: &[char], i: usize, c: char) -> usize {
+    chars[i..].iter().take_while(|&&x| x == c).count()
+}
+
+// The start of the next run of exactly `n` `c`s at or after `from`.
+fn find_run(chars: &[char], from: usize, c: char, n: usize) -> Option<usize> {
+    let mut i = from;
+    while i < chars.len() {
+        if chars[i] == c {
+            let len = run(chars, i, c);
+            if len == n {
+                return Some(i);
+            }
+            i += len;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+// A closing emphasis delimiter: `n` `c`s not preceded by whitespace. Code
+// spans are skipped.
+fn closing(chars: &[char], from: usize, c: char, n: usize) -> Option<usize> {
+    let mut i = from;
+    while i < chars.len() {
+        match chars[i] {
+            '`' => {
+                let len = run(chars, i, '`');
+                i = find_run(chars, i + len, '`', len).map_or(i + len, |end| end + len);
+            }
+            x if x == c => {
+                let len = run(chars, i, c);
+                let after_ok = c == '*' || chars.get(i + len).is_none_or(|c| !c.is_alphanumeric());
+                if len >= n && i > from && !chars[i - 1].is_whitespace() && after_ok {
+                    // `***a***` closes the strong run after the emphasis.
+                    return Some(if n == 2 { i + len - 2 } else { i });
+                }
+                i += len;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+// `[label](url)` starting at `chars[i] == '['`: label, url and the index
+// after the closing parenthesis.
+fn link(chars: &[char], i: usize) -> Option<(String, String, usize)> {
+    let mut depth = 0;
+    let mut close = None;
+    for (j, &c) in chars.iter().enumerate().skip(i) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(j);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 2 + chars[close + 2..].iter().position(|&c| c == ')')?;
+    let label = chars[i + 1..close].iter().collect();
+    let target: String = chars[close + 2..end].iter().collect();
+    // A title (`"..."`) after the URL is ignored.
+    let url = target.split_whitespace().next().unwrap_or("");
+    let url = url
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string();
+    Some((label, url, end + 1))
+}
+
+fn text_ends_with(out: &[Inline], suffix: &str) -> bool {
+    matches!(out.last(), Some(Inline::Text(t)) if t.ends_with(suffix))
+}
+
+fn trim_last(out: &mut Vec<Inline>) {
+    if let Some(Inline::Text(t)) = out.last_mut() {
+        let len = t.trim_end_matches([' ', '\\']).len();
+        t.truncate(len);
+        if t.is_empty() {
+            out.pop();
+        }
+    }
+}
+
+pub fn plain_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|i| match i {
+            Inline::Text(t) | Inline::Code(t) => t.clone(),
+            Inline::Strong(inner) | Inline::Emph(inner) | Inline::Link { text: inner, .. } => {
+                plain_text(inner)
+            }
+            Inline::Image { alt, .. } => alt.clone(),
+            Inline::Break => " ".to_string(),
+        })
+        .collect()
+}
+
+// Styled spans for inline content, split into lines at hard breaks.
+pub fn spans(inlines: &[Inline], style: Style) -> Vec<Line> {
+    let mut lines = vec![Vec::new()];
+    add_spans(inlines, style, &mut lines);
+    lines
+}
+
+fn add_spans(inlines: &[Inline], style: Style, lines: &mut Vec<Line>) {
+    let combine = |a: Style, b: Style| match (a, b) {
+        (Style::Bold | Style::Heading, Style::Italic) | (Style::Italic, Style::Bold) => {
+            Style::BoldItalic
+        }
+        (Style::Heading, _) => Style::Heading,
+        _ => b,
+    };
+    for inline in inlines {
+        match inline {
+            Inline::Text(t) => lines.last_mut().unwrap().push(Span::new(style, t.clone())),
+            Inline::Code(t) => lines
+                .last_mut()
+                .unwrap()
+                .push(Span::new(Style::Code, t.clone())),
+            Inline::Strong(inner) => add_spans(inner, combine(style, Style::Bold), lines),
+            Inline::Emph(inner) => add_spans(inner, combine(style, Style::Italic), lines),
+            Inline::Link { text, .. } => add_spans(text, combine(style, Style::Link), lines),
+            // Images cannot be shown, so they look like the badges most of
+            // ours are.
+            Inline::Image { alt, .. } => lines
+                .last_mut()
+                .unwrap()
+                .push(Span::new(Style::Reverse, format!(" {} ", alt))),
+            Inline::Break => lines.push(Vec::new()),
+        }
+    }
+}
+
+// Renders for a terminal `width` columns wide.
+pub fn render(blocks: &[Block], width: usize) -> Vec<Line> {
+    let mut center = false;
+    let mut out = Vec::new();
+    render_blocks(blocks, width.max(20), false, &mut center, &mut out);
+    out
+}
+
+fn render_blocks(
+    blocks: &[Block],
+    width: usize,
+    tight: bool,
+    center: &mut bool,
+    out: &mut Vec<Line>,
+) {
+    for block in blocks {
+        let mut lines = render_block(block, width, center);
+        if lines.is_empty() {
+            continue;
+        }
+        if !tight && !out.is_empty() {
+            out.push(Vec::new());
+        }
+        if *center {
+            for line in &mut lines {
+                let pad = width.saturating_sub(ansi::line_width(line)) / 2;
+                if pad > 0 {
+                    line.insert(0, Span::new(Style::Plain, " ".repeat(pad)));
+                }
+            }
+        }
+        out.append(&mut lines);
+    }
+}
+
+fn render_block(block: &Block, width: usize, center: &mut bool) -> Vec<Line> {
+    let wrapped = |inlines: &[Inline], style: Style, width: usize| -> Vec<Line> {
+        spans(inlines, style)
+            .iter()
+            .flat_map(|line| ansi::wrap(line, width))
+            .collect()
+    };
+    match block {
+        Block::Heading(level, text) => {
+            let style = if *level <= 2 {
+                Style::Heading
+            } else {
+                Style::Bold
+            };
+            let mut lines = wrapped(text, style, width);
+            let rule = match level {
+                1 => '═',
+                2 => '─',
+                _ => return lines,
+            };
+            let len = lines.iter().map(|l| ansi::line_width(l)).max().unwrap_or(0);
+            lines.push(vec![Span::new(Style::Dim, rule.to_string().repeat(len))]);
+            lines
+        }
+        Block::Paragraph(text) => wrapped(text, Style::Plain, width),
+        Block::Rule => vec![vec![Span::new(Style::Dim, "─".repeat(width))]],
+        Block::Quote(inner) => {
+            let mut lines = Vec::new();
+            render_blocks(
+                inner,
+                width.saturating_sub(2).max(1),
+                false,
+                &mut false,
+                &mut lines,
+            );
+            lines
+                .into_iter()
+                .map(|mut line| {
+                    line.insert(0, Span::new(Style::Dim, "│ "));
+                    line
+                })
+                .collect()
+        }
+        Block::List { start, items } => {
+            let markers: Vec<String> = (0..items.len())
+                .map(|k| match start {
+                    Some(n) => format!("{}. ", n + k as u64),
+                    None => "• ".to_string(),
+                })
+                .collect();
+            let marker_width = markers.iter().map(|m| ansi::width(m)).max().unwrap_or(2);
+            let mut lines = Vec::new();
+            for (item, marker) in items.iter().zip(markers) {
+                let mut inner = Vec::new();
+                render_blocks(
+                    item,
+                    width.saturating_sub(marker_width).max(1),
+                    true,
+                    &mut false,
+                    &mut inner,
+                );
+                if inner.is_empty() {
+                    inner.push(Vec::new());
+                }
+                for (k, mut line) in inner.into_iter().enumerate() {
+                    let prefix = if k == 0 {
+                        format!("{:>w$}", marker, w = marker_width)
+                    } else {
+                        " ".repeat(marker_width)
+                    };
+                    line.insert(0, Span::new(Style::Bold, prefix));
+                    lines.push(line);
+                }
+            }
+            lines
+        }
+        Block::Code { lang, text } => {
+            let code: Vec<Line> = match lang.as_str() {
+                "rust" | "rs" => highlight::to_lines(text),
+                _ => text
+                    .lines()
+                    .map(|l| vec![Span::new(Style::Code, ansi::expand_tabs(l))])
+                    .collect(),
+            };
+            let longest = code.iter().map(|l| ansi::line_width(l)).max().unwrap_or(0);
+            let label = if lang.is_empty() {
+                String::new()
+            } else {
+                format!(" {} ", lang)
+            };
+            let inner = longest
+                .max(ansi::width(&label) + 1)
+                .min(width.saturating_sub(4).max(1));
+            let top = format!(
+                "┌─{}{}┐",
+                label,
+                "─".repeat((inner + 1).saturating_sub(ansi::width(&label)))
+            );
+            let mut lines = vec![vec![Span::new(Style::Dim, top)]];
+            for line in code {
+                let mut row = vec![Span::new(Style::Dim, "│ ")];
+                row.extend(pad(&ansi::truncate(&line, inner), inner, Align::Left));
+                row.push(Span::new(Style::Dim, " │"));
+                lines.push(row);
+            }
+            lines.push(vec![Span::new(
+                Style::Dim,
+                format!("└{}┘", "─".repeat(inner + 2)),
+            )]);
+            lines
+        }
+        Block::Table {
+            align,
+            header,
+            rows,
+        } => table(align, header, rows, width),
+        Block::Html(html) => {
+            let lower = html.to_lowercase();
+            let opens = lower.contains("align=\"center\"") || lower.contains("<center>");
+            let closes =
+                lower.contains("</div>") || lower.contains("</center>") || lower.contains("</p>");
+            let text = strip_tags(html);
+            let lines = text
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .flat_map(|l| ansi::wrap(&[Span::new(Style::Plain, l.trim())], width))
+                .collect();
+            // `<div align="center">…</div>` on one block centres nothing
+            // after it.
+            if opens && !closes {
+                *center = true;
+            } else if closes && !opens {
+                *center = false;
+            }
+            lines
+        }
+    }
+}
+
+fn pad(line: &[Span], width: usize, align: Align) -> Line {
+    let room = width.saturating_sub(ansi::line_width(line));
+    let (left, right) = match align {
+        Align::Left => (0, room),
+        Align::Right => (room, 0),
+        Align::Center => (room / 2, room - room / 2),
+    };
+    let mut out = vec![Span::new(Style::Plain, " ".repeat(left))];
+    out.extend(line.iter().cloned());
+    out.push(Span::new(Style::Plain, " ".repeat(right)));
+    out
+}
+
+fn table(
+    align: &[Align],
+    header: &[Vec<Inline>],
+    rows: &[Vec<Vec<Inline>>],
+    width: usize,
+) -> Vec<Line> {
+    let cell = |inlines: &[Inline], style: Style| -> Line {
+        spans(inlines, style)
+            .into_iter()
+            .reduce(|mut a, b| {
+                a.push(Span::new(style, " "));
+                a.extend(b);
+                a
+            })
+            .unwrap_or_default()
+    };
+    let header: Vec<Line> = header.iter().map(|c| cell(c, Style::Bold)).collect();
+    let rows: Vec<Vec<Line>> = rows
+        .iter()
+        .map(|row| row.iter().map(|c| cell(c, Style::Plain)).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = (0..align.len())
+        .map(|k| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .filter_map(|row| row.get(k))
+                .map(|c| ansi::line_width(c))
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        })
+        .collect();
+    // Borders take `│ ` + ` │ ` between columns + ` │`.
+    let available = width.saturating_sub(3 * align.len() + 1);
+    while widths.iter().sum::<usize>() > available {
+        let widest = (0..widths.len()).max_by_key(|&k| widths[k]).unwrap();
+        if widths[widest] <= 3 {
+            break;
+        }
+        widths[widest] -= 1;
+    }
+
+    let border = |left: &str, mid: &str, right: &str| -> Line {
+        let parts: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        vec![Span::new(
+            Style::Dim,
+            format!("{}{}{}", left, parts.join(mid), right),
+        )]
+    };
+    let row_line = |row: &[Line]| -> Line {
+        let mut line = vec![Span::new(Style::Dim, "│ ")];
+        for (k, w) in widths.iter().enumerate() {
+            if k > 0 {
+                line.push(Span::new(Style::Dim, " │ "));
+            }
+            let content = row.get(k).cloned().unwrap_or_default();
+            line.extend(pad(&ansi::truncate(&content, *w), *w, align[k]));
+        }
+        line.push(Span::new(Style::Dim, " │"));
+        line
+    };
+    let mut lines = vec![
+        border("┌", "┬", "┐"),
+        row_line(&header),
+        border("├", "┼", "┤"),
+    ];
+    lines.extend(rows.iter().map(|r| row_line(r)));
+    lines.push(border("└", "┴", "┘"));
+    lines
+}
+
+// Text outside tags and comments.
+fn strip_tags(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out += &rest[..start];
+        let end = if rest[start..].starts_with("<!--") {
+            rest[start..].find("-->").map(|e| start + e + 3)
+        } else {
+            rest[start..].find('>').map(|e| start + e + 1)
+        };
+        match end {
+            Some(end) => rest = &rest[end..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    out += rest;
+    out
+}
+
+// Terminal output: styled when `color`, plain text otherwise.
+pub fn to_string(lines: &[Line], color: bool) -> String {
+    let mut out = String::new();
+    for line in lines {
+        let text = if color {
+            ansi::to_ansi(line)
+        } else {
+            ansi::text(line)
+        };
+        out += text.trim_end();
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string())
+    }
+
+    #[test]
+    fn parses_inline_markup() {
+        assert_eq!(
+            inline("a **bold** and *it* `x*y` [link](http://x \"t\")"),
+            [
+                text("a "),
+                Inline::Strong(vec![text("bold")]),
+                text(" and "),
+                Inline::Emph(vec![text("it")]),
+                text(" "),
+                Inline::Code("x*y".to_string()),
+                text(" "),
+                Inline::Link {
+                    text: vec![text("link")],
+                    url: "http://x".to_string()
+                },
+            ]
+        );
+        // A badge: an image inside a link.
+        assert_eq!(
+            inline("[![Rust](https://img/rust.svg)](https://rust-lang.org)"),
+            [Inline::Link {
+                text: vec![Inline::Image {
+                    alt: "Rust".to_string(),
+                    url: "https://img/rust.svg".to_string()
+                }],
+                url: "https://rust-lang.org".to_string()
+            }]
+        );
+        assert_eq!(
+            inline("snake_case_name and 2 * 3"),
+            [text("snake_case_name and 2 * 3")]
+        );
+        assert_eq!(inline("a\\*b\\*"), [text("a*b*")]);
+        assert_eq!(
+            inline("one  \ntwo"),
+            [text("one"), Inline::Break, text("two")]
+        );
+        assert_eq!(inline("x<br>y"), [text("x"), Inline::Break, text("y")]);
+    }
+
+    #[test]
+    fn parses_blocks() {
+        let doc = "\
+# Title #
+
+Some *text*
+over two lines.
+
+- one
+- two
+  - nested
+
+  more of two
+3. three
+
+| Name | Count |
+|:-----|------:|
+| a \\| b | 1 |
+
+```rust
+fn main() {}
+```
+> quoted
+---
+<div align=\"center\">
+";
+        let blocks = parse(doc);
+        assert_eq!(blocks[0], Block::Heading(1, vec![text("Title")]));
+        assert!(matches!(&blocks[1], Block::Paragraph(p) if p.len() == 4));
+        let Block::List { start: None, items } = &blocks[2] else {
+            panic!("{:?}", blocks[2]);
+        };
+        assert_eq!(items.len(), 2);
+        assert!(matches!(&items[1][1], Block::List { items, .. } if items.len() == 1));
+        assert!(matches!(&items[1][2], Block::Paragraph(_)));
+        assert!(matches!(&blocks[3], Block::List { start: Some(3), .. }));
+        let Block::Table { align, rows, .. } = &blocks[4] else {
+            panic!("{:?}", blocks[4]);
+        };
+        assert_eq!(align, &[Align::Left, Align::Right]);
+        assert_eq!(rows[0][0], [text("a | b")]);
+        assert_eq!(
+            blocks[5],
+            Block::Code {
+                lang: "rust".to_string(),
+                text: "fn main() {}\n".to_string()
+            }
+        );
+        assert!(matches!(&blocks[6], Block::Quote(q) if q.len() == 1));
+        assert_eq!(blocks[7], Block::Rule);
+        assert!(matches!(&blocks[8], Block::Html(_)));
+    }
+
+    #[test]
+    fn renders_for_the_terminal() {
+        let doc = "\
+<div align=\"center\">
+
+**Centred**
+
+</div>
+
+## Files
+
+| Directory | Files |
+|-----------|------:|
+| examples/ | 5 |
+
+1. first item that is long enough to wrap around
+2. second
+
+```
+let x = 1;
+```
+";
+        let out = to_string(&render(&parse(doc), 30), false);
+        let expected = [
+            "           Centred",
+            "",
+            "Files",
+            "─────",
+            "",
+            "┌───────────┬───────┐",
+            "│ Directory │ Files │",
+            "├───────────┼───────┤",
+            "│ examples/ │     5 │",
+            "└───────────┴───────┘",
+            "",
+            "1. first item that is long",
+            "   enough to wrap around",
+            "2. second",
+            "",
+            "┌────────────┐",
+            "│ let x = 1; │",
+            "└────────────┘",
+        ];
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+        let colored = to_string(&render(&parse("**bold**"), 30), true);
+        assert!(colored.contains("\x1b[0;1mbold"));
+    }
+
+    #[test]
+    fn renders_the_repository_docs() {
+        let root = crate::catalog::default_root();
+        for doc in ["README.md", "LEARNING_LOG.md"] {
+            let text = std::fs::read_to_string(root.join(doc)).unwrap();
+            for width in [40, 100] {
+                for line in render(&parse(&text), width) {
+                    assert!(ansi::line_width(&line) <= width, "{}: {:?}", doc, line);
+                }
+            }
+        }
+    }
+}
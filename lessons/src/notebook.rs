@@ -0,0 +1,358 @@
+// Exports lessons as Jupyter notebooks (nbformat 4.5) for the evcxr kernel.
+//
+// Prose blocks become markdown cells and code blocks become Rust code
+// cells. Where a golden stdout exists for a block it is attached as the
+// cell's output, so the notebook reads well before anyone runs it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::blocks::{self, BlockKind};
+use crate::catalog::{Catalog, Lesson};
+use crate::golden::Golden;
+use crate::json::Value;
+
+pub fn from_lesson(lesson: &Lesson, src: &str, golden: &Golden) -> Value {
+    let mut cells = vec![markdown_cell(
+        "title",
+        &format!("# {}\n\n`{}`\n", lesson.title, lesson.path.display()),
+    )];
+    let mut code_no = 0;
+    let mut executed = 0;
+
+    for (i, block) in blocks::split(src).iter().enumerate() {
+        let id = format!("cell-{}", i + 1);
+        match block.kind {
+            BlockKind::Prose => cells.push(markdown_cell(&id, &prose_to_markdown(&block.text))),
+            BlockKind::Code => {
+                code_no += 1;
+                let stdout = golden.stdout(&lesson.id, code_no);
+                if stdout.is_some() {
+                    executed += 1;
+                }
+                cells.push(code_cell(&id, &evcxr_source(&block.text), stdout, executed));
+            }
+        }
+    }
+
+    Value::object([
+        ("cells", Value::Array(cells)),
+        (
+            "metadata",
+            Value::object([
+                (
+                    "kernelspec",
+                    Value::object([
+                        ("display_name", "Rust".into()),
+                        ("language", "rust".into()),
+                        ("name", "rust".into()),
+                    ]),
+                ),
+                (
+                    "language_info",
+                    Value::object([
+                        ("codemirror_mode", "rust".into()),
+                        ("file_extension", ".rs".into()),
+                        ("mimetype", "text/rust".into()),
+                        ("name", "Rust".into()),
+                        ("pygments_lexer", "rust".into()),
+                    ]),
+                ),
+            ]),
+        ),
+        ("nbformat", 4usize.into()),
+        ("nbformat_minor", 5usize.into()),
+    ])
+}
+
+// Writes `<out>/<lesson id>.ipynb` and returns the path.
+pub fn export(
+    catalog: &Catalog,
+    lesson: &Lesson,
+    golden: &Golden,
+    out: &Path,
+) -> io::Result<PathBuf> {
+    let src = catalog.read(lesson)?;
+    let path = out.join(format!("{}.ipynb", lesson.id));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut json = from_lesson(lesson, &src, golden).to_pretty(1);
+    json.push('\n');
+    fs::write(&path, json)?;
+    Ok(path)
+}
+
+fn markdown_cell(id: &str, text: &str) -> Value {
+    Value::object([
+        ("cell_type", "markdown".into()),
+        ("id", id.into()),
+        ("metadata", Value::object::<&str>([])),
+        ("source", source_lines(text)),
+    ])
+}
+
+fn code_cell(id: &str, code: &str, stdout: Option<String>, count: usize) -> Value {
+    let (execution_count, outputs) = match stdout {
+        Some(text) => (
+            count.into(),
+            vec![Value::object([
+                ("name", "stdout".into()),
+                ("output_type", "stream".into()),
+                ("text", source_lines(&text)),
+            ])],
+        ),
+        None => (Value::Null, Vec::new()),
+    };
+    Value::object([
+        ("cell_type", "code".into()),
+        ("execution_count", execution_count),
+        ("id", id.into()),
+        ("metadata", Value::object::<&str>([])),
+        ("outputs", Value::Array(outputs)),
+        ("source", source_lines(code)),
+    ])
+}
+
+// nbformat stores multi-line strings as a list of lines, each keeping its
+// `\n` except the last.
+fn source_lines(text: &str) -> Value {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    Value::Array(text.split_inclusive('\n').map(Value::from).collect())
+}
+
+// evcxr runs cells as statements and never calls `main` itself.
+fn evcxr_source(code: &str) -> String {
+    let defines_main = code
+        .lines()
+        .any(|l| l.starts_with("fn main(") || l.starts_with("pub fn main("));
+    if defines_main {
+        format!("{}main();\n", code)
+    } else {
+        code.to_string()
+    }
+}
+
+// The prose is plain text with meaningful line breaks. Keep the breaks
+// (two trailing spaces) and stop `<T>` from being read as HTML, but leave
+// fenced blocks from other languages alone.
+fn prose_to_markdown(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            out.push_str(line);
+        } else if in_fence || line.trim().is_empty() {
+            out.push_str(line);
+        } else {
+            out.push_str(&line.replace('&', "&amp;").replace('<', "&lt;"));
+            out.push_str("  ");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::{self, Catalog};
+    use crate::json::{self, Value};
+    use crate::test_util::TempDir;
+
+    const SAMPLE: &str = "\
+What is a HashMap
+A HashMap<K, V> stores key/value pairs.
+
+use std::collections::HashMap;
+
+fn main() {
+    let mut scores = HashMap::new();
+    scores.insert(\"Alice\", 95);
+    println!(\"{:?}\", scores.get(\"Alice\"));
+}
+
+output Some(95)
+";
+
+    fn lesson() -> Lesson {
+        Lesson {
+            id: "test/sample".to_string(),
+            category: "test".to_string(),
+            title: "sample".to_string(),
+            path: PathBuf::from("sample.rs"),
+            pack: None,
+        }
+    }
+
+    fn golden_with(stdout: &str) -> (Golden, TempDir) {
+        let dir = TempDir::new("golden");
+        let golden = Golden::new(dir.path());
+        let path = golden.path("test/sample", 1);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, stdout).unwrap();
+        (golden, dir)
+    }
+
+    fn is_multiline(v: &Value) -> bool {
+        v.as_str().is_some()
+            || v.as_array()
+                .is_some_and(|lines| lines.iter().all(|l| l.as_str().is_some()))
+    }
+
+    // The parts of the nbformat v4 JSON schema that Jupyter enforces.
+    fn validate(nb: &Value) -> Result<(), String> {
+        if nb.get("nbformat").and_then(Value::as_f64) != Some(4.0) {
+            return Err("nbformat must be 4".into());
+        }
+        let minor = nb
+            .get("nbformat_minor")
+            .and_then(Value::as_f64)
+            .ok_or("missing nbformat_minor")?;
+        let metadata = nb.get("metadata").ok_or("missing metadata")?;
+        let kernelspec = metadata.get("kernelspec").ok_or("missing kernelspec")?;
+        for key in ["name", "display_name"] {
+            kernelspec
+                .get(key)
+                .and_then(Value::as_str)
+                .ok_or(format!("kernelspec.{} must be a string", key))?;
+        }
+
+        let cells = nb
+            .get("cells")
+            .and_then(Value::as_array)
+            .ok_or("cells must be an array")?;
+        let mut ids = Vec::new();
+        for (i, cell) in cells.iter().enumerate() {
+            let fail = |msg: &str| format!("cell {}: {}", i, msg);
+            if !cell.get("metadata").is_some_and(Value::is_object) {
+                return Err(fail("metadata must be an object"));
+            }
+            if !cell.get("source").is_some_and(is_multiline) {
+                return Err(fail("source must be a string or list of strings"));
+            }
+            if minor >= 5.0 {
+                let id = cell
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| fail("missing id"))?;
+                let valid = !id.is_empty()
+                    && id.len() <= 64
+                    && id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+                if !valid || ids.contains(&id) {
+                    return Err(fail("id must be unique and match [a-zA-Z0-9-_]{1,64}"));
+                }
+                ids.push(id);
+            }
+            match cell.get("cell_type").and_then(Value::as_str) {
+                Some("markdown") | Some("raw") => {
+                    if cell.get("outputs").is_some() || cell.get("execution_count").is_some() {
+                        return Err(fail("only code cells have outputs"));
+                    }
+                }
+                Some("code") => {
+                    match cell.get("execution_count") {
+                        Some(Value::Null) => {}
+                        Some(Value::Number(n)) if n.fract() == 0.0 && *n >= 0.0 => {}
+                        _ => return Err(fail("execution_count must be null or an integer")),
+                    }
+                    let outputs = cell
+                        .get("outputs")
+                        .and_then(Value::as_array)
+                        .ok_or_else(|| fail("outputs must be an array"))?;
+                    for out in outputs {
+                        match out.get("output_type").and_then(Value::as_str) {
+                            Some("stream") => {
+                                if !matches!(
+                                    out.get("name").and_then(Value::as_str),
+                                    Some("stdout" | "stderr")
+                                ) {
+                                    return Err(fail("stream name must be stdout or stderr"));
+                                }
+                                if !out.get("text").is_some_and(is_multiline) {
+                                    return Err(fail(
+                                        "stream text must be a string or list of strings",
+                                    ));
+                                }
+                            }
+                            Some("display_data" | "execute_result" | "error") => {}
+                            _ => return Err(fail("unknown output_type")),
+                        }
+                    }
+                }
+                _ => return Err(fail("unknown cell_type")),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sample_lesson_is_valid_nbformat() {
+        let nb = from_lesson(&lesson(), SAMPLE, &Golden::new(Path::new("/nonexistent")));
+        let parsed = json::parse(&nb.to_pretty(1)).expect("exporter wrote invalid JSON");
+        validate(&parsed).unwrap();
+
+        let cells = parsed.get("cells").and_then(Value::as_array).unwrap();
+        let types: Vec<&str> = cells
+            .iter()
+            .map(|c| c.get("cell_type").and_then(Value::as_str).unwrap())
+            .collect();
+        assert_eq!(types, ["markdown", "markdown", "code", "markdown"]);
+
+        let language = parsed
+            .get("metadata")
+            .and_then(|m| m.get("language_info"))
+            .unwrap();
+        assert_eq!(
+            language.get("pygments_lexer").and_then(Value::as_str),
+            Some("rust")
+        );
+        assert_eq!(language.get("pygment_lexer"), None);
+    }
+
+    #[test]
+    fn code_cells_call_main_for_evcxr() {
+        let nb = from_lesson(&lesson(), SAMPLE, &Golden::new(Path::new("/nonexistent")));
+        let code = &nb.get("cells").and_then(Value::as_array).unwrap()[2];
+        let source: String = code
+            .get("source")
+            .and_then(Value::as_array)
+            .unwrap()
+            .iter()
+            .map(|l| l.as_str().unwrap())
+            .collect();
+        assert!(source.starts_with("use std::collections::HashMap;\n"));
+        assert!(source.ends_with("}\nmain();"));
+    }
+
+    #[test]
+    fn golden_stdout_fills_the_output() {
+        let (golden, _dir) = golden_with("Some(95)\n");
+        let nb = json::parse(&from_lesson(&lesson(), SAMPLE, &golden).to_compact()).unwrap();
+        validate(&nb).unwrap();
+
+        let code = &nb.get("cells").and_then(Value::as_array).unwrap()[2];
+        assert_eq!(code.get("execution_count"), Some(&Value::Number(1.0)));
+        let output = &code.get("outputs").and_then(Value::as_array).unwrap()[0];
+        assert_eq!(
+            output.get("text"),
+            Some(&Value::Array(vec!["Some(95)".into()]))
+        );
+    }
+
+    #[test]
+    fn every_lesson_exports_valid_nbformat() {
+        let catalog = Catalog::discover(&catalog::default_root()).unwrap();
+        let golden = Golden::new(&Golden::default_dir());
+        for lesson in catalog.lessons() {
+            let src = catalog.read(lesson).unwrap();
+            let text = from_lesson(lesson, &src, &golden).to_pretty(1);
+            let nb = json::parse(&text).unwrap_or_else(|e| panic!("{}: {}", lesson.id, e));
+            validate(&nb).unwrap_or_else(|e| panic!("{}: {}", lesson.id, e));
+        }
+    }
+}
@@ -0,0 +1,570 @@
+// Lesson packs: a way to share lessons between teams without forking.
+//
+// A pack is a ustar archive (`.lpack`) holding `pack.toml` and the lesson
+// files it lists:
+//
+//   format = 1
+//   [pack]
+//   name = "team-async"
+//   version = "1.2.0"
+//   [dependencies]
+//   rust-1-to-100 = "^0.1"
+//   [[lesson]]
+//   id = "team-async/futures"
+//   title = "Futures"
+//   path = "lessons/team-async/futures.rs"
+//   sha256 = "…"
+//
+// Imported packs live unpacked under `<root>/packs/<name>/`, where the
+// catalog picks their lessons up. This repository's own notes count as the
+// pack `rust-1-to-100`, so other packs can depend on them.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::catalog::{Catalog, Lesson};
+use crate::sha256;
+use crate::tar::{self, Entry};
+use crate::toml::{self, Value};
+
+// The newest manifest format this code understands.
+pub const FORMAT: i64 = 1;
+pub const OWN_NAME: &str = "rust-1-to-100";
+pub const OWN_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const MANIFEST: &str = "pack.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    // `1.2.3`; `1.2` and `1` fill in zeros.
+    pub fn parse(s: &str) -> Option<Version> {
+        let mut parts = s.trim().split('.');
+        let mut next = || -> Option<u64> {
+            match parts.next() {
+                Some(p) => p.parse().ok(),
+                None => Some(0),
+            }
+        };
+        let version = Version {
+            major: next()?,
+            minor: next()?,
+            patch: next()?,
+        };
+        parts.next().is_none().then_some(version)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// Cargo-style requirements: `*`, `=1.2.3`, `>=1.2`, and `^1.2` (the same
+// as a bare `1.2`).
+pub fn satisfies(version: Version, requirement: &str) -> Result<bool, String> {
+    let requirement = requirement.trim();
+    if requirement == "*" {
+        return Ok(true);
+    }
+    let bad = || format!("invalid version requirement `{}`", requirement);
+    let (op, rest) = ["=", ">=", "^"]
+        .iter()
+        .rev()
+        .find_map(|op| requirement.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("^", requirement));
+    let wanted = Version::parse(rest).ok_or_else(bad)?;
+    Ok(match op {
+        "=" => version == wanted,
+        ">=" => version >= wanted,
+        // Compatible: same leftmost non-zero component.
+        _ => {
+            version >= wanted
+                && if wanted.major > 0 {
+                    version.major == wanted.major
+                } else {
+                    version.major == 0 && version.minor == wanted.minor
+                }
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackLesson {
+    pub id: String,
+    pub title: String,
+    // Inside the pack.
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub name: String,
+    pub version: Version,
+    pub description: String,
+    // Pack name and version requirement.
+    pub dependencies: Vec<(String, String)>,
+    pub lessons: Vec<PackLesson>,
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Manifest, String> {
+        let doc = toml::parse(text).map_err(|e| format!("{}: {}", MANIFEST, e))?;
+        let format = doc.get("format").and_then(Value::as_integer).unwrap_or(0);
+        if format < 1 {
+            return Err(format!("{}: missing `format`", MANIFEST));
+        }
+        if format > FORMAT {
+            return Err(format!(
+                "pack format {} is newer than this tool understands ({}); update the lessons crate",
+                format, FORMAT
+            ));
+        }
+
+        let pack = doc
+            .get("pack")
+            .and_then(Value::as_table)
+            .ok_or("missing [pack]")?;
+        let field = |table: &toml::Table, key: &str| {
+            table.get(key).and_then(Value::as_str).map(str::to_string)
+        };
+        let name = field(pack, "name").ok_or("pack needs a `name`")?;
+        if name.is_empty() || name.contains(['/', '\\', '.']) {
+            return Err(format!("invalid pack name `{}`", name));
+        }
+        let version = field(pack, "version")
+            .as_deref()
+            .and_then(Version::parse)
+            .ok_or("pack needs a `version` like 1.0.0")?;
+
+        let mut dependencies = Vec::new();
+        if let Some(deps) = doc.get("dependencies").and_then(Value::as_table) {
+            for (dep, requirement) in deps {
+                let requirement = requirement
+                    .as_str()
+                    .ok_or_else(|| format!("dependency `{}` needs a version requirement", dep))?;
+                satisfies(Version::parse("0").unwrap(), requirement)?;
+                dependencies.push((dep.clone(), requirement.to_string()));
+            }
+        }
+
+        let mut lessons = Vec::new();
+        for entry in doc
+            .get("lesson")
+            .and_then(Value::as_array)
+            .unwrap_or_default()
+        {
+            let table = entry.as_table().ok_or("[[lesson]] must be a table")?;
+            let get =
+                |key| field(table, key).ok_or_else(|| format!("every [[lesson]] needs `{}`", key));
+            let lesson = PackLesson {
+                id: get("id")?,
+                title: get("title")?,
+                path: get("path")?,
+                sha256: get("sha256")?,
+            };
+            if !safe_path(&lesson.path) {
+                return Err(format!("lesson `{}` has an unsafe path", lesson.id));
+            }
+            if lessons.iter().any(|l: &PackLesson| l.id == lesson.id) {
+                return Err(format!("lesson `{}` is listed twice", lesson.id));
+            }
+            lessons.push(lesson);
+        }
+
+        Ok(Manifest {
+            name,
+            version,
+            description: field(pack, "description").unwrap_or_default(),
+            dependencies,
+            lessons,
+        })
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = format!(
+            "format = {}\n\n[pack]\nname = {}\nversion = {}\n",
+            FORMAT,
+            toml::quote(&self.name),
+            toml::quote(&self.version.to_string())
+        );
+        if !self.description.is_empty() {
+            out += &format!("description = {}\n", toml::quote(&self.description));
+        }
+        out += "\n[dependencies]\n";
+        for (name, requirement) in &self.dependencies {
+            out += &format!("{} = {}\n", toml::quote(name), toml::quote(requirement));
+        }
+        for lesson in &self.lessons {
+            out += &format!(
+                "\n[[lesson]]\nid = {}\ntitle = {}\npath = {}\nsha256 = {}\n",
+                toml::quote(&lesson.id),
+                toml::quote(&lesson.title),
+                toml::quote(&lesson.path),
+                toml::quote(&lesson.sha256)
+            );
+        }
+        out
+    }
+}
+
+// Relative, no `..`, so unpacking cannot write outside the pack directory.
+fn safe_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && !path.contains(':')
+        && path
+            .split('/')
+            .all(|c| !c.is_empty() && c != "." && c != "..")
+}
+
+#[derive(Debug)]
+pub struct Pack {
+    pub manifest: Manifest,
+    // (path inside the pack, contents), in manifest order.
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+impl Pack {
+    // Packs the given catalog lessons, keeping their ids.
+    pub fn from_lessons(
+        catalog: &Catalog,
+        lessons: &[&Lesson],
+        name: &str,
+        version: Version,
+        description: &str,
+        dependencies: Vec<(String, String)>,
+    ) -> Result<Pack, String> {
+        let mut files = Vec::new();
+        let mut entries = Vec::new();
+        for lesson in lessons {
+            let full = catalog.root().join(&lesson.path);
+            let data = fs::read(&full).map_err(|e| format!("{}: {}", full.display(), e))?;
+            let path = format!("lessons/{}.rs", lesson.id);
+            entries.push(PackLesson {
+                id: lesson.id.clone(),
+                title: lesson.title.clone(),
+                path: path.clone(),
+                sha256: sha256::hex(&sha256::digest(&data)),
+            });
+            files.push((path, data));
+        }
+        let manifest = Manifest {
+            name: name.to_string(),
+            version,
+            description: description.to_string(),
+            dependencies,
+            lessons: entries,
+        };
+        // Round-trip so a pack we write is always one we can read.
+        Manifest::parse(&manifest.to_toml())?;
+        Ok(Pack { manifest, files })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut entries = vec![Entry {
+            path: MANIFEST.to_string(),
+            data: self.manifest.to_toml().into_bytes(),
+        }];
+        for (path, data) in &self.files {
+            entries.push(Entry {
+                path: path.clone(),
+                data: data.clone(),
+            });
+        }
+        tar::write(&entries)
+    }
+
+    // Reads an archive and checks every listed file against its checksum.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Pack, String> {
+        let entries = tar::read(bytes)?;
+        let manifest = entries
+            .iter()
+            .find(|e| e.path == MANIFEST)
+            .ok_or("not a lesson pack: no pack.toml")?;
+        let manifest = Manifest::parse(&String::from_utf8_lossy(&manifest.data))?;
+
+        let mut files = Vec::new();
+        for lesson in &manifest.lessons {
+            let entry = entries
+                .iter()
+                .find(|e| e.path == lesson.path)
+                .ok_or_else(|| format!("lesson `{}`: {} is missing", lesson.id, lesson.path))?;
+            if sha256::hex(&sha256::digest(&entry.data)) != lesson.sha256 {
+                return Err(format!(
+                    "lesson `{}`: {} does not match its checksum",
+                    lesson.id, lesson.path
+                ));
+            }
+            files.push((lesson.path.clone(), entry.data.clone()));
+        }
+        Ok(Pack { manifest, files })
+    }
+}
+
+pub fn packs_dir(root: &Path) -> PathBuf {
+    root.join("packs")
+}
+
+// Manifests of the packs under `<root>/packs`, by name.
+pub fn installed(root: &Path) -> Result<Vec<Manifest>, String> {
+    let dir = packs_dir(root);
+    let mut manifests = Vec::new();
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(manifests);
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        let file = path.join(MANIFEST);
+        if !file.is_file() {
+            continue;
+        }
+        let text = fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        manifests.push(Manifest::parse(&text).map_err(|e| format!("{}: {}", file.display(), e))?);
+    }
+    Ok(manifests)
+}
+
+// The version of `name` available locally: ours or an installed pack.
+fn available(name: &str, installed: &[Manifest]) -> Option<Version> {
+    if name == OWN_NAME {
+        return Version::parse(OWN_VERSION);
+    }
+    installed.iter().find(|m| m.name == name).map(|m| m.version)
+}
+
+// Why `manifest` cannot sit next to the other packs; empty when it can.
+pub fn problems(manifest: &Manifest, catalog: &Catalog, others: &[Manifest]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (dep, requirement) in &manifest.dependencies {
+        match available(dep, others) {
+            None => problems.push(format!(
+                "needs pack `{}` ({}), which is not installed",
+                dep, requirement
+            )),
+            Some(version) => match satisfies(version, requirement) {
+                Ok(true) => {}
+                Ok(false) => problems.push(format!(
+                    "needs `{}` {}, but {} is installed",
+                    dep, requirement, version
+                )),
+                Err(e) => problems.push(e),
+            },
+        }
+    }
+
+    let pack_dir = packs_dir(Path::new("")).join(&manifest.name);
+    for lesson in &manifest.lessons {
+        // The same pack's own lessons, from an older install, do not count.
+        let clash = catalog
+            .lessons()
+            .iter()
+            .find(|l| l.id == lesson.id && !l.path.starts_with(&pack_dir));
+        if let Some(existing) = clash {
+            problems.push(format!(
+                "lesson id `{}` is already defined by {}",
+                lesson.id,
+                existing.path.display()
+            ));
+        }
+    }
+    problems
+}
+
+// Unpacks into `<root>/packs/<name>`, replacing an older version.
+pub fn install(root: &Path, pack: &Pack, catalog: &Catalog) -> Result<PathBuf, String> {
+    let manifest = &pack.manifest;
+    let installed = installed(root)?;
+    if manifest.name == OWN_NAME {
+        return Err(format!("`{}` is this repository's own pack", OWN_NAME));
+    }
+    if let Some(current) = installed.iter().find(|m| m.name == manifest.name)
+        && current.version >= manifest.version
+    {
+        return Err(format!(
+            "{} {} is already installed",
+            current.name, current.version
+        ));
+    }
+    let others: Vec<Manifest> = installed
+        .into_iter()
+        .filter(|m| m.name != manifest.name)
+        .collect();
+    let problems = problems(manifest, catalog, &others);
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+
+    let dir = packs_dir(root).join(&manifest.name);
+    let io = |e: std::io::Error| format!("{}: {}", dir.display(), e);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(io)?;
+    }
+    for (path, data) in &pack.files {
+        let dest = dir.join(path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(io)?;
+        }
+        fs::write(&dest, data).map_err(io)?;
+    }
+    fs::write(dir.join(MANIFEST), manifest.to_toml()).map_err(io)?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+    use crate::test_util::TempDir;
+
+    fn scratch(name: &str) -> TempDir {
+        let tmp = TempDir::new(&format!("pack-{}", name));
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("helloRust/src/examples")).unwrap();
+        fs::write(
+            dir.join("helloRust/src/examples/main.rs"),
+            "fn main() {\n    println!(\"Hello, world!\");\n}\n",
+        )
+        .unwrap();
+        tmp
+    }
+
+    // A pack from another team with one lesson, built in a separate tree.
+    fn team_pack(version: &str, id: &str, requires: &str) -> Vec<u8> {
+        let tmp = scratch(&format!("src-{}-{}", version, id.replace('/', "-")));
+        let src = tmp.path();
+        let file = src.join("helloRust/src/examples/futures.rs");
+        fs::write(&file, "async fn answer() -> u32 { 42 }\n").unwrap();
+        let catalog = Catalog::discover(src).unwrap();
+        let mut lesson = catalog.get("examples/futures").unwrap().clone();
+        lesson.id = id.to_string();
+        let pack = Pack::from_lessons(
+            &catalog,
+            &[&lesson],
+            "team-async",
+            Version::parse(version).unwrap(),
+            "Async lessons",
+            vec![(OWN_NAME.to_string(), requires.to_string())],
+        )
+        .unwrap();
+        pack.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn version_requirements() {
+        let v = |s| Version::parse(s).unwrap();
+        assert!(satisfies(v("1.4.2"), "^1.2").unwrap());
+        assert!(satisfies(v("1.4.2"), "1.2").unwrap());
+        assert!(!satisfies(v("2.0.0"), "^1.2").unwrap());
+        assert!(!satisfies(v("0.2.0"), "^0.1").unwrap());
+        assert!(satisfies(v("0.1.9"), "^0.1").unwrap());
+        assert!(satisfies(v("3.0.0"), ">=1.2").unwrap());
+        assert!(!satisfies(v("1.2.1"), "=1.2.0").unwrap());
+        assert!(satisfies(v("9.9.9"), "*").unwrap());
+        assert!(satisfies(v("1.0.0"), "~1").is_err());
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let tmp = scratch("round-trip");
+        let root = tmp.path();
+        let bytes = team_pack("1.0.0", "team-async/futures", "^0.1");
+        let pack = Pack::from_bytes(&bytes).unwrap();
+        assert_eq!(pack.manifest.name, "team-async");
+
+        let catalog = Catalog::discover(root).unwrap();
+        let dir = install(root, &pack, &catalog).unwrap();
+        assert!(dir.join("lessons/team-async/futures.rs").is_file());
+
+        let catalog = Catalog::discover(root).unwrap();
+        let lesson = catalog.get("team-async/futures").unwrap();
+        assert_eq!(lesson.pack.as_deref(), Some("team-async"));
+        assert!(catalog.read(lesson).unwrap().contains("async fn"));
+
+        // Same version again is refused; a newer one replaces it.
+        assert!(
+            install(root, &pack, &catalog)
+                .unwrap_err()
+                .contains("already installed")
+        );
+        let newer = Pack::from_bytes(&team_pack("1.1.0", "team-async/futures", "^0.1")).unwrap();
+        install(root, &newer, &catalog).unwrap();
+        assert_eq!(
+            installed(root).unwrap()[0].version,
+            Version::parse("1.1.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn detects_conflicts_and_missing_dependencies() {
+        let tmp = scratch("conflicts");
+        let root = tmp.path();
+        let catalog = Catalog::discover(root).unwrap();
+
+        let clash = Pack::from_bytes(&team_pack("1.0.0", "examples/main", "*")).unwrap();
+        let err = install(root, &clash, &catalog).unwrap_err();
+        assert!(
+            err.contains("lesson id `examples/main` is already defined by helloRust"),
+            "{}",
+            err
+        );
+
+        let too_new = Pack::from_bytes(&team_pack("1.0.0", "team-async/futures", "^9")).unwrap();
+        let err = install(root, &too_new, &catalog).unwrap_err();
+        assert!(err.contains("needs `rust-1-to-100` ^9"), "{}", err);
+        assert!(installed(root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_tampered_and_future_packs() {
+        let bytes = team_pack("1.0.0", "team-async/futures", "*");
+        let mut entries = tar::read(&bytes).unwrap();
+        entries[1].data = b"fn main() { evil() }\n".to_vec();
+        let err = Pack::from_bytes(&tar::write(&entries).unwrap()).unwrap_err();
+        assert!(err.contains("checksum"), "{}", err);
+
+        let mut entries = tar::read(&bytes).unwrap();
+        let manifest = String::from_utf8(entries[0].data.clone()).unwrap();
+        entries[0].data = manifest.replace("format = 1", "format = 2").into_bytes();
+        let err = Pack::from_bytes(&tar::write(&entries).unwrap()).unwrap_err();
+        assert!(err.contains("newer"), "{}", err);
+
+        let unsafe_path = manifest.replace("path = \"lessons/", "path = \"../");
+        assert!(
+            Manifest::parse(&unsafe_path)
+                .unwrap_err()
+                .contains("unsafe")
+        );
+    }
+
+    #[test]
+    fn our_lessons_export_as_a_pack() {
+        let catalog = Catalog::discover(&catalog::default_root()).unwrap();
+        let lessons: Vec<&Lesson> = catalog
+            .lessons()
+            .iter()
+            .filter(|l| l.pack.is_none())
+            .collect();
+        let pack = Pack::from_lessons(
+            &catalog,
+            &lessons,
+            OWN_NAME,
+            Version::parse(OWN_VERSION).unwrap(),
+            "",
+            Vec::new(),
+        )
+        .unwrap();
+        let back = Pack::from_bytes(&pack.to_bytes().unwrap()).unwrap();
+        assert_eq!(back.manifest, pack.manifest);
+        assert_eq!(back.files.len(), lessons.len());
+    }
+}
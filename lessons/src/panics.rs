@@ -0,0 +1,303 @@
+// Turns a snippet's panic output into a report a learner can act on.
+//
+// The runner already isolates each snippet in its own process, so a panic
+// only ends that snippet. Here we read the panic message and location
+// back out of stderr, map the location to the line in the notes file and
+// pick a hint from `lessons/hints.toml`.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::snippets::Snippet;
+use crate::toml;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    pub thread: String,
+    pub message: String,
+    // Location in the compiled source, when the panic names one.
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+// Every panic in `stderr`, in order. Understands both the current format
+//
+//   thread 'main' panicked at src/main.rs:9:45:
+//   no entry found for key
+//
+// and the pre-1.73 one, `thread 'main' panicked at 'msg', src/main.rs:9:45`.
+pub fn parse(stderr: &str) -> Vec<Panic> {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let mut panics = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(rest) = line.strip_prefix("thread '") else {
+            continue;
+        };
+        let Some((thread, rest)) = rest.split_once('\'') else {
+            continue;
+        };
+        // Threads also carry an id since 1.87: `thread 'main' (1234) panicked`.
+        let Some((_, rest)) = rest.split_once("panicked at ") else {
+            continue;
+        };
+
+        let (message, location) = if let Some(old) = rest.strip_prefix('\'') {
+            match old.rsplit_once("', ") {
+                Some((message, location)) => (message.to_string(), location),
+                None => continue,
+            }
+        } else {
+            // The message runs until the backtrace, the `note:` line or the
+            // next panic.
+            let message: Vec<&str> = lines[i + 1..]
+                .iter()
+                .take_while(|l| {
+                    !l.starts_with("note: ")
+                        && !l.starts_with("stack backtrace:")
+                        && !l.starts_with("thread '")
+                })
+                .copied()
+                .collect();
+            let location = rest.strip_suffix(':').unwrap_or(rest);
+            (message.join("\n").trim_end().to_string(), location)
+        };
+
+        let (file, line) = split_location(location);
+        panics.push(Panic {
+            thread: thread.to_string(),
+            message,
+            file,
+            line,
+        });
+    }
+    panics
+}
+
+// `path:line:col` -> (path, line). Paths may contain `:` on Windows.
+fn split_location(location: &str) -> (Option<String>, Option<usize>) {
+    let mut parts = location.rsplitn(3, ':');
+    let _column = parts.next();
+    let line = parts.next().and_then(|l| l.parse().ok());
+    match (parts.next(), line) {
+        (Some(file), Some(line)) => (Some(file.to_string()), Some(line)),
+        _ => (None, None),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hint {
+    pub lesson: Option<String>,
+    pub panic: String,
+    pub text: String,
+    pub read: Option<String>,
+}
+
+pub struct Hints {
+    hints: Vec<Hint>,
+}
+
+impl Hints {
+    pub fn default_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("hints.toml")
+    }
+
+    pub fn load(path: &Path) -> Result<Hints, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let doc = toml::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut hints = Vec::new();
+        for entry in doc
+            .get("hint")
+            .and_then(|v| v.as_array())
+            .unwrap_or_default()
+        {
+            let field = |key| {
+                entry
+                    .as_table()
+                    .and_then(|t| t.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            let (Some(panic), Some(text)) = (field("panic"), field("text")) else {
+                return Err(format!(
+                    "{}: every [[hint]] needs `panic` and `text`",
+                    path.display()
+                ));
+            };
+            hints.push(Hint {
+                lesson: field("lesson"),
+                panic,
+                text,
+                read: field("read"),
+            });
+        }
+        Ok(Hints { hints })
+    }
+
+    // Hints for the lesson win over general ones.
+    pub fn find(&self, lesson_id: &str, message: &str) -> Option<&Hint> {
+        let matching = |h: &&Hint| message.contains(&h.panic);
+        self.hints
+            .iter()
+            .filter(|h| h.lesson.as_deref() == Some(lesson_id))
+            .find(matching)
+            .or_else(|| {
+                self.hints
+                    .iter()
+                    .filter(|h| h.lesson.is_none())
+                    .find(matching)
+            })
+    }
+}
+
+pub struct Report<'a> {
+    pub notes: &'a Path,
+    pub panic: Panic,
+    // Line in the notes file, when the panic points into the snippet.
+    pub notes_line: Option<usize>,
+    // Panics after the first, usually consequences of it.
+    pub more: usize,
+    pub hint: Option<&'a Hint>,
+}
+
+// The report for the first panic in `stderr`, if there is one.
+pub fn report<'a>(
+    snippet: &Snippet,
+    notes: &'a Path,
+    stderr: &str,
+    hints: &'a Hints,
+) -> Option<Report<'a>> {
+    let mut panics = parse(stderr).into_iter();
+    let panic = panics.next()?;
+    // Snippets are compiled from a copy, so any `.rs` file that is not the
+    // standard library's is the snippet.
+    let in_snippet = panic
+        .file
+        .as_deref()
+        .is_some_and(|f| f.ends_with(".rs") && !f.contains("/rustc/") && !f.contains("library/"));
+    let notes_line = panic
+        .line
+        .filter(|_| in_snippet)
+        .map(|line| snippet.first_line + line - 1);
+    let hint = hints.find(&snippet.lesson_id, &panic.message);
+    Some(Report {
+        notes,
+        notes_line,
+        more: panics.count(),
+        hint,
+        panic,
+    })
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let thread = if self.panic.thread == "main" {
+            String::new()
+        } else {
+            format!(" in thread '{}'", self.panic.thread)
+        };
+        writeln!(f, "  panicked{}: {}", thread, self.panic.message)?;
+        match self.notes_line {
+            Some(line) => writeln!(f, "  --> {}:{}", self.notes.display(), line)?,
+            None => writeln!(f, "  --> {}", self.notes.display())?,
+        }
+        if self.more > 0 {
+            writeln!(f, "  ({} more panic(s) followed)", self.more)?;
+        }
+        if let Some(hint) = self.hint {
+            for line in hint.text.lines() {
+                writeln!(f, "  hint: {}", line)?;
+            }
+            if let Some(read) = &hint.read {
+                writeln!(f, "  read: {}", read)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(lesson_id: &str, first_line: usize) -> Snippet {
+        Snippet {
+            lesson_id: lesson_id.to_string(),
+            block: 1,
+            first_line,
+            source: String::new(),
+        }
+    }
+
+    #[test]
+    fn parses_current_and_old_formats() {
+        let new = "thread 'main' (4242) panicked at /tmp/x/tmp-1-ab.rs:9:45:\n\
+                   no entry found for key\n\
+                   note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+        assert_eq!(
+            parse(new),
+            [Panic {
+                thread: "main".to_string(),
+                message: "no entry found for key".to_string(),
+                file: Some("/tmp/x/tmp-1-ab.rs".to_string()),
+                line: Some(9),
+            }]
+        );
+
+        let old = "thread 'main' panicked at 'index out of bounds: the len is 3 but the index is 5', src/main.rs:4:5\n";
+        let panic = &parse(old)[0];
+        assert_eq!(
+            panic.message,
+            "index out of bounds: the len is 3 but the index is 5"
+        );
+        assert_eq!(panic.line, Some(4));
+    }
+
+    #[test]
+    fn poisoned_lock_reports_the_first_panic() {
+        let stderr = "thread '<unnamed>' panicked at counter.rs:12:9:\n\
+                      boom\n\
+                      note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n\
+                      thread 'main' panicked at counter.rs:20:37:\n\
+                      called `Result::unwrap()` on an `Err` value: PoisonError { .. }\n";
+        let hints = Hints { hints: Vec::new() };
+        let report = report(
+            &snippet("more-advanced-datastructure/multithreading", 100),
+            Path::new("Multithreading.rs"),
+            stderr,
+            &hints,
+        )
+        .unwrap();
+        assert_eq!(report.panic.message, "boom");
+        assert_eq!(report.notes_line, Some(111));
+        assert_eq!(report.more, 1);
+        assert!(report.to_string().contains("in thread '<unnamed>'"));
+    }
+
+    #[test]
+    fn lesson_hints_win_over_general_ones() {
+        let hints = Hints::load(&Hints::default_path()).unwrap();
+        let lesson = hints.find("bin/test", "no entry found for key").unwrap();
+        assert!(lesson.text.contains("moneyheistcities"));
+        let general = hints
+            .find("data_structures/rust_hashmap", "no entry found for key")
+            .unwrap();
+        assert!(general.lesson.is_none());
+        assert!(hints.find("bin/test", "something else").is_none());
+    }
+
+    #[test]
+    fn hints_point_at_real_lessons() {
+        let catalog = crate::catalog::Catalog::discover(&crate::catalog::default_root()).unwrap();
+        let hints = Hints::load(&Hints::default_path()).unwrap();
+        for hint in &hints.hints {
+            for id in hint.lesson.iter().chain(&hint.read) {
+                assert!(
+                    catalog.get(id).is_some(),
+                    "hints.toml: unknown lesson `{}`",
+                    id
+                );
+            }
+        }
+    }
+}
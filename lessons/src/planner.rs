@@ -0,0 +1,443 @@
+// Study planner: lays the curriculum out on a calendar.
+//
+// `lessons/curriculum.toml` gives the study order, prerequisites, minutes
+// per lesson and the learner's weekly availability. The plan always starts
+// today from what is not done yet, so finishing lessons early pulls the
+// rest forward and falling behind pushes it back.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::catalog::Catalog;
+use crate::date::{self, Date};
+use crate::toml::{self, Value};
+
+// Don't open a lesson in the last few minutes of a day unless it fits.
+const MIN_SESSION: u32 = 15;
+
+pub const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+#[derive(Debug, Clone)]
+pub struct Item {
+    pub id: String,
+    pub title: String,
+    pub minutes: u32,
+    pub requires: Vec<String>,
+    // The notes file, for items that have one yet.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Availability {
+    // Minutes per weekday, Monday first.
+    pub minutes: [u32; 7],
+    // Minutes after midnight when sessions start.
+    pub start: u32,
+}
+
+#[derive(Debug)]
+pub struct Curriculum {
+    pub items: Vec<Item>,
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub date: Date,
+    // Minutes after midnight.
+    pub start: u32,
+    pub minutes: u32,
+    pub item: usize,
+    // 1-based; lessons longer than a day's time are split.
+    pub part: usize,
+    pub parts: usize,
+}
+
+impl Curriculum {
+    pub fn default_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("curriculum.toml")
+    }
+
+    pub fn load(path: &Path, catalog: &Catalog) -> Result<Curriculum, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Curriculum::parse(&text, catalog).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str, catalog: &Catalog) -> Result<Curriculum, String> {
+        let doc = toml::parse(text).map_err(|e| e.to_string())?;
+
+        let table = doc
+            .get("availability")
+            .and_then(Value::as_table)
+            .ok_or("missing [availability]")?;
+        let mut minutes = [0; 7];
+        for (day, name) in minutes.iter_mut().zip(WEEKDAYS) {
+            if let Some(value) = table.get(name) {
+                *day = value
+                    .as_integer()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .ok_or_else(|| format!("availability.{} must be minutes", name))?;
+            }
+        }
+        if minutes.iter().all(|&m| m == 0) {
+            return Err("availability has no time on any day".to_string());
+        }
+        let start = match table.get("start").and_then(Value::as_str) {
+            Some(s) => parse_time(s).ok_or("availability.start must be HH:MM")?,
+            None => 18 * 60,
+        };
+
+        let mut items: Vec<Item> = Vec::new();
+        let entries = doc
+            .get("lesson")
+            .and_then(Value::as_array)
+            .unwrap_or_default();
+        for entry in entries {
+            let table = entry.as_table().ok_or("[[lesson]] must be a table")?;
+            let id = table
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or("every [[lesson]] needs an `id`")?;
+            let err = |msg: &str| format!("lesson `{}`: {}", id, msg);
+            if items.iter().any(|i| i.id == id) {
+                return Err(err("listed twice"));
+            }
+            let minutes = table
+                .get("minutes")
+                .and_then(Value::as_integer)
+                .and_then(|n| u32::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .ok_or_else(|| err("needs positive `minutes`"))?;
+            let mut requires = Vec::new();
+            for r in table
+                .get("requires")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+            {
+                let r = r.as_str().ok_or_else(|| err("`requires` must be ids"))?;
+                requires.push(r.to_string());
+            }
+            let lesson = catalog.get(id);
+            let title = match (table.get("title").and_then(Value::as_str), lesson) {
+                (Some(title), _) => title.to_string(),
+                (None, Some(lesson)) => lesson.title.clone(),
+                (None, None) => return Err(err("not a lesson; give it a `title`")),
+            };
+            let path = lesson.map(|l| l.path.clone());
+            items.push(Item {
+                id: id.to_string(),
+                title,
+                minutes,
+                requires,
+                path,
+            });
+        }
+        for item in &items {
+            for r in &item.requires {
+                if !items.iter().any(|i| &i.id == r) {
+                    return Err(format!("lesson `{}` requires unknown `{}`", item.id, r));
+                }
+            }
+        }
+
+        Ok(Curriculum {
+            items,
+            availability: Availability { minutes, start },
+        })
+    }
+
+    // Indexes of the items not yet done, prerequisites first and otherwise
+    // in file order.
+    pub fn order(&self, done: &BTreeSet<String>) -> Result<Vec<usize>, String> {
+        let mut placed: BTreeSet<&str> = done.iter().map(String::as_str).collect();
+        let mut pending: Vec<usize> = (0..self.items.len())
+            .filter(|&i| !done.contains(&self.items[i].id))
+            .collect();
+        let mut order = Vec::new();
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|&i| {
+                self.items[i]
+                    .requires
+                    .iter()
+                    .all(|r| placed.contains(r.as_str()))
+            });
+            let Some(pos) = ready else {
+                let stuck: Vec<&str> = pending.iter().map(|&i| self.items[i].id.as_str()).collect();
+                return Err(format!("prerequisites form a cycle: {}", stuck.join(", ")));
+            };
+            let i = pending.remove(pos);
+            placed.insert(&self.items[i].id);
+            order.push(i);
+        }
+        Ok(order)
+    }
+
+    // Day-by-day sessions from `start` for everything not in `done`.
+    pub fn schedule(&self, done: &BTreeSet<String>, start: Date) -> Result<Vec<Session>, String> {
+        let available = &self.availability.minutes;
+        let mut sessions: Vec<Session> = Vec::new();
+        let mut day = start;
+        let mut used = 0;
+        for i in self.order(done)? {
+            let first = sessions.len();
+            let mut left = self.items[i].minutes;
+            while left > 0 {
+                let room = available[day.weekday()] - used;
+                if room == 0 || room < left.min(MIN_SESSION) {
+                    day = day.add_days(1);
+                    used = 0;
+                    continue;
+                }
+                let minutes = left.min(room);
+                sessions.push(Session {
+                    date: day,
+                    start: self.availability.start + used,
+                    minutes,
+                    item: i,
+                    part: sessions.len() - first + 1,
+                    parts: 0,
+                });
+                used += minutes;
+                left -= minutes;
+            }
+            let parts = sessions.len() - first;
+            for s in &mut sessions[first..] {
+                s.parts = parts;
+            }
+        }
+        Ok(sessions)
+    }
+
+    pub fn session_title(&self, session: &Session) -> String {
+        let title = &self.items[session.item].title;
+        if session.parts > 1 {
+            format!("{} ({}/{})", title, session.part, session.parts)
+        } else {
+            title.clone()
+        }
+    }
+
+    pub fn to_text(&self, sessions: &[Session]) -> String {
+        let mut out = String::new();
+        let mut last = None;
+        for s in sessions {
+            if last != Some(s.date) {
+                let weekday = WEEKDAYS[s.date.weekday()];
+                let _ = writeln!(
+                    out,
+                    "{}{} {}",
+                    if last.is_some() { "\n" } else { "" },
+                    weekday,
+                    s.date
+                );
+                last = Some(s.date);
+            }
+            let _ = writeln!(
+                out,
+                "  {}  {:>3} min  {}",
+                format_time(s.start),
+                s.minutes,
+                self.session_title(s)
+            );
+        }
+        out
+    }
+
+    // An iCalendar file with one event per session. Times are floating
+    // (no time zone), so calendars show them in the learner's local time.
+    // UIDs depend only on the lesson and part, so importing a rescheduled
+    // plan moves the events instead of duplicating them.
+    pub fn to_ics(&self, sessions: &[Session], stamp_unix: u64) -> String {
+        let stamp = date::timestamp(stamp_unix)
+            .replace(['-', ':'], "")
+            .replace(' ', "T")
+            + "Z";
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Rust-1-to-100//lessons plan//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        for s in sessions {
+            let item = &self.items[s.item];
+            let description = match &item.path {
+                Some(path) => format!("{}\n{}", item.id, path.display()),
+                None => item.id.clone(),
+            };
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{}@rust-1-to-100", item.id.replace('/', "-"), s.part),
+                format!("DTSTAMP:{}", stamp),
+                format!("DTSTART:{}", ics_time(s.date, s.start)),
+                format!("DTEND:{}", ics_time(s.date, s.start + s.minutes)),
+                format!(
+                    "SUMMARY:{}",
+                    ics_escape(&format!("Rust: {}", self.session_title(s)))
+                ),
+                format!("DESCRIPTION:{}", ics_escape(&description)),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        let mut out = String::new();
+        for line in lines {
+            fold(&line, &mut out);
+        }
+        out
+    }
+}
+
+fn parse_time(s: &str) -> Option<u32> {
+    let (h, m) = s.split_once(':')?;
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+fn format_time(minutes: u32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+// Sessions past midnight roll over to the next day.
+fn ics_time(date: Date, minutes: u32) -> String {
+    let date = date.add_days(i64::from(minutes / 1440));
+    let minutes = minutes % 1440;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}00",
+        date.year,
+        date.month,
+        date.day,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+// RFC 5545 TEXT escaping.
+fn ics_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Content lines are at most 75 octets; continuations start with a space.
+fn fold(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+
+    fn catalog() -> Catalog {
+        Catalog::discover(&catalog::default_root()).unwrap()
+    }
+
+    const SMALL: &str = r#"
+[availability]
+start = "19:00"
+mon = 60
+tue = 30
+sat = 120
+
+[[lesson]]
+id = "examples/main"
+minutes = 20
+
+[[lesson]]
+id = "concepts/rust_datatypes"
+minutes = 50
+requires = ["examples/main"]
+
+[[lesson]]
+id = "next/async-await"
+title = "Async/await patterns"
+minutes = 100
+requires = ["concepts/rust_datatypes"]
+"#;
+
+    fn monday() -> Date {
+        Date::parse("2025-10-20").unwrap()
+    }
+
+    #[test]
+    fn fills_days_and_splits_long_lessons() {
+        let plan = Curriculum::parse(SMALL, &catalog()).unwrap();
+        let sessions = plan.schedule(&BTreeSet::new(), monday()).unwrap();
+        let text = plan.to_text(&sessions);
+        assert_eq!(
+            text,
+            "mon 2025-10-20\n  19:00   20 min  main\n  19:20   40 min  rust_datatypes (1/2)\n\
+             \ntue 2025-10-21\n  19:00   10 min  rust_datatypes (2/2)\n  19:10   20 min  Async/await patterns (1/2)\n\
+             \nsat 2025-10-25\n  19:00   80 min  Async/await patterns (2/2)\n"
+        );
+    }
+
+    #[test]
+    fn finishing_early_pulls_the_plan_forward() {
+        let plan = Curriculum::parse(SMALL, &catalog()).unwrap();
+        let done = BTreeSet::from(["examples/main".to_string()]);
+        let sessions = plan.schedule(&done, monday()).unwrap();
+        assert_eq!(plan.items[sessions[0].item].id, "concepts/rust_datatypes");
+        assert_eq!(sessions[0].minutes, 50);
+        // Async/await now gets all of Tuesday instead of 20 minutes of it.
+        let async_await: Vec<(Date, u32)> =
+            sessions[1..].iter().map(|s| (s.date, s.minutes)).collect();
+        assert_eq!(
+            async_await,
+            [(monday().add_days(1), 30), (monday().add_days(5), 70)]
+        );
+    }
+
+    #[test]
+    fn rejects_cycles_and_unknown_lessons() {
+        let cycle = SMALL.replace(
+            "id = \"examples/main\"\nminutes = 20",
+            "id = \"examples/main\"\nminutes = 20\nrequires = [\"next/async-await\"]",
+        );
+        let plan = Curriculum::parse(&cycle, &catalog()).unwrap();
+        let err = plan.order(&BTreeSet::new()).unwrap_err();
+        assert!(err.contains("cycle"), "{}", err);
+
+        let unknown = SMALL.replace("title = \"Async/await patterns\"\n", "");
+        let err = Curriculum::parse(&unknown, &catalog()).unwrap_err();
+        assert!(err.contains("next/async-await"), "{}", err);
+    }
+
+    #[test]
+    fn exports_icalendar() {
+        let plan = Curriculum::parse(SMALL, &catalog()).unwrap();
+        let sessions = plan.schedule(&BTreeSet::new(), monday()).unwrap();
+        let ics = plan.to_ics(&sessions, 1760000000);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), sessions.len());
+        assert!(ics.contains("DTSTAMP:20251009T085320Z\r\n"));
+        assert!(ics.contains(
+            "UID:concepts-rust_datatypes-2@rust-1-to-100\r\nDTSTAMP:20251009T085320Z\r\n\
+             DTSTART:20251021T190000\r\nDTEND:20251021T191000\r\n"
+        ));
+        assert!(ics.lines().all(|l| l.len() <= 76));
+    }
+
+    #[test]
+    fn repository_curriculum_schedules() {
+        let catalog = catalog();
+        let plan = Curriculum::load(&Curriculum::default_path(), &catalog).unwrap();
+        let sessions = plan.schedule(&BTreeSet::new(), monday()).unwrap();
+        let planned: u32 = sessions.iter().map(|s| s.minutes).sum();
+        assert_eq!(planned, plan.items.iter().map(|i| i.minutes).sum::<u32>());
+    }
+}
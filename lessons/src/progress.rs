@@ -0,0 +1,93 @@
+// The learner's progress: one line per item, tab separated.
+//
+//   exercise:strings/first_word    passed    1760000000
+//   lesson:concepts/rust_datatypes done      1760000000
+//
+// The last column is a Unix timestamp. Later lines for the same item
+// replace earlier ones, so recording is a plain append.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::date;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub status: String,
+    pub at: u64,
+}
+
+pub struct Progress {
+    path: PathBuf,
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Progress {
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".progress.tsv")
+    }
+
+    // A missing file is an empty store.
+    pub fn load(path: &Path) -> io::Result<Progress> {
+        let mut entries = BTreeMap::new();
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                for line in text.lines() {
+                    let mut cols = line.split('\t');
+                    if let (Some(key), Some(status), Some(at)) =
+                        (cols.next(), cols.next(), cols.next())
+                    {
+                        let at = at.trim().parse().unwrap_or(0);
+                        entries.insert(
+                            key.to_string(),
+                            Entry {
+                                status: status.to_string(),
+                                at,
+                            },
+                        );
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(Progress {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.get(key)
+    }
+
+    // Entries whose key starts with `prefix`, e.g. "exercise:".
+    pub fn with_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a Entry)> {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k.starts_with(prefix))
+            .map(|(k, e)| (k.as_str(), e))
+    }
+
+    pub fn record(&mut self, key: &str, status: &str) -> io::Result<()> {
+        let at = date::unix_now();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}\t{}\t{}", key, status, at)?;
+        self.entries.insert(
+            key.to_string(),
+            Entry {
+                status: status.to_string(),
+                at,
+            },
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+// Regenerates the parts of README.md that are derived from the notes.
+//
+// Each generated section sits between `<!-- lessons:NAME -->` and
+// `<!-- /lessons:NAME -->`; everything outside the markers is left alone.
+
+use crate::badge::Badge;
+use crate::catalog::Catalog;
+
+// Directory label and purpose for each catalog category, in README order.
+const CATEGORIES: &[(&str, &str, &str)] = &[
+    ("examples", "examples/", "Basic Rust programs"),
+    ("concepts", "concepts/", "Core language concepts"),
+    (
+        "data_structures",
+        "data_structures/",
+        "Advanced data structures",
+    ),
+    (
+        "advanced_concepts",
+        "advanced_concepts/",
+        "Advanced Rust features",
+    ),
+    ("bin", "bin/", "Executable binaries"),
+    (
+        "more-advanced-datastructure",
+        "more advanced datastructure/",
+        "Generics, traits, lifetimes, threads",
+    ),
+    ("notes", "(repository root)", "Standalone notes"),
+    (
+        "notespush",
+        "notespush/",
+        "Demo catalog: iterators to concurrency",
+    ),
+];
+
+pub fn file_table(catalog: &Catalog) -> String {
+    let mut out = String::from(
+        "| Directory | Purpose | File Count |\n|-----------|---------|------------|\n",
+    );
+    let count = |category: &str| {
+        catalog
+            .lessons()
+            .iter()
+            .filter(|l| l.category == category)
+            .count()
+    };
+    for (category, dir, purpose) in CATEGORIES {
+        let n = count(category);
+        if n > 0 {
+            out += &format!("| 📁 **{}** | {} | {} {} |\n", dir, purpose, n, files(n));
+        }
+    }
+    // Directories added since this table was written. Installed packs are
+    // local to each checkout and stay out of the README.
+    let mut others: Vec<&str> = catalog
+        .lessons()
+        .iter()
+        .filter(|l| l.pack.is_none())
+        .map(|l| l.category.as_str())
+        .filter(|c| !CATEGORIES.iter().any(|(known, _, _)| known == c))
+        .collect();
+    others.sort();
+    others.dedup();
+    for category in others {
+        let n = count(category);
+        out += &format!("| 📁 **{}/** | | {} {} |\n", category, n, files(n));
+    }
+    out
+}
+
+fn files(n: usize) -> &'static str {
+    if n == 1 { "file" } else { "files" }
+}
+
+// Image links for badges written to `dir` (relative to the README), one
+// per line so they sit side by side like the shields.io ones above them.
+pub fn badge_links(badges: &[(&str, Badge)], dir: &str) -> String {
+    badges
+        .iter()
+        .map(|(name, badge)| {
+            format!(
+                "![{}: {}]({}/{}.svg)\n",
+                badge.label, badge.message, dir, name
+            )
+        })
+        .collect()
+}
+
+// Replaces the body of section `name`. Returns `None` when the markers are
+// missing.
+pub fn replace_section(readme: &str, name: &str, body: &str) -> Option<String> {
+    let open = format!("<!-- lessons:{} -->", name);
+    let close = format!("<!-- /lessons:{} -->", name);
+    let start = readme.find(&open)? + open.len();
+    let end = start + readme[start..].find(&close)?;
+    Some(format!("{}\n{}{}", &readme[..start], body, &readme[end..]))
+}
+
+// README.md with every generated section brought up to date.
+pub fn update(readme: &str, catalog: &Catalog) -> Result<String, String> {
+    replace_section(readme, "files", &file_table(catalog))
+        .ok_or_else(|| "README.md has no `<!-- lessons:files -->` section".to_string())
+}
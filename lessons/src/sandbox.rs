@@ -0,0 +1,343 @@
+// Runs compiled snippets as child processes under limits.
+//
+// Each run gets a wall-clock timeout and a cap on captured output. On Linux
+// the child also gets CPU-time and address-space rlimits. The child leads
+// its own process group, so threads and any processes it spawned are
+// killed together when the run ends.
+
+use std::fmt;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub timeout: Duration,
+    // Per stream; output beyond this stops the run.
+    pub output_bytes: usize,
+    // Linux only.
+    pub cpu_secs: u64,
+    pub memory_bytes: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            timeout: Duration::from_secs(10),
+            output_bytes: 64 * 1024,
+            cpu_secs: 10,
+            memory_bytes: 1 << 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Exit(i32),
+    // Still running when the timeout expired.
+    Timeout,
+    // Died from a signal we did not send (a CPU or memory limit, a crash).
+    Killed(i32),
+    // Stopped after writing more than `output_bytes`.
+    Truncated,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Success => write!(f, "success"),
+            Outcome::Exit(code) => write!(f, "exit status {}", code),
+            Outcome::Timeout => write!(f, "timed out"),
+            Outcome::Killed(signal) => write!(f, "killed by signal {}", signal),
+            Outcome::Truncated => write!(f, "output limit reached"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Run {
+    pub outcome: Outcome,
+    pub stdout: String,
+    pub stderr: String,
+    pub elapsed: Duration,
+}
+
+pub fn run(program: &Path, limits: &Limits) -> io::Result<Run> {
+    let mut command = Command::new(program);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    sys::confine(&mut command, limits);
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+    let overflow = Arc::new(AtomicBool::new(false));
+    let stdout = capture(child.stdout.take(), limits.output_bytes, &overflow);
+    let stderr = capture(child.stderr.take(), limits.output_bytes, &overflow);
+
+    let outcome = loop {
+        if let Some(status) = child.try_wait()? {
+            break exit_outcome(status);
+        }
+        if overflow.load(Ordering::Relaxed) {
+            break Outcome::Truncated;
+        }
+        if start.elapsed() >= limits.timeout {
+            break Outcome::Timeout;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+    let elapsed = start.elapsed();
+
+    // Also reaps anything the snippet left running in the background,
+    // which would otherwise hold the pipes open.
+    sys::kill_group(&mut child);
+    let _ = child.wait();
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let outcome = if overflow.load(Ordering::Relaxed) && outcome != Outcome::Timeout {
+        Outcome::Truncated
+    } else {
+        outcome
+    };
+    Ok(Run {
+        outcome,
+        stdout,
+        stderr,
+        elapsed,
+    })
+}
+
+// Reads a pipe on its own thread, keeping at most `cap` bytes.
+fn capture<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    cap: usize,
+    overflow: &Arc<AtomicBool>,
+) -> thread::JoinHandle<String> {
+    let overflow = Arc::clone(overflow);
+    thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return String::new();
+        };
+        let mut kept = Vec::new();
+        let mut buf = [0; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let room = cap - kept.len();
+                    kept.extend_from_slice(&buf[..n.min(room)]);
+                    if n > room {
+                        overflow.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            }
+        }
+        String::from_utf8_lossy(&kept).into_owned()
+    })
+}
+
+fn exit_outcome(status: ExitStatus) -> Outcome {
+    match status.code() {
+        Some(0) => Outcome::Success,
+        Some(code) => Outcome::Exit(code),
+        None => Outcome::Killed(sys::signal(status)),
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::os::raw::c_int;
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::process::{Child, Command, ExitStatus};
+
+    use super::Limits;
+
+    const SIGKILL: c_int = 9;
+
+    unsafe extern "C" {
+        fn kill(pid: c_int, sig: c_int) -> c_int;
+    }
+
+    pub fn confine(command: &mut Command, limits: &Limits) {
+        command.process_group(0);
+        rlimits::apply(command, limits);
+    }
+
+    pub fn kill_group(child: &mut Child) {
+        // The child is its group's leader, so its pid is the group id.
+        // SAFETY: plain syscall; a group that is already gone gives ESRCH.
+        unsafe {
+            kill(-(child.id() as c_int), SIGKILL);
+        }
+    }
+
+    pub fn signal(status: ExitStatus) -> i32 {
+        status.signal().unwrap_or(0)
+    }
+
+    #[cfg(target_os = "linux")]
+    mod rlimits {
+        use std::io;
+        use std::os::raw::{c_int, c_ulong};
+        use std::os::unix::process::CommandExt;
+        use std::process::Command;
+
+        use super::super::Limits;
+
+        const RLIMIT_CPU: c_int = 0;
+        const RLIMIT_AS: c_int = 9;
+
+        // `rlim_t` is an `unsigned long` on Linux, 32 or 64 bits wide.
+        #[repr(C)]
+        struct Rlimit {
+            cur: c_ulong,
+            max: c_ulong,
+        }
+
+        unsafe extern "C" {
+            fn setrlimit(resource: c_int, rlim: *const Rlimit) -> c_int;
+        }
+
+        fn set(resource: c_int, cur: u64, max: u64) -> io::Result<()> {
+            // Too big for a 32-bit `rlim_t` means no limit (RLIM_INFINITY).
+            let limit = Rlimit {
+                cur: c_ulong::try_from(cur).unwrap_or(c_ulong::MAX),
+                max: c_ulong::try_from(max).unwrap_or(c_ulong::MAX),
+            };
+            // SAFETY: `limit` outlives the call and matches `struct rlimit`.
+            if unsafe { setrlimit(resource, &limit) } == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+
+        pub fn apply(command: &mut Command, limits: &Limits) {
+            let (cpu, memory) = (limits.cpu_secs, limits.memory_bytes);
+            // SAFETY: only async-signal-safe syscalls run between fork and
+            // exec.
+            unsafe {
+                command.pre_exec(move || {
+                    // SIGXCPU at the soft limit, SIGKILL a second later.
+                    set(RLIMIT_CPU, cpu, cpu.saturating_add(1))?;
+                    set(RLIMIT_AS, memory, memory)
+                });
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    mod rlimits {
+        use std::process::Command;
+
+        use super::super::Limits;
+
+        pub fn apply(_: &mut Command, _: &Limits) {}
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::process::{Child, Command, ExitStatus};
+
+    use super::Limits;
+
+    pub fn confine(_: &mut Command, _: &Limits) {}
+
+    pub fn kill_group(child: &mut Child) {
+        let _ = child.kill();
+    }
+
+    pub fn signal(_: ExitStatus) -> i32 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn sh(script: &str, limits: &Limits) -> Run {
+        // `run` takes a program path, so wrap the script in an executable.
+        let dir = TempDir::new("sandbox");
+        let path = dir.path().join("script.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        run(&path, limits).unwrap()
+    }
+
+    fn quick() -> Limits {
+        Limits {
+            timeout: Duration::from_millis(500),
+            output_bytes: 1024,
+            ..Limits::default()
+        }
+    }
+
+    #[test]
+    fn captures_output_and_exit_status() {
+        let ok = sh("echo hello; echo oops >&2", &quick());
+        assert_eq!(ok.outcome, Outcome::Success);
+        assert_eq!(ok.stdout, "hello\n");
+        assert_eq!(ok.stderr, "oops\n");
+
+        assert_eq!(sh("exit 101", &quick()).outcome, Outcome::Exit(101));
+
+        // The largest limits mean no limit, not an overflow in the child.
+        let unlimited = Limits {
+            cpu_secs: u64::MAX,
+            memory_bytes: u64::MAX,
+            ..quick()
+        };
+        assert_eq!(sh("echo hi", &unlimited).outcome, Outcome::Success);
+    }
+
+    #[test]
+    fn times_out_and_kills_background_children() {
+        // The background sleep keeps stdout open; if the group were not
+        // killed, joining the reader would wait for it.
+        let started = Instant::now();
+        let run = sh("sleep 30 & sleep 30", &quick());
+        assert_eq!(run.outcome, Outcome::Timeout);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn stops_runaway_output() {
+        let run = sh("while :; do echo spam; done", &quick());
+        assert_eq!(run.outcome, Outcome::Truncated);
+        assert_eq!(run.stdout.len(), 1024);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_signals() {
+        assert_eq!(sh("kill -SEGV $$", &quick()).outcome, Outcome::Killed(11));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn cpu_limit_stops_busy_loops() {
+        let limits = Limits {
+            timeout: Duration::from_secs(10),
+            cpu_secs: 1,
+            ..quick()
+        };
+        let run = sh("while :; do :; done", &limits);
+        assert_eq!(run.outcome, Outcome::Killed(24));
+    }
+}
@@ -0,0 +1,41 @@
+// Runnable snippets: the code blocks of a lesson that define `fn main`.
+// Each one is compiled on its own as a single-file crate.
+
+use crate::blocks::{self, BlockKind};
+use crate::catalog::Lesson;
+
+// For everything this tool compiles, snippets and exercises alike; the
+// same edition as the crate itself.
+pub const EDITION: &str = "2024";
+
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    pub lesson_id: String,
+    // 1-based position among the lesson's code blocks, as in `golden/`.
+    pub block: usize,
+    // Where the block starts in the notes file.
+    pub first_line: usize,
+    pub source: String,
+}
+
+impl Snippet {
+    // A file-name-safe label, e.g. `bin-test-1`.
+    pub fn name(&self) -> String {
+        format!("{}-{}", self.lesson_id.replace('/', "-"), self.block)
+    }
+}
+
+pub fn runnable(lesson: &Lesson, src: &str) -> Vec<Snippet> {
+    blocks::split(src)
+        .into_iter()
+        .filter(|b| b.kind == BlockKind::Code)
+        .enumerate()
+        .filter(|(_, b)| b.text.lines().any(|l| l.starts_with("fn main(")))
+        .map(|(i, b)| Snippet {
+            lesson_id: lesson.id.clone(),
+            block: i + 1,
+            first_line: b.first_line,
+            source: b.text,
+        })
+        .collect()
+}
@@ -0,0 +1,461 @@
+// A spell checker for the prose in the notes.
+//
+// Words are looked up in a built-in English list (dict/english.txt), a list
+// of Rust terms (dict/rust.txt) and the project allowlist, which holds the
+// Hinglish words and names the notes use on purpose. Code is skipped: code
+// blocks (but not their comments), `inline code`, and anything shaped like
+// an identifier, path or URL. Unknown words get suggestions ranked by edit
+// distance.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::blocks::{self, BlockKind};
+use crate::lexer;
+
+const ENGLISH: &str = include_str!("../dict/english.txt");
+const RUST: &str = include_str!("../dict/rust.txt");
+
+const SUGGESTIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Misspelling {
+    pub line: usize,
+    // 1-based, in characters.
+    pub column: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+pub struct Dictionary {
+    // Lowercase.
+    words: HashSet<String>,
+    // Lowercase to the usual spelling, e.g. "hashmap" to "HashMap".
+    terms: HashMap<String, String>,
+}
+
+pub fn default_allowlist() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("allowlist.txt")
+}
+
+// Non-empty lines that are not `#` comments.
+fn entries(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+}
+
+impl Dictionary {
+    pub fn builtin() -> Dictionary {
+        Dictionary {
+            words: entries(ENGLISH).map(str::to_lowercase).collect(),
+            terms: entries(RUST)
+                .map(|t| (t.to_lowercase(), t.to_string()))
+                .collect(),
+        }
+    }
+
+    // The built-in lists plus the allowlist at `path`, if there is one.
+    pub fn load(path: &Path) -> Result<Dictionary, String> {
+        let mut dict = Dictionary::builtin();
+        match fs::read_to_string(path) {
+            Ok(text) => dict.allow(&text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        }
+        Ok(dict)
+    }
+
+    pub fn allow(&mut self, list: &str) {
+        self.words.extend(entries(list).map(str::to_lowercase));
+    }
+
+    pub fn knows(&self, word: &str) -> bool {
+        let lower = word.to_lowercase().replace('’', "'");
+        if self.known(&lower) {
+            return true;
+        }
+        // A CamelCase name made of words, like `MyTrait`, is taken as a type
+        // name, unless it is one slip away from a real one (`HasMap`).
+        let parts = camel_parts(word);
+        parts.len() > 1
+            && parts.iter().all(|p| self.known(&p.to_lowercase()))
+            && !self.terms.keys().any(|t| distance(&lower, t) <= 1)
+    }
+
+    fn known(&self, lower: &str) -> bool {
+        if self.words.contains(lower) || self.terms.contains_key(lower) {
+            return true;
+        }
+        if let Some(base) = lower.strip_suffix("'s").or(lower.strip_suffix("s'")) {
+            return self.words.contains(base) || self.terms.contains_key(base);
+        }
+        if let Some((a, b)) = lower.split_once('-') {
+            // `e-commerce`, `x-axis`
+            return (a.chars().count() == 1 || self.known(a)) && self.known(b);
+        }
+        bases(lower).iter().any(|base| {
+            self.words.contains(base.as_str()) || self.terms.contains_key(base.as_str())
+        })
+    }
+
+    // Closest known words first, at most three.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let limit = match lower.chars().count() {
+            0..=4 => 1,
+            5..=7 => 2,
+            _ => 3,
+        };
+        // (distance, is a split, starts differently, suggestion)
+        let mut found: Vec<(usize, bool, bool, String)> = Vec::new();
+        for (key, shown) in self.words.iter().map(|w| (w, w)).chain(self.terms.iter()) {
+            if key.len().abs_diff(lower.len()) > limit {
+                continue;
+            }
+            let d = distance(&lower, key);
+            if d <= limit {
+                let same_start = key.chars().next() == lower.chars().next();
+                found.push((d, false, !same_start, shown.clone()));
+            }
+        }
+        // Words run together: `shouldthe` is `should the`.
+        for (i, _) in lower.char_indices().skip(2) {
+            let (a, b) = lower.split_at(i);
+            if b.chars().count() >= 2 && self.words.contains(a) && self.words.contains(b) {
+                found.push((1, true, false, format!("{} {}", a, b)));
+            }
+        }
+        found.sort();
+        let mut seen = HashSet::new();
+        found
+            .into_iter()
+            .map(|(_, _, _, s)| match_case(word, &s))
+            .filter(|s| seen.insert(s.clone()))
+            .take(SUGGESTIONS)
+            .collect()
+    }
+}
+
+// What `word` could be an inflection of: `tests` of `test`, `making` of
+// `make`, `stopped` of `stop`, `easily` of `easy`. One suffix only, so
+// `creatinging` is not `creating` plus `ing`.
+fn bases(word: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let rules: &[(&str, &[&str])] = &[
+        ("ies", &["y"]),
+        ("ied", &["y"]),
+        ("ier", &["y"]),
+        ("iest", &["y"]),
+        ("ily", &["y"]),
+        ("ally", &["", "al"]),
+        ("es", &["", "e"]),
+        ("s", &[""]),
+        ("ed", &["", "e"]),
+        ("ing", &["", "e"]),
+        ("ly", &["", "le"]),
+        ("er", &["", "e"]),
+        ("ers", &["", "e"]),
+        ("est", &["", "e"]),
+        ("ness", &[""]),
+        ("ment", &[""]),
+        ("ments", &[""]),
+        ("able", &["", "e"]),
+        ("ability", &["", "e", "able"]),
+        ("ation", &["", "e"]),
+        ("ations", &["", "e"]),
+        ("ful", &[""]),
+        ("less", &[""]),
+    ];
+    for (suffix, endings) in rules {
+        let Some(stem) = word.strip_suffix(suffix) else {
+            continue;
+        };
+        if stem.len() < 2 || stem.ends_with(suffix) {
+            continue;
+        }
+        for ending in *endings {
+            out.push(format!("{}{}", stem, ending));
+        }
+        // `running`, `stopped`: a doubled final consonant.
+        // Exactly doubled: `passsing` is not `pass` plus `sing`.
+        let mut chars = stem.chars().rev();
+        if let (Some(a), Some(b)) = (chars.next(), chars.next())
+            && a == b
+            && chars.next() != Some(a)
+            && !"aeiou".contains(a)
+        {
+            out.push(stem[..stem.len() - a.len_utf8()].to_string());
+        }
+    }
+    for prefix in ["un", "re", "non", "pre", "multi", "sub", "over", "under"] {
+        if let Some(rest) = word.strip_prefix(prefix)
+            && rest.len() >= 3
+        {
+            out.push(rest.to_string());
+            out.extend(bases(rest).into_iter().filter(|b| !b.starts_with(prefix)));
+        }
+    }
+    out
+}
+
+fn camel_parts(word: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, c) in word.char_indices().skip(1) {
+        if c.is_uppercase() {
+            parts.push(&word[start..i]);
+            start = i;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+// `Genrics` gets `Generics`; Rust terms keep their own spelling.
+fn match_case(word: &str, suggestion: &str) -> String {
+    let capital = word.starts_with(char::is_uppercase);
+    if capital && suggestion.chars().all(|c| !c.is_uppercase()) {
+        let mut chars = suggestion.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        suggestion.to_string()
+    }
+}
+
+// Optimal string alignment distance: insertions, deletions, substitutions
+// and swaps of neighbouring letters (`cdoe`) each cost one.
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+// The prose of a notes file: prose blocks, and comments in code blocks.
+pub fn check_notes(src: &str, dict: &Dictionary) -> Vec<Misspelling> {
+    let mut out = Vec::new();
+    for block in blocks::split(src) {
+        match block.kind {
+            BlockKind::Prose => {
+                for (i, line) in block.text.lines().enumerate() {
+                    check_line(line, block.first_line + i, 0, dict, &mut out);
+                }
+            }
+            BlockKind::Code => {
+                for token in lexer::tokenize(&block.text) {
+                    if !token.is_comment() {
+                        continue;
+                    }
+                    // Column offsets are counted from the start of the line.
+                    let line_start = block.text[..token.start].rfind('\n').map_or(0, |i| i + 1);
+                    let before = block.text[line_start..token.start].chars().count();
+                    for (i, line) in token.text(&block.text).lines().enumerate() {
+                        let line_no = block.first_line + token.line - 1 + i;
+                        let offset = if i == 0 { before } else { 0 };
+                        check_line(line, line_no, offset, dict, &mut out);
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+// A markdown file, without fenced code.
+pub fn check_markdown(src: &str, dict: &Dictionary) -> Vec<Misspelling> {
+    let mut out = Vec::new();
+    let mut fenced = false;
+    for (i, line) in src.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            fenced = !fenced;
+        } else if !fenced {
+            check_line(line, i + 1, 0, dict, &mut out);
+        }
+    }
+    out
+}
+
+fn check_line(
+    line: &str,
+    line_no: usize,
+    offset: usize,
+    dict: &Dictionary,
+    out: &mut Vec<Misspelling>,
+) {
+    // Blank out `inline code` so columns stay right.
+    let mut text = String::with_capacity(line.len());
+    let mut code = false;
+    for c in line.chars() {
+        if c == '`' {
+            code = !code;
+        }
+        text.push(if code || c == '`' { ' ' } else { c });
+    }
+
+    let mut column = offset;
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let start = column;
+        column += chunk.chars().count();
+        let chunk = chunk.trim_end();
+        if looks_like_code(chunk) {
+            continue;
+        }
+        let mut pos = 0;
+        for word in words(chunk) {
+            let at = chunk[pos..].find(word).map_or(pos, |i| pos + i);
+            pos = at + word.len();
+            let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+            // Acronyms, also in the plural: `API`, `URLs`.
+            let acronym = word
+                .strip_suffix('s')
+                .unwrap_or(word)
+                .chars()
+                .all(|c| !c.is_lowercase());
+            if letters < 2 || acronym || dict.knows(word) {
+                continue;
+            }
+            out.push(Misspelling {
+                line: line_no,
+                column: start + chunk[..at].chars().count() + 1,
+                word: word.to_string(),
+                suggestions: dict.suggest(word),
+            });
+        }
+    }
+}
+
+// Runs of letters, with apostrophes and hyphens inside words kept.
+fn words(chunk: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = None;
+    let chars: Vec<(usize, char)> = chunk.char_indices().collect();
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        let inner = matches!(c, '\'' | '’' | '-')
+            && start.is_some()
+            && chars
+                .get(n + 1)
+                .is_some_and(|(_, next)| next.is_alphabetic());
+        if c.is_alphabetic() || inner {
+            start.get_or_insert(i);
+        } else if let Some(s) = start.take() {
+            out.push(&chunk[s..i]);
+        }
+    }
+    if let Some(s) = start {
+        out.push(&chunk[s..]);
+    }
+    out
+}
+
+// Identifiers, paths, URLs, HTML and calls, which are not prose even
+// outside backticks.
+fn looks_like_code(chunk: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "::", "_", "=", "{", "}", "<", ">", "/", "\\", "@", "#", "&", "*", "[", "$", "|",
+    ];
+    if MARKERS.iter().any(|m| chunk.contains(m)) {
+        return true;
+    }
+    let chars: Vec<char> = chunk.chars().collect();
+    chars.windows(2).any(|w| {
+        (w[0].is_alphabetic() && (w[1].is_ascii_digit() || w[1] == '(' || w[1] == '!'))
+            || (w[0].is_ascii_digit() && w[1].is_alphabetic())
+            || (w[0] == '.' && w[1].is_alphabetic())
+    }) && !chunk.ends_with('!')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dict() -> Dictionary {
+        let mut dict = Dictionary::builtin();
+        dict.allow("# Hinglish\nhai\nkar\n");
+        dict
+    }
+
+    fn flagged(src: &str) -> Vec<(usize, usize, String, Vec<String>)> {
+        check_notes(src, &dict())
+            .into_iter()
+            .map(|m| (m.line, m.column, m.word, m.suggestions))
+            .collect()
+    }
+
+    #[test]
+    fn flags_typos_in_prose_and_comments_only() {
+        let src = "\
+Using threads to run cdoe simultaneously
+Genrics mean the types are filled in later, kar hai
+fn main() {
+    let hasmap = 1; // store it in a HasMap
+}
+";
+        let found = flagged(src);
+        let words: Vec<&str> = found.iter().map(|f| f.2.as_str()).collect();
+        assert_eq!(words, ["cdoe", "Genrics", "HasMap"]);
+        assert_eq!((found[0].0, found[0].1), (1, 22));
+        assert_eq!(found[0].3[0], "code");
+        assert_eq!(found[1].3[0], "Generics");
+        assert_eq!((found[2].0, found[2].1), (4, 38));
+        assert_eq!(found[2].3[0], "HashMap");
+    }
+
+    #[test]
+    fn suggests_by_edit_distance_and_splits_run_together_words() {
+        let dict = dict();
+        assert_eq!(dict.suggest("passsing")[0], "passing");
+        assert_eq!(dict.suggest("creatinging")[0], "creating");
+        assert!(
+            dict.suggest("shouldthe")
+                .contains(&"should the".to_string())
+        );
+        assert_eq!(distance("cdoe", "code"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn knows_inflections_names_and_code() {
+        let dict = dict();
+        for word in [
+            "borrowing",
+            "stopped",
+            "easily",
+            "libraries",
+            "HashMaps",
+            "Rust's",
+            "MyTrait",
+            "don't",
+            "well-known",
+            "unsafe",
+            "e-commerce",
+        ] {
+            assert!(dict.knows(word), "{}", word);
+        }
+        assert!(!dict.knows("creatinging"));
+        assert!(!dict.knows("passsing"));
+        assert!(!dict.knows("HasMap"));
+        let md = "See `cdoe` and https://example.com/cdoe or `x.cdoe()`, API, URLs, std::cdoe.\n\
+                  ```\nlet cdoe = 1;\n```\n";
+        assert_eq!(check_markdown(md, &dict), []);
+    }
+}
@@ -0,0 +1,508 @@
+// Aligned text tables for demo and tool output, so a map prints as rows in
+// a stable order instead of a `{:?}` dump in hash order.
+//
+// Anything that is a `Row` can go in: tuples of displayable values (which
+// covers map entries), or a struct with a `Row` impl of its own. Numbers
+// are right-aligned and sort numerically; widths are counted in terminal
+// columns so emoji and CJK text line up.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::ansi;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Border {
+    Unicode,
+    Ascii,
+}
+
+impl Border {
+    // Box drawing when the locale says UTF-8, plain ASCII otherwise.
+    pub fn from_env() -> Border {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if locale.contains("utf-8") || locale.contains("utf8") {
+            Border::Unicode
+        } else {
+            Border::Ascii
+        }
+    }
+
+    // Horizontal line, then the left, middle and right joints of the top,
+    // header separator and bottom rules, then the vertical line.
+    fn chars(self) -> [char; 11] {
+        match self {
+            Border::Unicode => ['─', '┌', '┬', '┐', '├', '┼', '┤', '└', '┴', '┘', '│'],
+            Border::Ascii => ['-', '+', '+', '+', '+', '+', '+', '+', '+', '+', '|'],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub text: String,
+    pub align: Align,
+    number: Option<f64>,
+}
+
+impl Cell {
+    pub fn text(text: impl Into<String>) -> Cell {
+        Cell {
+            text: text.into(),
+            align: Align::Left,
+            number: None,
+        }
+    }
+
+    pub fn number(value: f64, text: impl Into<String>) -> Cell {
+        Cell {
+            text: text.into(),
+            align: Align::Right,
+            number: Some(value),
+        }
+    }
+
+    // Numbers before text, numbers by value, text by its characters.
+    fn compare(&self, other: &Cell) -> Ordering {
+        match (self.number, other.number) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.text.cmp(&other.text),
+        }
+    }
+}
+
+pub trait ToCell {
+    fn to_cell(&self) -> Cell;
+}
+
+impl ToCell for Cell {
+    fn to_cell(&self) -> Cell {
+        self.clone()
+    }
+}
+
+impl ToCell for str {
+    fn to_cell(&self) -> Cell {
+        Cell::text(self)
+    }
+}
+
+impl ToCell for String {
+    fn to_cell(&self) -> Cell {
+        Cell::text(self.as_str())
+    }
+}
+
+impl ToCell for char {
+    fn to_cell(&self) -> Cell {
+        Cell::text(self.to_string())
+    }
+}
+
+impl ToCell for bool {
+    fn to_cell(&self) -> Cell {
+        Cell::text(if *self { "yes" } else { "no" })
+    }
+}
+
+impl<T: ToCell + ?Sized> ToCell for &T {
+    fn to_cell(&self) -> Cell {
+        (**self).to_cell()
+    }
+}
+
+// An empty cell for `None`.
+impl<T: ToCell> ToCell for Option<T> {
+    fn to_cell(&self) -> Cell {
+        match self {
+            Some(value) => value.to_cell(),
+            None => Cell::text(""),
+        }
+    }
+}
+
+// Skills and tags, comma-separated in one cell.
+impl<T: ToCell> ToCell for Vec<T> {
+    fn to_cell(&self) -> Cell {
+        let parts: Vec<String> = self.iter().map(|v| v.to_cell().text).collect();
+        Cell::text(parts.join(", "))
+    }
+}
+
+macro_rules! number_cells {
+    ($($t:ty),*) => {$(
+        impl ToCell for $t {
+            fn to_cell(&self) -> Cell {
+                Cell::number(*self as f64, self.to_string())
+            }
+        }
+    )*};
+}
+
+number_cells!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+pub trait Row {
+    fn cells(&self) -> Vec<Cell>;
+}
+
+impl<R: Row + ?Sized> Row for &R {
+    fn cells(&self) -> Vec<Cell> {
+        (**self).cells()
+    }
+}
+
+impl<T: ToCell> Row for [T] {
+    fn cells(&self) -> Vec<Cell> {
+        self.iter().map(ToCell::to_cell).collect()
+    }
+}
+
+impl<T: ToCell> Row for Vec<T> {
+    fn cells(&self) -> Vec<Cell> {
+        self.as_slice().cells()
+    }
+}
+
+macro_rules! tuple_rows {
+    ($(($($t:ident $i:tt),*)),*) => {$(
+        impl<$($t: ToCell),*> Row for ($($t,)*) {
+            fn cells(&self) -> Vec<Cell> {
+                vec![$(self.$i.to_cell()),*]
+            }
+        }
+    )*};
+}
+
+tuple_rows!(
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+    (A 0, B 1, C 2, D 3, E 4),
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+    border: Border,
+}
+
+impl Table {
+    pub fn new(header: &[&str]) -> Table {
+        Table {
+            header: header.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+            border: Border::Unicode,
+        }
+    }
+
+    pub fn push(&mut self, row: impl Row) {
+        self.rows.push(row.cells());
+    }
+
+    pub fn set_border(&mut self, border: Border) {
+        self.border = border;
+    }
+
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    pub fn rows(&self) -> &[Vec<Cell>] {
+        &self.rows
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    // Stable, so sorting by one column and then another gives rows ordered
+    // by the second with ties kept in the order of the first.
+    pub fn sort_by(&mut self, column: usize) {
+        let empty = Cell::text("");
+        self.rows.sort_by(|a, b| {
+            let a = a.get(column).unwrap_or(&empty);
+            let b = b.get(column).unwrap_or(&empty);
+            a.compare(b)
+        });
+    }
+
+    // Largest first; not `sort_by` then reverse, which would flip ties.
+    pub fn sort_by_desc(&mut self, column: usize) {
+        let empty = Cell::text("");
+        self.rows.sort_by(|a, b| {
+            let a = a.get(column).unwrap_or(&empty);
+            let b = b.get(column).unwrap_or(&empty);
+            b.compare(a)
+        });
+    }
+
+    fn columns(&self) -> usize {
+        self.rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.header.len()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Right when every filled cell under the header is a number.
+    fn align(&self, column: usize) -> Align {
+        let mut cells = self
+            .rows
+            .iter()
+            .filter_map(|row| row.get(column))
+            .filter(|cell| !cell.text.is_empty())
+            .peekable();
+        if cells.peek().is_some() && cells.all(|cell| cell.align == Align::Right) {
+            Align::Right
+        } else {
+            Align::Left
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let columns = self.columns();
+        let header: Vec<Vec<String>> = self.header.iter().map(|h| cell_lines(h)).collect();
+        let rows: Vec<Vec<Vec<String>>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell_lines(&cell.text)).collect())
+            .collect();
+        let widths: Vec<usize> = (0..columns)
+            .map(|c| {
+                rows.iter()
+                    .chain([&header])
+                    .filter_map(|row| row.get(c))
+                    .flatten()
+                    .map(|line| ansi::width(line))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let aligns: Vec<Align> = (0..columns).map(|c| self.align(c)).collect();
+        let [h, tl, tm, tr, ml, mm, mr, bl, bm, br, v] = self.border.chars();
+
+        let rule = |left: char, middle: char, right: char| {
+            let mut line = String::from(left);
+            for (i, width) in widths.iter().enumerate() {
+                if i > 0 {
+                    line.push(middle);
+                }
+                line.extend(std::iter::repeat_n(h, width + 2));
+            }
+            line.push(right);
+            line.push('\n');
+            line
+        };
+        // As many lines as its tallest cell.
+        let row = |cells: &[Vec<String>]| {
+            let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+            let mut out = String::new();
+            for n in 0..height {
+                out.push(v);
+                for (i, width) in widths.iter().enumerate() {
+                    let text = cells
+                        .get(i)
+                        .and_then(|c| c.get(n))
+                        .map_or("", String::as_str);
+                    let pad = " ".repeat(width - ansi::width(text));
+                    match aligns[i] {
+                        Align::Left => out += &format!(" {}{} ", text, pad),
+                        Align::Right => out += &format!(" {}{} ", pad, text),
+                    }
+                    out.push(v);
+                }
+                out.push('\n');
+            }
+            out
+        };
+
+        let mut out = rule(tl, tm, tr);
+        if !self.header.is_empty() {
+            out += &row(&header);
+            out += &rule(ml, mm, mr);
+        }
+        for cells in &rows {
+            out += &row(cells);
+        }
+        out += &rule(bl, bm, br);
+        out
+    }
+}
+
+// A cell as drawn: tabs expanded, and each line break starting a
+// continuation line in the same row, so the borders stay straight.
+fn cell_lines(text: &str) -> Vec<String> {
+    let lines: Vec<String> = ansi::expand_tabs(text)
+        .lines()
+        .map(|line| line.replace('\r', ""))
+        .collect();
+    if lines.is_empty() {
+        vec![String::new()]
+    } else {
+        lines
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+// `scores.iter().tabulate(&["Name", "Score"])` for any iterator of rows.
+pub trait Tabulate: Iterator + Sized
+where
+    Self::Item: Row,
+{
+    fn tabulate(self, header: &[&str]) -> Table {
+        let mut table = Table::new(header);
+        for row in self {
+            table.push(row);
+        }
+        table
+    }
+}
+
+impl<I> Tabulate for I
+where
+    I: Iterator,
+    I::Item: Row,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn renders_map_entries_sorted() {
+        let scores: HashMap<&str, u32> = [("Charlie", 92), ("Alice", 95), ("Bob", 87)].into();
+        let mut table = scores.iter().tabulate(&["Name", "Score"]);
+        table.sort_by_desc(1);
+        assert_eq!(
+            table.to_string(),
+            "\
+┌─────────┬───────┐
+│ Name    │ Score │
+├─────────┼───────┤
+│ Alice   │    95 │
+│ Charlie │    92 │
+│ Bob     │    87 │
+└─────────┴───────┘
+"
+        );
+        table.sort_by(0);
+        table.set_border(Border::Ascii);
+        assert_eq!(
+            table.to_string(),
+            "\
++---------+-------+
+| Name    | Score |
++---------+-------+
+| Alice   |    95 |
+| Bob     |    87 |
+| Charlie |    92 |
++---------+-------+
+"
+        );
+    }
+
+    struct Person {
+        name: &'static str,
+        age: u32,
+        skills: Vec<&'static str>,
+    }
+
+    impl Row for Person {
+        fn cells(&self) -> Vec<Cell> {
+            vec![
+                self.name.to_cell(),
+                self.age.to_cell(),
+                self.skills.to_cell(),
+            ]
+        }
+    }
+
+    #[test]
+    fn lines_up_structs_with_wide_characters() {
+        let people = [
+            Person {
+                name: "🦀 Ferris",
+                age: 9,
+                skills: vec!["Rust"],
+            },
+            Person {
+                name: "山田",
+                age: 31,
+                skills: vec!["Python", "Go"],
+            },
+        ];
+        let mut table = people.iter().tabulate(&["Name", "Age", "Skills"]);
+        table.set_border(Border::Ascii);
+        let lines: Vec<String> = table.render().lines().map(String::from).collect();
+        assert_eq!(lines[3], "| 🦀 Ferris |   9 | Rust       |");
+        assert_eq!(lines[4], "| 山田      |  31 | Python, Go |");
+        assert!(
+            lines
+                .iter()
+                .all(|l| ansi::width(l) == ansi::width(&lines[0]))
+        );
+    }
+
+    #[test]
+    fn breaks_multi_line_cells_and_expands_tabs() {
+        let mut table = Table::new(&["key", "note"]);
+        table.set_border(Border::Ascii);
+        table.push(("a\tb", "first\r\nsecond line"));
+        table.push(("c", ""));
+        assert_eq!(
+            table.to_string(),
+            "\
++--------+-------------+
+| key    | note        |
++--------+-------------+
+| a    b | first       |
+|        | second line |
+| c      |             |
++--------+-------------+
+"
+        );
+    }
+
+    #[test]
+    fn sorts_numbers_by_value_and_keeps_ties_stable() {
+        let mut table = Table::new(&["n", "tag"]);
+        for row in [(10, "a"), (9, "b"), (10, "c"), (100, "d")] {
+            table.push(row);
+        }
+        table.sort_by(0);
+        let tags: Vec<&str> = table.rows.iter().map(|r| r[1].text.as_str()).collect();
+        assert_eq!(tags, ["b", "a", "c", "d"]);
+        table.sort_by_desc(0);
+        let tags: Vec<&str> = table.rows.iter().map(|r| r[1].text.as_str()).collect();
+        assert_eq!(tags, ["d", "a", "c", "b"]);
+    }
+}
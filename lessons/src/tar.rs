@@ -0,0 +1,167 @@
+// Minimal ustar archives: regular files only, which is all lesson packs
+// need. Packs written here open with any `tar`, and `tar -cf` output made
+// from plain files reads back.
+
+const BLOCK: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+pub fn write(entries: &[Entry]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for entry in entries {
+        let mut header = [0u8; BLOCK];
+        let (prefix, name) = split_path(&entry.path)?;
+        put(&mut header[0..100], name.as_bytes());
+        put(&mut header[100..108], b"0000644\0");
+        put(&mut header[108..116], b"0000000\0");
+        put(&mut header[116..124], b"0000000\0");
+        put(
+            &mut header[124..136],
+            format!("{:011o}\0", entry.data.len()).as_bytes(),
+        );
+        // A fixed mtime keeps archives reproducible.
+        put(&mut header[136..148], b"00000000000\0");
+        header[156] = b'0';
+        put(&mut header[257..263], b"ustar\0");
+        put(&mut header[263..265], b"00");
+        put(&mut header[345..500], prefix.as_bytes());
+
+        header[148..156].fill(b' ');
+        let sum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+        put(&mut header[148..156], format!("{:06o}\0 ", sum).as_bytes());
+
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&entry.data);
+        out.resize(out.len().next_multiple_of(BLOCK), 0);
+    }
+    // Two zero blocks end the archive.
+    out.resize(out.len() + 2 * BLOCK, 0);
+    Ok(out)
+}
+
+pub fn read(bytes: &[u8]) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos + BLOCK <= bytes.len() {
+        let header = &bytes[pos..pos + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            return Ok(entries);
+        }
+        let stored = octal(&header[148..156]).ok_or("bad header checksum field")?;
+        let sum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    u64::from(b)
+                }
+            })
+            .sum();
+        if sum != stored {
+            return Err(format!("corrupt header at byte {}", pos));
+        }
+
+        let size = octal(&header[124..136]).ok_or("bad size field")? as usize;
+        let start = pos + BLOCK;
+        let data = bytes
+            .get(start..start + size)
+            .ok_or("archive is truncated")?;
+        // '0' and NUL are regular files; directories and the rest are
+        // skipped.
+        if matches!(header[156], b'0' | 0) {
+            let name = text(&header[0..100]);
+            let prefix = text(&header[345..500]);
+            let path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            entries.push(Entry {
+                path,
+                data: data.to_vec(),
+            });
+        }
+        pos = start + size.next_multiple_of(BLOCK);
+    }
+    Err("archive is truncated".to_string())
+}
+
+// ustar keeps up to 155 bytes of directory in `prefix` and 100 in `name`.
+fn split_path(path: &str) -> Result<(&str, &str), String> {
+    if path.len() <= 100 {
+        return Ok(("", path));
+    }
+    path.match_indices('/')
+        .map(|(i, _)| (&path[..i], &path[i + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100 && !name.is_empty())
+        .ok_or_else(|| format!("path too long for ustar: {}", path))
+}
+
+fn put(field: &mut [u8], value: &[u8]) {
+    field[..value.len()].copy_from_slice(value);
+}
+
+fn octal(field: &[u8]) -> Option<u64> {
+    let s = text(field);
+    u64::from_str_radix(s.trim(), 8).ok()
+}
+
+fn text(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::process::Command;
+
+    #[test]
+    fn round_trips_and_matches_system_tar() {
+        let long = format!("lessons/{}/notes.rs", "deep".repeat(30));
+        let entries = vec![
+            Entry {
+                path: "pack.toml".to_string(),
+                data: b"[pack]\nname = \"demo\"\n".to_vec(),
+            },
+            Entry {
+                path: long.clone(),
+                data: vec![b'x'; 1500],
+            },
+        ];
+        let bytes = write(&entries).unwrap();
+        assert_eq!(bytes.len() % BLOCK, 0);
+        assert_eq!(read(&bytes).unwrap(), entries);
+
+        // Cross-check with the system tar where there is one.
+        let dir = TempDir::new("tar");
+        let file = dir.path().join("demo.tar");
+        std::fs::write(&file, &bytes).unwrap();
+        if let Ok(out) = Command::new("tar").arg("-tf").arg(&file).output() {
+            let listed = String::from_utf8_lossy(&out.stdout);
+            assert_eq!(
+                listed.lines().collect::<Vec<_>>(),
+                ["pack.toml", long.as_str()]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_corruption() {
+        let mut bytes = write(&[Entry {
+            path: "a.rs".to_string(),
+            data: b"fn main() {}".to_vec(),
+        }])
+        .unwrap();
+        assert!(read(&bytes[..BLOCK + 4]).is_err());
+        bytes[0] = b'b';
+        assert!(read(&bytes).unwrap_err().contains("corrupt"));
+    }
+}
@@ -0,0 +1,280 @@
+// Repository tasks (push, run lessons, checks, README) read from
+// `tasks.toml` at the repository root. This replaces quick-push.sh and
+// quick-push.bat, so the workflow is the same on every platform.
+//
+// Each `[task]` has a `description` and `steps`, a list of commands given
+// as `["program", "arg", ...]`. Arguments may use `{name}` for a value from
+// `[vars]` or for `{message}`, the commit message.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::date;
+use crate::toml::{self, Value};
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct TaskFile {
+    pub vars: BTreeMap<String, String>,
+    pub tasks: Vec<Task>,
+}
+
+impl TaskFile {
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join("tasks.toml")
+    }
+
+    pub fn parse(text: &str) -> Result<TaskFile, String> {
+        let doc = toml::parse(text).map_err(|e| e.to_string())?;
+        let mut vars = BTreeMap::new();
+        let mut tasks = Vec::new();
+        for (name, value) in &doc {
+            let table = value
+                .as_table()
+                .ok_or_else(|| format!("`{}` must be a table", name))?;
+            if name == "vars" {
+                for (key, value) in table {
+                    let value = value
+                        .as_str()
+                        .ok_or_else(|| format!("vars.{} must be a string", key))?;
+                    vars.insert(key.clone(), value.to_string());
+                }
+                continue;
+            }
+
+            let steps = table
+                .get("steps")
+                .and_then(Value::as_array)
+                .ok_or_else(|| format!("task `{}` needs `steps`", name))?;
+            let steps = steps
+                .iter()
+                .map(|step| {
+                    let words: Option<Vec<String>> = step
+                        .as_array()
+                        .map(|w| w.iter().map(|a| a.as_str().map(str::to_string)).collect())
+                        .unwrap_or_default();
+                    match words {
+                        Some(words) if !words.is_empty() => Ok(words),
+                        _ => Err(format!(
+                            "task `{}`: each step must be a non-empty list of strings",
+                            name
+                        )),
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            tasks.push(Task {
+                name: name.clone(),
+                description: table
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                steps,
+            });
+        }
+        Ok(TaskFile { vars, tasks })
+    }
+
+    pub fn load(path: &Path) -> Result<TaskFile, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        TaskFile::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.name == name)
+    }
+}
+
+impl Task {
+    pub fn uses(&self, var: &str) -> bool {
+        let pattern = format!("{{{}}}", var);
+        self.steps.iter().flatten().any(|a| a.contains(&pattern))
+    }
+}
+
+// Replaces `{name}` with its value. Unknown names are an error rather than
+// being passed through to git.
+pub fn expand(arg: &str, vars: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        let value = vars
+            .get(name)
+            .ok_or_else(|| format!("unknown variable `{{{}}}`", name))?;
+        out += &rest[..start];
+        out += value;
+        rest = &rest[start + len + 1..];
+    }
+    out += rest;
+    Ok(out)
+}
+
+// Runs the steps in order from `dir`, echoing each one, and stops at the
+// first that fails.
+pub fn run(task: &Task, vars: &BTreeMap<String, String>, dir: &Path) -> Result<(), String> {
+    for step in &task.steps {
+        let words = step
+            .iter()
+            .map(|w| expand(w, vars))
+            .collect::<Result<Vec<_>, _>>()?;
+        println!("$ {}", words.join(" "));
+        let status = Command::new(&words[0])
+            .args(&words[1..])
+            .current_dir(dir)
+            .status()
+            .map_err(|e| format!("{}: {}", words[0], e))?;
+        if !status.success() {
+            return Err(format!("`{}` failed ({})", words.join(" "), status));
+        }
+    }
+    Ok(())
+}
+
+// The commit message used when none is given, as the old scripts did:
+// `Update: %Y-%m-%d %H:%M:%S` in local time, or in UTC, marked as such,
+// where the local time cannot be had.
+pub fn default_message() -> String {
+    let stamp = date::local_timestamp()
+        .unwrap_or_else(|| format!("{} UTC", date::timestamp(date::unix_now())));
+    format!("Update: {}", stamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const PUSH: &str = r#"
+[vars]
+remote = "origin"
+branch = "main"
+
+[push]
+description = "Add, commit and push every change"
+steps = [
+    ["git", "add", "."],
+    ["git", "commit", "-q", "-m", "{message}"],
+    ["git", "push", "-q", "{remote}", "{branch}"],
+]
+"#;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&out.stderr)
+        );
+        String::from_utf8(out.stdout).unwrap()
+    }
+
+    // A clone-like working repo whose `origin` is a fresh bare repository.
+    fn repos(name: &str) -> (TempDir, PathBuf) {
+        let base = TempDir::new(name);
+        let (remote, work) = (base.path().join("remote.git"), base.path().join("work"));
+        fs::create_dir_all(&remote).unwrap();
+        fs::create_dir_all(&work).unwrap();
+        git(&remote, &["init", "-q", "--bare", "-b", "main"]);
+        git(&work, &["init", "-q", "-b", "main"]);
+        git(&work, &["config", "user.name", "Learner"]);
+        git(&work, &["config", "user.email", "learner@example.com"]);
+        git(
+            &work,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+        (base, work)
+    }
+
+    fn is_default_message(subject: &str) -> bool {
+        subject
+            .strip_prefix("Update: ")
+            .is_some_and(date::is_timestamp)
+    }
+
+    #[test]
+    fn default_message_matches_the_old_scripts() {
+        assert!(is_default_message(&default_message()));
+    }
+
+    // The scripts used local time; the UTC fallback is for machines where
+    // `date` cannot tell it.
+    #[cfg(unix)]
+    #[test]
+    fn default_message_uses_local_time() {
+        assert!(!default_message().ends_with(" UTC"));
+    }
+
+    #[test]
+    fn expands_variables() {
+        let vars = BTreeMap::from([("remote".to_string(), "origin".to_string())]);
+        assert_eq!(expand("{remote}/main", &vars).unwrap(), "origin/main");
+        assert_eq!(expand("no braces", &vars).unwrap(), "no braces");
+        assert!(expand("{branch}", &vars).is_err());
+    }
+
+    #[test]
+    fn push_commits_with_the_default_message_and_pushes() {
+        let (base, work) = repos("tasks-push");
+        fs::write(work.join("notes.rs"), "fn main() {}\n").unwrap();
+
+        let file = TaskFile::parse(PUSH).unwrap();
+        let push = file.get("push").unwrap();
+        assert!(push.uses("message"));
+        let mut vars = file.vars.clone();
+        vars.insert("message".to_string(), default_message());
+        run(push, &vars, &work).unwrap();
+
+        let remote = base.path().join("remote.git");
+        let subject = git(&remote, &["log", "-1", "--format=%s", "main"]);
+        assert!(is_default_message(subject.trim()), "{:?}", subject);
+        let files = git(&remote, &["ls-tree", "--name-only", "main"]);
+        assert_eq!(files.trim(), "notes.rs");
+    }
+
+    #[test]
+    fn stops_at_the_first_failing_step() {
+        let (base, work) = repos("tasks-fail");
+        let file = TaskFile::parse(PUSH).unwrap();
+        let mut vars = file.vars.clone();
+        vars.insert("message".to_string(), "nothing to commit".to_string());
+
+        // Nothing is staged, so `git commit` fails and nothing is pushed.
+        let err = run(file.get("push").unwrap(), &vars, &work).unwrap_err();
+        assert!(err.contains("git commit"), "{}", err);
+        let branches = git(&base.path().join("remote.git"), &["branch"]);
+        assert!(branches.is_empty());
+    }
+
+    #[test]
+    fn repository_task_file_is_valid() {
+        let path = TaskFile::default_path(&crate::catalog::default_root());
+        let file = TaskFile::load(&path).unwrap();
+        for name in ["push", "lessons", "check", "readme"] {
+            let task = file
+                .get(name)
+                .unwrap_or_else(|| panic!("no `{}` task", name));
+            for word in task.steps.iter().flatten() {
+                let mut vars = file.vars.clone();
+                vars.insert("message".to_string(), String::new());
+                expand(word, &vars).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+// Just enough terminal handling for `lessons browse`: raw mode, the
+// window size, and decoding key presses from the bytes a terminal sends.
+//
+// Raw mode goes through `stty` so no termios layout has to be declared
+// here; the window size comes from the TIOCGWINSZ ioctl because it is
+// polled several times a second to notice resizes.
+
+use std::env;
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Tab,
+    BackTab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+// Decodes everything in `bytes`. Sequences we do not know are dropped
+// whole rather than read as stray characters.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let text = String::from_utf8_lossy(bytes);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' => match chars.peek() {
+                Some('[' | 'O') => {
+                    chars.next();
+                    let mut params = String::new();
+                    let mut last = None;
+                    for c in chars.by_ref() {
+                        if c.is_ascii_digit() || c == ';' {
+                            params.push(c);
+                        } else {
+                            last = Some(c);
+                            break;
+                        }
+                    }
+                    match (last, params.as_str()) {
+                        (Some('A'), _) => Key::Up,
+                        (Some('B'), _) => Key::Down,
+                        (Some('C'), _) => Key::Right,
+                        (Some('D'), _) => Key::Left,
+                        (Some('H'), _) | (Some('~'), "1" | "7") => Key::Home,
+                        (Some('F'), _) | (Some('~'), "4" | "8") => Key::End,
+                        (Some('Z'), _) => Key::BackTab,
+                        (Some('~'), "5") => Key::PageUp,
+                        (Some('~'), "6") => Key::PageDown,
+                        _ => continue,
+                    }
+                }
+                _ => Key::Esc,
+            },
+            '\r' | '\n' => Key::Enter,
+            '\t' => Key::Tab,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x01'..='\x1a' => Key::Ctrl((b'a' + c as u8 - 1) as char),
+            c if c.is_control() => continue,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+// Raw input, no echo, and reads that give up after a tenth of a second so
+// the caller can check for resizes. Dropping it restores the terminal.
+pub struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    pub fn enter() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+        let mut out = io::stdout();
+        // Alternate screen, hidden cursor.
+        out.write_all(b"\x1b[?1049h\x1b[?25l")?;
+        out.flush()?;
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+
+    // Keys pressed since the last call; empty after the read timeout.
+    pub fn read_keys(&self) -> io::Result<Vec<Key>> {
+        let mut buf = [0u8; 64];
+        let n = io::stdin().read(&mut buf)?;
+        Ok(parse_keys(&buf[..n]))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        leave();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn leave() {
+    let mut out = io::stdout();
+    let _ = out.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l");
+    let _ = out.flush();
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !out.status.success() {
+        return Err(io::Error::other(format!(
+            "`stty {}` failed",
+            args.join(" ")
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+// (columns, rows) of the terminal on stdout, if it is one. Where the
+// ioctl fails, `stty size` is asked, then $COLUMNS and $LINES.
+pub fn size() -> Option<(usize, usize)> {
+    sys::size()
+        .or_else(|| {
+            let out = stty(&["size"]).ok()?;
+            let mut parts = out.split_whitespace().map(|p| p.parse().ok());
+            let rows = parts.next()??;
+            let columns = parts.next()??;
+            Some((columns, rows))
+        })
+        .or_else(|| from_env(env::var("COLUMNS").ok(), env::var("LINES").ok()))
+}
+
+fn from_env(columns: Option<String>, rows: Option<String>) -> Option<(usize, usize)> {
+    let columns = columns?.trim().parse().ok().filter(|&n| n > 0)?;
+    let rows = rows?.trim().parse().ok().filter(|&n| n > 0)?;
+    Some((columns, rows))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sys {
+    use std::os::raw::{c_int, c_ulong, c_ushort};
+
+    // Linux numbers ioctls per architecture: most use the old x86 value,
+    // while mips, powerpc and sparc use the BSD encoding, as macOS does.
+    #[cfg(all(
+        target_os = "linux",
+        not(any(
+            target_arch = "mips",
+            target_arch = "mips64",
+            target_arch = "powerpc",
+            target_arch = "powerpc64",
+            target_arch = "sparc",
+            target_arch = "sparc64"
+        ))
+    ))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(any(
+        target_os = "macos",
+        all(
+            target_os = "linux",
+            any(
+                target_arch = "mips",
+                target_arch = "mips64",
+                target_arch = "powerpc",
+                target_arch = "powerpc64",
+                target_arch = "sparc",
+                target_arch = "sparc64"
+            )
+        )
+    ))]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Winsize {
+        rows: c_ushort,
+        columns: c_ushort,
+        x_pixels: c_ushort,
+        y_pixels: c_ushort,
+    }
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    pub fn size() -> Option<(usize, usize)> {
+        let mut ws = Winsize::default();
+        // SAFETY: TIOCGWINSZ writes one `struct winsize`, which `ws` is.
+        let ok = unsafe { ioctl(1, TIOCGWINSZ, &mut ws as *mut Winsize) } == 0;
+        (ok && ws.columns > 0 && ws.rows > 0).then(|| (ws.columns.into(), ws.rows.into()))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod sys {
+    pub fn size() -> Option<(usize, usize)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_keys_and_escape_sequences() {
+        assert_eq!(
+            parse_keys(b"jk\x1b[A\x1b[B\r\t\x1b[5~\x1b[6~\x1bOH\x1b[F\x03q"),
+            [
+                Key::Char('j'),
+                Key::Char('k'),
+                Key::Up,
+                Key::Down,
+                Key::Enter,
+                Key::Tab,
+                Key::PageUp,
+                Key::PageDown,
+                Key::Home,
+                Key::End,
+                Key::Ctrl('c'),
+                Key::Char('q'),
+            ]
+        );
+        assert_eq!(parse_keys(b"\x1b"), [Key::Esc]);
+        // Unknown sequences (here F5) vanish instead of typing "15~".
+        assert_eq!(parse_keys(b"\x1b[15~x"), [Key::Char('x')]);
+        assert_eq!(parse_keys("é".as_bytes()), [Key::Char('é')]);
+    }
+
+    #[test]
+    fn reads_the_size_from_columns_and_lines() {
+        let size = |c: &str, r: &str| from_env(Some(c.to_string()), Some(r.to_string()));
+        assert_eq!(size("120", "40"), Some((120, 40)));
+        assert_eq!(size("0", "40"), None);
+        assert_eq!(size("wide", "40"), None);
+        assert_eq!(from_env(None, Some("40".to_string())), None);
+    }
+}
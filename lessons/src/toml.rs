@@ -0,0 +1,580 @@
+// A TOML subset, parsed in-house: tables, arrays of tables, strings
+// (basic, literal and multi-line), integers, floats, booleans, arrays and
+// inline tables. Dates and dotted keys are not supported.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub type Table = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&Table> {
+        match self {
+            Value::Table(t) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub fn parse(text: &str) -> Result<Table, Error> {
+    let mut p = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    let mut root = Table::new();
+    // Path of the table that `key = value` lines currently go into.
+    let mut current: Vec<String> = Vec::new();
+
+    loop {
+        p.skip_ws_and_comments(true);
+        let Some(c) = p.peek() else { break };
+
+        if c == '[' {
+            let array = p.peek_at(1) == Some('[');
+            p.pos += if array { 2 } else { 1 };
+            let path = p.header_path()?;
+            p.expect(']')?;
+            if array {
+                p.expect(']')?;
+            }
+            p.end_of_line()?;
+            open_table(&mut root, &path, array).map_err(|m| p.error(&m))?;
+            current = path;
+            continue;
+        }
+
+        let key = p.key()?;
+        p.skip_inline_ws();
+        p.expect('=')?;
+        p.skip_inline_ws();
+        let value = p.value()?;
+        p.end_of_line()?;
+
+        let table = table_at(&mut root, &current);
+        if table.contains_key(&key) {
+            return Err(p.error(&format!("duplicate key `{}`", key)));
+        }
+        table.insert(key, value);
+    }
+
+    Ok(root)
+}
+
+// Creates `[a.b]` (or appends a new element for `[[a.b]]`).
+fn open_table(root: &mut Table, path: &[String], array: bool) -> Result<(), String> {
+    let (last, parents) = path.split_last().ok_or("empty table name")?;
+    let parent = table_at(root, parents);
+    if array {
+        match parent
+            .entry(last.clone())
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            Value::Array(items) => items.push(Value::Table(Table::new())),
+            _ => return Err(format!("`{}` is already defined and is not an array", last)),
+        }
+    } else {
+        match parent.get(last) {
+            None => {
+                parent.insert(last.clone(), Value::Table(Table::new()));
+            }
+            Some(Value::Table(_)) => {}
+            Some(_) => return Err(format!("`{}` is already defined and is not a table", last)),
+        }
+    }
+    Ok(())
+}
+
+// The table at `path`, descending into the last element of arrays of
+// tables. Missing intermediate tables are created.
+fn table_at<'a>(root: &'a mut Table, path: &[String]) -> &'a mut Table {
+    let mut table = root;
+    for name in path {
+        let value = table
+            .entry(name.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        table = match value {
+            Value::Array(items) => match items.last_mut() {
+                Some(Value::Table(t)) => t,
+                _ => unreachable!("headers only create arrays of tables"),
+            },
+            Value::Table(t) => t,
+            _ => unreachable!("open_table rejects non-table paths"),
+        };
+    }
+    table
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> Error {
+        Error {
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.peek() == Some(c) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", c)))
+        }
+    }
+
+    fn skip_inline_ws(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_ws_and_comments(&mut self, newlines: bool) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\r') => {
+                    self.bump();
+                }
+                Some('\n') if newlines => {
+                    self.bump();
+                }
+                Some('#') => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), Error> {
+        self.skip_ws_and_comments(false);
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some(c) => Err(self.error(&format!("unexpected `{}` after value", c))),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, Error> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            _ => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                {
+                    self.bump();
+                }
+                if start == self.pos {
+                    return Err(self.error("expected a key"));
+                }
+                Ok(self.chars[start..self.pos].iter().collect())
+            }
+        }
+    }
+
+    fn header_path(&mut self) -> Result<Vec<String>, Error> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_inline_ws();
+            path.push(self.key()?);
+            self.skip_inline_ws();
+            if self.peek() == Some('.') {
+                self.bump();
+            } else {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        match self.peek() {
+            Some('"') if self.starts_with("\"\"\"") => {
+                self.multiline_string('"').map(Value::String)
+            }
+            Some('\'') if self.starts_with("'''") => self.multiline_string('\'').map(Value::String),
+            Some('"') => self.basic_string().map(Value::String),
+            Some('\'') => self.literal_string().map(Value::String),
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            Some('t') if self.starts_with("true") => {
+                self.pos += 4;
+                Ok(Value::Boolean(true))
+            }
+            Some('f') if self.starts_with("false") => {
+                self.pos += 5;
+                Ok(Value::Boolean(false))
+            }
+            Some(c) if c.is_ascii_digit() || c == '-' || c == '+' => self.number(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '+'))
+        {
+            self.bump();
+        }
+        let text: String = self.chars[start..self.pos]
+            .iter()
+            .filter(|&&c| c != '_')
+            .collect();
+        if let Ok(n) = text.parse::<i64>() {
+            return Ok(Value::Integer(n));
+        }
+        text.parse::<f64>()
+            .map(Value::Float)
+            .map_err(|_| self.error(&format!("invalid number `{}`", text)))
+    }
+
+    fn escape(&mut self) -> Result<char, Error> {
+        let c = self
+            .bump()
+            .ok_or_else(|| self.error("unterminated string"))?;
+        Ok(match c {
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '"' => '"',
+            '\\' => '\\',
+            'u' | 'U' => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let hex: String = (0..len).filter_map(|_| self.bump()).collect();
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            _ => return Err(self.error(&format!("invalid escape `\\{}`", c))),
+        })
+    }
+
+    fn basic_string(&mut self) -> Result<String, Error> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(out),
+                Some('\\') => out.push(self.escape()?),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, Error> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error("unterminated string")),
+                Some('\'') => return Ok(out),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn multiline_string(&mut self, quote: char) -> Result<String, Error> {
+        let delim: String = std::iter::repeat_n(quote, 3).collect();
+        self.pos += 3;
+        // A newline right after the opening delimiter is trimmed.
+        if self.peek() == Some('\r') {
+            self.bump();
+        }
+        if self.peek() == Some('\n') {
+            self.bump();
+        }
+        let mut out = String::new();
+        loop {
+            if self.starts_with(&delim) {
+                self.pos += 3;
+                return Ok(out);
+            }
+            match self.bump() {
+                None => return Err(self.error("unterminated multi-line string")),
+                Some('\\') if quote == '"' => {
+                    // Line-ending backslash: skip the newline and indentation.
+                    if matches!(self.peek(), Some('\n' | '\r' | ' ' | '\t')) {
+                        while matches!(self.peek(), Some('\n' | '\r' | ' ' | '\t')) {
+                            self.bump();
+                        }
+                    } else {
+                        out.push(self.escape()?);
+                    }
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws_and_comments(true);
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_ws_and_comments(true);
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {}
+                _ => return Err(self.error("expected `,` or `]` in array")),
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<Value, Error> {
+        self.bump();
+        let mut table = Table::new();
+        self.skip_inline_ws();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Ok(Value::Table(table));
+        }
+        loop {
+            self.skip_inline_ws();
+            let key = self.key()?;
+            self.skip_inline_ws();
+            self.expect('=')?;
+            self.skip_inline_ws();
+            let value = self.value()?;
+            table.insert(key, value);
+            self.skip_inline_ws();
+            match self.bump() {
+                Some(',') => {}
+                Some('}') => return Ok(Value::Table(table)),
+                _ => return Err(self.error("expected `,` or `}` in inline table")),
+            }
+        }
+    }
+}
+
+// Quotes a string for writing TOML back out. Multi-line text uses `"""`.
+// Control characters other than line breaks and tabs are escaped, as TOML
+// does not allow them raw.
+pub fn quote(s: &str) -> String {
+    let multiline = s.contains('\n');
+    let delim = if multiline { "\"\"\"" } else { "\"" };
+    let mut out = String::from(delim);
+    if multiline {
+        out.push('\n');
+    }
+    for c in s.chars() {
+        match c {
+            // Escaped in multi-line strings too, so a quote at the end
+            // cannot run into the closing `"""`.
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push('\n'),
+            '\t' if multiline => out.push('\t'),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push_str(delim);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(v: &Value) -> &str {
+        v.as_str().unwrap()
+    }
+
+    #[test]
+    fn parses_strings_and_escapes() {
+        let doc = parse(
+            r#"basic = "tab\there \"q\" \\ \u00e9\U0001F980"
+literal = 'C:\path\n'
+multi = """
+one
+two \
+    three"""
+raw = '''
+keep \n this'''
+"#,
+        )
+        .unwrap();
+        assert_eq!(string(&doc["basic"]), "tab\there \"q\" \\ é🦀");
+        assert_eq!(string(&doc["literal"]), "C:\\path\\n");
+        assert_eq!(string(&doc["multi"]), "one\ntwo three");
+        assert_eq!(string(&doc["raw"]), "keep \\n this");
+
+        let error = |text: &str| parse(text).unwrap_err().to_string();
+        assert_eq!(error("a = \"open\nb = 1"), "line 2: unterminated string");
+        assert_eq!(error("a = \"\\q\""), "line 1: invalid escape `\\q`");
+        assert_eq!(error("a = 1 2"), "line 1: unexpected `2` after value");
+    }
+
+    #[test]
+    fn parses_numbers_arrays_and_inline_tables() {
+        let doc = parse(
+            "n = 1_000\nf = -2.5\nyes = true\n\
+             list = [1, \"two\", [3], # comment\n]\n\
+             point = { x = 1, y = { z = 'deep' } }\n",
+        )
+        .unwrap();
+        assert_eq!(doc["n"], Value::Integer(1000));
+        assert_eq!(doc["f"], Value::Float(-2.5));
+        assert_eq!(doc["yes"].as_bool(), Some(true));
+        let list = doc["list"].as_array().unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[2], Value::Array(vec![Value::Integer(3)]));
+        let point = doc["point"].as_table().unwrap();
+        assert_eq!(point["x"].as_integer(), Some(1));
+        assert_eq!(string(&point["y"].as_table().unwrap()["z"]), "deep");
+    }
+
+    #[test]
+    fn parses_tables_and_arrays_of_tables() {
+        let doc = parse(
+            "title = 'top'\n\
+             [[lesson]]\nid = 'a'\n\
+             [lesson.extra]\nnote = 'for a'\n\
+             [[lesson]]\nid = 'b'\n\
+             [tool.sub]\nx = 1\n",
+        )
+        .unwrap();
+        let lessons = doc["lesson"].as_array().unwrap();
+        assert_eq!(lessons.len(), 2);
+        let first = lessons[0].as_table().unwrap();
+        assert_eq!(string(&first["id"]), "a");
+        assert_eq!(string(&first["extra"].as_table().unwrap()["note"]), "for a");
+        assert_eq!(string(&lessons[1].as_table().unwrap()["id"]), "b");
+        let tool = doc["tool"].as_table().unwrap();
+        assert_eq!(tool["sub"].as_table().unwrap()["x"].as_integer(), Some(1));
+    }
+
+    #[test]
+    fn rejects_redefinitions() {
+        let error = |text: &str| parse(text).unwrap_err().to_string();
+        assert_eq!(error("a = 1\nb = 2\na = 3\n"), "line 3: duplicate key `a`");
+        assert_eq!(
+            error("[t]\nx = 1\n[t]\nx = 2\n"),
+            "line 4: duplicate key `x`"
+        );
+        assert_eq!(
+            error("a = 1\n[[a]]\n"),
+            "line 2: `a` is already defined and is not an array"
+        );
+        assert_eq!(
+            error("a = 1\n[a]\n"),
+            "line 2: `a` is already defined and is not a table"
+        );
+    }
+
+    #[test]
+    fn quotes_what_parse_reads_back() {
+        for s in [
+            "plain",
+            "say \"hi\" \\ bye",
+            "tab\there",
+            "carriage\rreturn",
+            "bell\u{7}and\u{1b}escape\u{7f}",
+            "two\nlines ending in a quote\"",
+            "crlf\r\nin\tmulti-line \"\"\" text\n",
+        ] {
+            let doc = parse(&format!("s = {}\n", quote(s))).unwrap();
+            assert_eq!(string(&doc["s"]), s, "{:?}", quote(s));
+        }
+        assert_eq!(quote("a\rb\u{1}"), "\"a\\rb\\u0001\"");
+        assert_eq!(quote("a\n\"b\""), "\"\"\"\na\n\\\"b\\\"\"\"\"");
+    }
+}
@@ -0,0 +1,243 @@
+// Command-line options for the `notespush` binary, parsed by hand like the
+// lessons tool's. The help text lists the demos from the catalog, so a new
+// demo shows up in `--help` without touching this file.
+
+use std::path::PathBuf;
+
+use crate::demo::{Catalog, Demo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Help,
+    List,
+    Run,
+    // `notespush config show`
+    ShowConfig,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    // From `--only` and bare demo names, in the order given.
+    pub only: Vec<String>,
+    pub skip: Vec<String>,
+    pub format: Format,
+    // None when the seed should come from the clock.
+    pub seed: Option<u64>,
+    // `--config FILE`, instead of $NOTESPUSH_CONFIG or notespush.toml.
+    pub config: Option<PathBuf>,
+}
+
+impl Options {
+    // The demos to run: `only` (or the whole catalog) minus `skip`. Unknown
+    // names in either list are errors, with suggestions.
+    pub fn select<'a>(&self, catalog: &'a Catalog) -> Result<Vec<&'a dyn Demo>, String> {
+        if let Some(name) = self.skip.iter().find(|name| catalog.get(name).is_none()) {
+            return Err(catalog.unknown(name));
+        }
+        let mut demos = catalog.select(&self.only)?;
+        demos.retain(|demo| !self.skip.iter().any(|name| name == demo.id()));
+        Ok(demos)
+    }
+}
+
+pub fn usage(catalog: &Catalog) -> String {
+    let mut out = String::from(
+        "\
+usage: notespush [OPTIONS] [DEMO...]
+       notespush [--config FILE] [--format text|json] config show
+
+Runs every demo in catalog order, or the ones named in the order given.
+`config show` lists the settings for the banner and where each came from.
+
+options:
+  --list               list the demos and exit
+  --only A,B           run only these demos (same as naming them)
+  --skip A,B           leave these demos out
+  --format text|json   human-readable text (default) or JSON Lines
+  --seed N             seed for the random data (default: from the clock,
+                       and printed so the run can be repeated)
+  --config FILE        settings file (default: $NOTESPUSH_CONFIG, or
+                       notespush.toml next to Cargo.toml)
+  -h, --help           this help
+
+Progress is logged to stderr, with a tree of timings at the end. Set
+$NOTESPUSH_LOG to off, error, warn, info (default), debug or trace.
+
+demos:
+",
+    );
+    let width = catalog.demos().map(|d| d.id().len()).max().unwrap_or(0);
+    for demo in catalog.demos() {
+        out += &format!(
+            "  {:width$}   {}\n",
+            demo.id(),
+            demo.description(),
+            width = width
+        );
+    }
+    out
+}
+
+pub fn parse<S: AsRef<str>>(args: &[S]) -> Result<Options, String> {
+    let mut options = Options {
+        command: Command::Run,
+        only: Vec::new(),
+        skip: Vec::new(),
+        format: Format::Text,
+        seed: None,
+        config: None,
+    };
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        // `--only=a,b` as well as `--only a,b`.
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (arg, None),
+        };
+        // The next argument is the value unless it is another option, so
+        // `--only --list` is a mistake rather than a demo named `--list`.
+        let mut value = || {
+            inline
+                .or_else(|| args.next().filter(|next| !next.starts_with("--")))
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+        match flag {
+            "-h" | "--help" => options.command = Command::Help,
+            "--list" => options.command = Command::List,
+            "--only" => options.only.extend(names(value()?)),
+            "--skip" => options.skip.extend(names(value()?)),
+            "--format" => {
+                options.format = match value()? {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    other => {
+                        return Err(format!(
+                            "unknown format `{}` (expected text or json)",
+                            other
+                        ));
+                    }
+                }
+            }
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("--seed needs a whole number, not `{}`", seed))?,
+                );
+            }
+            "--config" => options.config = Some(PathBuf::from(value()?)),
+            "config" => match args.next() {
+                Some("show") => options.command = Command::ShowConfig,
+                Some(other) => return Err(format!("unknown config command `{}`", other)),
+                None => return Err("config needs a command: show".to_string()),
+            },
+            _ if flag.starts_with('-') => return Err(format!("unknown option {}", flag)),
+            _ => options.only.extend(names(arg)),
+        }
+    }
+    Ok(options)
+}
+
+fn names(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(options: &Options) -> Vec<&'static str> {
+        options
+            .select(&Catalog::builtin())
+            .unwrap()
+            .iter()
+            .map(|d| d.id())
+            .collect()
+    }
+
+    #[test]
+    fn parses_selection_format_and_seed() {
+        let options = parse(&[
+            "--only",
+            "closures,iterators",
+            "--format=json",
+            "--seed",
+            "42",
+        ])
+        .unwrap();
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(ids(&options), ["closures", "iterators"]);
+
+        let options = parse(&["--skip", "concurrency,statistics"]).unwrap();
+        let all = Catalog::builtin().len();
+        assert_eq!(ids(&options).len(), all - 2);
+        assert!(!ids(&options).contains(&"concurrency"));
+
+        assert_eq!(parse(&["macros", "--list"]).unwrap().command, Command::List);
+
+        let options = parse(&["--config", "me.toml", "config", "show"]).unwrap();
+        assert_eq!(options.command, Command::ShowConfig);
+        assert_eq!(options.config, Some(PathBuf::from("me.toml")));
+    }
+
+    #[test]
+    fn reports_bad_arguments() {
+        assert_eq!(parse(&["--only"]).unwrap_err(), "--only needs a value");
+        assert_eq!(
+            parse(&["--only", "--list"]).unwrap_err(),
+            "--only needs a value"
+        );
+        assert_eq!(
+            parse(&["--skip", "--format=json"]).unwrap_err(),
+            "--skip needs a value"
+        );
+        assert_eq!(
+            parse(&["--config", "--seed", "1"]).unwrap_err(),
+            "--config needs a value"
+        );
+        assert_eq!(
+            parse(&["--format", "yaml"]).unwrap_err(),
+            "unknown format `yaml` (expected text or json)"
+        );
+        assert_eq!(
+            parse(&["--seed", "-1"]).unwrap_err(),
+            "--seed needs a whole number, not `-1`"
+        );
+        assert_eq!(
+            parse(&["--verbose"]).unwrap_err(),
+            "unknown option --verbose"
+        );
+        assert_eq!(
+            parse(&["config", "edit"]).unwrap_err(),
+            "unknown config command `edit`"
+        );
+
+        let catalog = Catalog::builtin();
+        let options = parse(&["--skip", "concurency"]).unwrap();
+        assert_eq!(
+            options.select(&catalog).err().unwrap(),
+            "no demo named `concurency` (did you mean concurrency?)"
+        );
+    }
+
+    #[test]
+    fn help_lists_every_demo() {
+        let catalog = Catalog::builtin();
+        let help = usage(&catalog);
+        for demo in catalog.demos() {
+            assert!(help.contains(&format!("  {} ", demo.id())), "{}", demo.id());
+        }
+    }
+}
@@ -0,0 +1,501 @@
+// A compact binary encoding for demo data, for datasets where JSON is too
+// big and too slow.
+//
+// Unsigned integers are LEB128 varints, signed ones zigzag-encoded first;
+// floats are 8 bytes little-endian; strings and vectors are a varint
+// length then their contents; enums a varint tag then their fields. A
+// payload starts with `MAGIC` and the format version as a varint.
+//
+// Payloads written by older versions must keep decoding: a format change
+// bumps `VERSION`, and `Decode` impls branch on `Reader::version`. The
+// fixtures under `fixtures/v1/` hold us to that.
+
+use std::fmt;
+
+pub const MAGIC: &[u8; 4] = b"NPSH";
+pub const VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    // Where in the payload decoding stopped.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Default)]
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Writer {
+        Writer::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    // Seven bits at a time, low bits first; the high bit means "more".
+    pub fn varint(&mut self, mut n: u64) {
+        while n >= 0x80 {
+            self.bytes.push(n as u8 | 0x80);
+            n >>= 7;
+        }
+        self.bytes.push(n as u8);
+    }
+
+    // Zigzag, so small negative numbers stay small: 0, -1, 1, -2 become
+    // 0, 1, 2, 3.
+    pub fn signed(&mut self, n: i64) {
+        self.varint(((n << 1) ^ (n >> 63)) as u64);
+    }
+
+    pub fn f64(&mut self, x: f64) {
+        self.bytes.extend_from_slice(&x.to_le_bytes());
+    }
+
+    pub fn bool(&mut self, b: bool) {
+        self.bytes.push(u8::from(b));
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.varint(bytes.len() as u64);
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn str(&mut self, s: &str) {
+        self.bytes(s.as_bytes());
+    }
+}
+
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    version: u64,
+}
+
+impl<'a> Reader<'a> {
+    // `bytes` without the header, written by format `version`.
+    pub fn new(bytes: &'a [u8], version: u64) -> Reader<'a> {
+        Reader {
+            bytes,
+            pos: 0,
+            version,
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> Error {
+        Error {
+            offset: self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if n > self.remaining() {
+            return Err(self.error(format!("needs {} more bytes, {} left", n, self.remaining())));
+        }
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    pub fn varint(&mut self) -> Result<u64, Error> {
+        let start = self.pos;
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            let bits = u64::from(byte & 0x7f);
+            if shift == 63 && bits > 1 {
+                break;
+            }
+            n |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(Error {
+            offset: start,
+            message: "varint does not fit in 64 bits".to_string(),
+        })
+    }
+
+    pub fn signed(&mut self) -> Result<i64, Error> {
+        let n = self.varint()?;
+        Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+    }
+
+    pub fn f64(&mut self) -> Result<f64, Error> {
+        let bytes = self.take(8)?;
+        Ok(f64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        match self.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(self.error(format!("invalid bool {}", b))),
+        }
+    }
+
+    // A length prefix, checked against what is left so a corrupt length
+    // cannot make us allocate gigabytes: every item takes at least a byte.
+    pub fn length(&mut self) -> Result<usize, Error> {
+        let n = self.varint()?;
+        if n > self.remaining() as u64 {
+            return Err(self.error(format!(
+                "length {} is more than the {} bytes left",
+                n,
+                self.remaining()
+            )));
+        }
+        Ok(n as usize)
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], Error> {
+        let n = self.length()?;
+        self.take(n)
+    }
+
+    pub fn str(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error {
+            offset: start,
+            message: "string is not UTF-8".to_string(),
+        })
+    }
+
+    // An enum's variant tag, `names` listing the variants for the error.
+    pub fn tag(&mut self, what: &str, names: &[&str]) -> Result<usize, Error> {
+        let start = self.pos;
+        let tag = self.varint()?;
+        if tag < names.len() as u64 {
+            Ok(tag as usize)
+        } else {
+            Err(Error {
+                offset: start,
+                message: format!(
+                    "unknown {} tag {} (expected 0 to {}: {})",
+                    what,
+                    tag,
+                    names.len() - 1,
+                    names.join(", ")
+                ),
+            })
+        }
+    }
+}
+
+pub trait Encode {
+    fn encode(&self, w: &mut Writer);
+}
+
+pub trait Decode: Sized {
+    fn decode(r: &mut Reader) -> Result<Self, Error>;
+}
+
+// `MAGIC`, `VERSION`, then `value`.
+pub fn to_bytes<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.bytes.extend_from_slice(MAGIC);
+    w.varint(VERSION);
+    value.encode(&mut w);
+    w.into_bytes()
+}
+
+pub fn from_bytes<T: Decode>(bytes: &[u8]) -> Result<T, Error> {
+    if !bytes.starts_with(MAGIC) {
+        return Err(Error {
+            offset: 0,
+            message: "not a notespush payload (bad magic)".to_string(),
+        });
+    }
+    let mut header = Reader::new(&bytes[MAGIC.len()..], 0);
+    let version = header.varint().map_err(|e| Error {
+        offset: e.offset + MAGIC.len(),
+        ..e
+    })?;
+    if version == 0 || version > VERSION {
+        return Err(Error {
+            offset: MAGIC.len(),
+            message: format!(
+                "format version {} is not supported (this build reads 1 to {})",
+                version, VERSION
+            ),
+        });
+    }
+    let start = MAGIC.len() + header.pos;
+    let mut r = Reader::new(&bytes[start..], version);
+    let at = |e: Error| Error {
+        offset: e.offset + start,
+        ..e
+    };
+    let value = T::decode(&mut r).map_err(at)?;
+    if r.remaining() > 0 {
+        return Err(at(r.error("trailing bytes")));
+    }
+    Ok(value)
+}
+
+impl Encode for bool {
+    fn encode(&self, w: &mut Writer) {
+        w.bool(*self);
+    }
+}
+
+impl Decode for bool {
+    fn decode(r: &mut Reader) -> Result<bool, Error> {
+        r.bool()
+    }
+}
+
+macro_rules! unsigned {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, w: &mut Writer) {
+                w.varint(*self as u64);
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(r: &mut Reader) -> Result<$t, Error> {
+                let start = r.pos;
+                let n = r.varint()?;
+                <$t>::try_from(n).map_err(|_| Error {
+                    offset: start,
+                    message: format!("{} does not fit in {}", n, stringify!($t)),
+                })
+            }
+        }
+    )*};
+}
+
+unsigned!(u16, u32, u64, usize);
+
+macro_rules! signed {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, w: &mut Writer) {
+                w.signed(*self as i64);
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(r: &mut Reader) -> Result<$t, Error> {
+                let start = r.pos;
+                let n = r.signed()?;
+                <$t>::try_from(n).map_err(|_| Error {
+                    offset: start,
+                    message: format!("{} does not fit in {}", n, stringify!($t)),
+                })
+            }
+        }
+    )*};
+}
+
+signed!(i16, i32, i64, isize);
+
+impl Encode for f64 {
+    fn encode(&self, w: &mut Writer) {
+        w.f64(*self);
+    }
+}
+
+impl Decode for f64 {
+    fn decode(r: &mut Reader) -> Result<f64, Error> {
+        r.f64()
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, w: &mut Writer) {
+        w.str(self);
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, w: &mut Writer) {
+        w.str(self);
+    }
+}
+
+impl Decode for String {
+    fn decode(r: &mut Reader) -> Result<String, Error> {
+        r.str()
+    }
+}
+
+// A 0 or 1 tag, then the value.
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, w: &mut Writer) {
+        match self {
+            None => w.varint(0),
+            Some(value) => {
+                w.varint(1);
+                value.encode(w);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(r: &mut Reader) -> Result<Option<T>, Error> {
+        match r.tag("Option", &["None", "Some"])? {
+            0 => Ok(None),
+            _ => T::decode(r).map(Some),
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, w: &mut Writer) {
+        w.varint(self.len() as u64);
+        for item in self {
+            item.encode(w);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, w: &mut Writer) {
+        self.as_slice().encode(w);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(r: &mut Reader) -> Result<Vec<T>, Error> {
+        let n = r.length()?;
+        (0..n).map(|_| T::decode(r)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::person::Person;
+    use crate::shape::Shape;
+
+    #[test]
+    fn writes_varints_and_zigzag() {
+        let mut w = Writer::new();
+        for n in [0, 1, 127, 128, 300, u64::MAX] {
+            w.varint(n);
+        }
+        for n in [0, -1, 1, -64, i64::MIN] {
+            w.signed(n);
+        }
+        let bytes = w.into_bytes();
+        assert_eq!(&bytes[..7], [0x00, 0x01, 0x7f, 0x80, 0x01, 0xac, 0x02]);
+
+        let mut r = Reader::new(&bytes, VERSION);
+        let unsigned: Vec<u64> = (0..6).map(|_| r.varint().unwrap()).collect();
+        assert_eq!(unsigned, [0, 1, 127, 128, 300, u64::MAX]);
+        let signed: Vec<i64> = (0..5).map(|_| r.signed().unwrap()).collect();
+        assert_eq!(signed, [0, -1, 1, -64, i64::MIN]);
+        assert_eq!(r.remaining(), 0);
+    }
+
+    #[test]
+    fn round_trips_values() {
+        let value = (
+            vec![Some("crab".to_string()), None],
+            vec![-3i32, 70_000],
+            3.5f64,
+        );
+        let mut w = Writer::new();
+        value.0.encode(&mut w);
+        value.1.encode(&mut w);
+        value.2.encode(&mut w);
+        let bytes = w.into_bytes();
+        let mut r = Reader::new(&bytes, VERSION);
+        assert_eq!(Vec::<Option<String>>::decode(&mut r).unwrap(), value.0);
+        assert_eq!(Vec::<i32>::decode(&mut r).unwrap(), value.1);
+        assert_eq!(f64::decode(&mut r).unwrap(), value.2);
+    }
+
+    #[test]
+    fn rejects_bad_payloads() {
+        let good = to_bytes(&vec![Shape::Circle(1.0)]);
+        let err = |bytes: &[u8]| from_bytes::<Vec<Shape>>(bytes).unwrap_err().to_string();
+        assert_eq!(
+            err(b"JSON{}"),
+            "byte 0: not a notespush payload (bad magic)"
+        );
+        assert_eq!(
+            err(b"NPSH\x07"),
+            "byte 4: format version 7 is not supported (this build reads 1 to 1)"
+        );
+        assert_eq!(
+            err(&good[..good.len() - 1]),
+            "byte 7: needs 8 more bytes, 7 left"
+        );
+        assert_eq!(
+            err(&[MAGIC.as_slice(), &[1, 1, 9]].concat()),
+            "byte 6: unknown Shape tag 9 (expected 0 to 2: Circle, Rectangle, Triangle)"
+        );
+        assert_eq!(
+            err(&[MAGIC.as_slice(), &[1, 0xff, 0xff, 0x7f]].concat()),
+            "byte 8: length 2097151 is more than the 0 bytes left"
+        );
+        assert_eq!(
+            err(&[good.as_slice(), &[0]].concat()),
+            "byte 15: trailing bytes"
+        );
+        assert_eq!(
+            from_bytes::<u16>(&[MAGIC.as_slice(), &[1, 0x80, 0x80, 0x04]].concat())
+                .unwrap_err()
+                .to_string(),
+            "byte 5: 65536 does not fit in u16"
+        );
+    }
+
+    fn people() -> Vec<Person> {
+        let mut alice = Person::new("Alice", 25);
+        alice.add_skill("Rust");
+        alice.add_skill("Python");
+        let mut bob = Person::new("Bob", 17);
+        bob.add_skill("JavaScript");
+        vec![alice, bob, Person::new("Zoë", 0)]
+    }
+
+    fn shapes() -> Vec<Shape> {
+        vec![
+            Shape::Circle(5.0),
+            Shape::Rectangle(4.0, 6.5),
+            Shape::Triangle(3.0, 4.0, 5.0),
+        ]
+    }
+
+    // Payloads written by version 1. Never regenerate these: if they stop
+    // decoding, the format changed without a version bump.
+    #[test]
+    fn decodes_version_1_fixtures() {
+        let people_v1 = include_bytes!("../fixtures/v1/people.bin");
+        let shapes_v1 = include_bytes!("../fixtures/v1/shapes.bin");
+        assert_eq!(from_bytes::<Vec<Person>>(people_v1), Ok(people()));
+        assert_eq!(from_bytes::<Vec<Shape>>(shapes_v1), Ok(shapes()));
+        // While the format is at version 1, writing gives the same bytes.
+        if VERSION == 1 {
+            assert_eq!(to_bytes(&people()), people_v1);
+            assert_eq!(to_bytes(&shapes()), shapes_v1);
+        }
+    }
+}
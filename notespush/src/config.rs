@@ -0,0 +1,500 @@
+// Settings for the banner and the learner's profile, from `notespush.toml`
+// (a TOML subset, read with `lessons::toml`) with `NOTESPUSH_*` environment
+// variables on top. Every value remembers where it came from, so
+// `notespush config show` can explain it.
+//
+// `banner.date` is today's date from the system clock unless it is set:
+// the local date where `date` (PowerShell on Windows) can tell it, else the
+// UTC date, which the banner then says it is.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lessons::date::Date;
+use lessons::spell;
+use lessons::toml::{self, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Default,
+    Clock,
+    UtcClock,
+    File(PathBuf),
+    Env(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => f.write_str("default"),
+            Source::Clock => f.write_str("system clock"),
+            Source::UtcClock => f.write_str("system clock (UTC)"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env(var) => write!(f, "${}", var),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    String,
+    Integer,
+    Boolean,
+    // Of strings; `|` separates them in an environment variable.
+    Array,
+    Date,
+}
+
+// Every setting, in the order `config show` lists them.
+const KEYS: &[(&str, Kind)] = &[
+    ("profile.name", Kind::String),
+    ("profile.email", Kind::String),
+    ("banner.show", Kind::Boolean),
+    ("banner.title", Kind::String),
+    ("banner.rule", Kind::Integer),
+    ("banner.lines", Kind::Array),
+    ("banner.date", Kind::Date),
+    ("banner.after_date", Kind::Array),
+];
+
+// The widest `banner.rule`, in `=` signs; 0 leaves the rule out.
+const MAX_RULE: i64 = 200;
+
+fn default(key: &str) -> Value {
+    match key {
+        "banner.show" => Value::Boolean(true),
+        "banner.title" => Value::String("🦀 Advanced Rust Programming Examples 🦀".to_string()),
+        "banner.rule" => Value::Integer(42),
+        "banner.lines" | "banner.after_date" => Value::Array(Vec::new()),
+        _ => Value::String(String::new()),
+    }
+}
+
+// `banner.title` is read from `NOTESPUSH_BANNER_TITLE`.
+pub fn env_var(key: &str) -> String {
+    format!("NOTESPUSH_{}", key.replace('.', "_").to_uppercase())
+}
+
+// Next to Cargo.toml, like the lessons tool's files.
+pub fn default_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("notespush.toml")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting {
+    pub key: &'static str,
+    pub value: Value,
+    pub source: Source,
+}
+
+// Today's date, and whether it is the local one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Today {
+    Local(Date),
+    Utc(Date),
+}
+
+impl Today {
+    pub fn now() -> Today {
+        Date::local_today().map_or_else(|| Today::Utc(Date::today()), Today::Local)
+    }
+
+    fn date(self) -> Date {
+        match self {
+            Today::Local(date) | Today::Utc(date) => date,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    settings: Vec<Setting>,
+    date: Date,
+}
+
+impl Config {
+    // `path` is the file to read; a missing file is only an error when
+    // `required` (it was asked for with `--config` or $NOTESPUSH_CONFIG).
+    pub fn load(
+        path: &Path,
+        required: bool,
+        env: impl Fn(&str) -> Option<String>,
+        today: Today,
+    ) -> Result<Config, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => Some(text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => None,
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        Config::parse(text.as_deref(), path, env, today)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(
+        text: Option<&str>,
+        path: &Path,
+        env: impl Fn(&str) -> Option<String>,
+        today: Today,
+    ) -> Result<Config, String> {
+        let doc = match text {
+            Some(text) => toml::parse(text).map_err(|e| e.to_string())?,
+            None => toml::Table::new(),
+        };
+        let file = flatten(&doc)?;
+
+        let mut settings = Vec::new();
+        for &(key, kind) in KEYS {
+            let setting = if let Some(text) = env(&env_var(key)) {
+                Setting {
+                    key,
+                    value: from_env(key, kind, &text)?,
+                    source: Source::Env(env_var(key)),
+                }
+            } else if let Some(value) = file.iter().find(|(k, _)| k == key).map(|(_, v)| v) {
+                check(key, kind, value)?;
+                Setting {
+                    key,
+                    value: value.clone(),
+                    source: Source::File(path.to_path_buf()),
+                }
+            } else if kind == Kind::Date {
+                Setting {
+                    key,
+                    value: Value::String(today.date().to_string()),
+                    source: match today {
+                        Today::Local(_) => Source::Clock,
+                        Today::Utc(_) => Source::UtcClock,
+                    },
+                }
+            } else {
+                Setting {
+                    key,
+                    value: default(key),
+                    source: Source::Default,
+                }
+            };
+            settings.push(setting);
+        }
+        let date = settings
+            .iter()
+            .find(|s| s.key == "banner.date")
+            .and_then(|s| s.value.as_str())
+            .and_then(|s| Date::parse(s).ok())
+            .unwrap_or(today.date());
+        Ok(Config { settings, date })
+    }
+
+    pub fn settings(&self) -> &[Setting] {
+        &self.settings
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Setting> {
+        self.settings.iter().find(|s| s.key == key)
+    }
+
+    fn string(&self, key: &str) -> &str {
+        self.get(key).and_then(|s| s.value.as_str()).unwrap_or("")
+    }
+
+    fn strings(&self, key: &str) -> Vec<String> {
+        let items = self.get(key).and_then(|s| s.value.as_array());
+        items
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn name(&self) -> &str {
+        self.string("profile.name")
+    }
+
+    pub fn email(&self) -> &str {
+        self.string("profile.email")
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    // The lines printed before the demos, empty when `banner.show` is off:
+    // the title and rule, `banner.lines`, the date, `banner.after_date`,
+    // then the author and contact, as the old hard-coded banner had them.
+    pub fn banner(&self) -> Vec<String> {
+        let show = self
+            .get("banner.show")
+            .and_then(|s| s.value.as_bool())
+            .unwrap_or(true);
+        if !show {
+            return Vec::new();
+        }
+        let rule = self
+            .get("banner.rule")
+            .and_then(|s| s.value.as_integer())
+            .unwrap_or(0);
+        let mut lines = vec![self.string("banner.title").to_string()];
+        if rule > 0 {
+            lines.push("=".repeat(rule as usize));
+        }
+        lines.extend(self.strings("banner.lines"));
+        let utc = self
+            .get("banner.date")
+            .is_some_and(|s| s.source == Source::UtcClock);
+        lines.push(format!(
+            "📅 Today's date: {}{}",
+            long_date(self.date),
+            if utc { " (UTC)" } else { "" }
+        ));
+        lines.extend(self.strings("banner.after_date"));
+        if !self.name().is_empty() {
+            lines.push(format!("👤 Author: {}", self.name()));
+        }
+        if !self.email().is_empty() {
+            lines.push(format!("📧 Contact: {}", self.email()));
+        }
+        lines
+    }
+}
+
+// `[profile] name = "…"` as `("profile.name", …)`. Unknown keys are errors
+// so a typo does not silently fall back to the default.
+fn flatten(doc: &toml::Table) -> Result<Vec<(String, Value)>, String> {
+    let mut out = Vec::new();
+    for (section, value) in doc {
+        let Some(table) = value.as_table() else {
+            return Err(unknown(section));
+        };
+        for (name, value) in table {
+            let key = format!("{}.{}", section, name);
+            if !KEYS.iter().any(|(k, _)| *k == key) {
+                return Err(unknown(&key));
+            }
+            out.push((key, value.clone()));
+        }
+    }
+    Ok(out)
+}
+
+fn unknown(key: &str) -> String {
+    let close: Vec<&str> = KEYS
+        .iter()
+        .map(|(k, _)| *k)
+        .filter(|k| spell::distance(key, k) <= 2)
+        .collect();
+    if close.is_empty() {
+        format!("unknown setting `{}`", key)
+    } else {
+        format!(
+            "unknown setting `{}` (did you mean {}?)",
+            key,
+            close.join(", ")
+        )
+    }
+}
+
+fn check(key: &str, kind: Kind, value: &Value) -> Result<(), String> {
+    let ok = match kind {
+        Kind::String => value.as_str().is_some(),
+        Kind::Integer => value.as_integer().is_some(),
+        Kind::Boolean => value.as_bool().is_some(),
+        Kind::Array => value
+            .as_array()
+            .is_some_and(|items| items.iter().all(|v| v.as_str().is_some())),
+        Kind::Date => value.as_str().is_some_and(|s| Date::parse(s).is_ok()),
+    };
+    if !ok {
+        return Err(format!("{} must be {}", key, describe(kind)));
+    }
+    if key == "banner.rule"
+        && !value
+            .as_integer()
+            .is_some_and(|n| (0..=MAX_RULE).contains(&n))
+    {
+        return Err(format!("banner.rule must be from 0 to {}", MAX_RULE));
+    }
+    Ok(())
+}
+
+fn from_env(key: &str, kind: Kind, text: &str) -> Result<Value, String> {
+    let value = match kind {
+        Kind::String | Kind::Date => Some(Value::String(text.to_string())),
+        Kind::Integer => text.trim().parse().ok().map(Value::Integer),
+        Kind::Boolean => match text.trim() {
+            "true" | "1" | "yes" => Some(Value::Boolean(true)),
+            "false" | "0" | "no" => Some(Value::Boolean(false)),
+            _ => None,
+        },
+        Kind::Array => Some(Value::Array(
+            text.split('|')
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+    };
+    match value {
+        Some(value) => check(key, kind, &value).map(|()| value),
+        None => Err(format!("{} must be {}", key, describe(kind))),
+    }
+    .map_err(|e| format!("{} (from ${})", e, env_var(key)))
+}
+
+fn describe(kind: Kind) -> &'static str {
+    match kind {
+        Kind::String => "a string",
+        Kind::Integer => "a whole number",
+        Kind::Boolean => "true or false",
+        Kind::Array => "a list of strings",
+        Kind::Date => "a date like 2025-09-02",
+    }
+}
+
+// `September 2, 2025`.
+pub fn long_date(date: Date) -> String {
+    const MONTHS: [&str; 12] = [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ];
+    format!(
+        "{} {}, {}",
+        MONTHS[date.month as usize - 1],
+        date.day,
+        date.year
+    )
+}
+
+// How a setting is shown by `config show`: strings quoted, like the file.
+pub fn show(value: &Value) -> String {
+    match value {
+        Value::String(s) => toml::quote(s),
+        Value::Integer(n) => n.to_string(),
+        Value::Float(x) => x.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(show).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Table(_) => "{…}".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+[profile]
+name = "Ferris"
+email = "ferris@example.com"
+
+[banner]
+rule = 3
+lines = ["✅ ready"]
+after_date = ["🎯 learning"]
+"#;
+
+    fn today() -> Today {
+        Today::Local(Date::parse("2026-10-19").unwrap())
+    }
+
+    fn parse(text: &str, env: &[(&str, &str)]) -> Result<Config, String> {
+        let env: Vec<(String, String)> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::parse(
+            Some(text),
+            Path::new("notespush.toml"),
+            |var| env.iter().find(|(k, _)| k == var).map(|(_, v)| v.clone()),
+            today(),
+        )
+    }
+
+    #[test]
+    fn layers_defaults_file_env_and_clock() {
+        let config = parse(FILE, &[("NOTESPUSH_PROFILE_NAME", "Corro")]).unwrap();
+        assert_eq!(
+            config.banner(),
+            [
+                "🦀 Advanced Rust Programming Examples 🦀",
+                "===",
+                "✅ ready",
+                "📅 Today's date: October 19, 2026",
+                "🎯 learning",
+                "👤 Author: Corro",
+                "📧 Contact: ferris@example.com",
+            ]
+        );
+        let source = |key| config.get(key).unwrap().source.to_string();
+        assert_eq!(source("profile.name"), "$NOTESPUSH_PROFILE_NAME");
+        assert_eq!(source("profile.email"), "notespush.toml");
+        assert_eq!(source("banner.title"), "default");
+        assert_eq!(source("banner.date"), "system clock");
+
+        let config = parse(
+            FILE,
+            &[
+                ("NOTESPUSH_BANNER_DATE", "2025-09-02"),
+                ("NOTESPUSH_BANNER_SHOW", "no"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(long_date(config.date()), "September 2, 2025");
+        assert!(config.banner().is_empty());
+    }
+
+    #[test]
+    fn says_when_the_date_is_utc() {
+        let utc = Today::Utc(Date::parse("2026-10-19").unwrap());
+        let config = Config::parse(Some(FILE), Path::new("notespush.toml"), |_| None, utc).unwrap();
+        assert_eq!(
+            config.banner()[3],
+            "📅 Today's date: October 19, 2026 (UTC)"
+        );
+        let date = config.get("banner.date").unwrap();
+        assert_eq!(date.source.to_string(), "system clock (UTC)");
+
+        let config = parse(FILE, &[("NOTESPUSH_BANNER_DATE", "2025-09-02")]).unwrap();
+        assert_eq!(config.banner()[3], "📅 Today's date: September 2, 2025");
+    }
+
+    #[test]
+    fn rejects_bad_settings() {
+        assert_eq!(
+            parse("[banner]\ntitel = \"x\"", &[]).unwrap_err(),
+            "unknown setting `banner.titel` (did you mean banner.title?)"
+        );
+        assert_eq!(
+            parse("[banner]\nrule = \"long\"", &[]).unwrap_err(),
+            "banner.rule must be a whole number"
+        );
+        assert_eq!(
+            parse("[banner]\nrule = 201", &[]).unwrap_err(),
+            "banner.rule must be from 0 to 200"
+        );
+        assert_eq!(
+            parse("", &[("NOTESPUSH_BANNER_RULE", "-1")]).unwrap_err(),
+            "banner.rule must be from 0 to 200 (from $NOTESPUSH_BANNER_RULE)"
+        );
+        assert!(parse("[banner]\nrule = 200", &[]).is_ok());
+        assert_eq!(
+            parse("[banner]\ndate = \"2025-02-30\"", &[]).unwrap_err(),
+            "banner.date must be a date like 2025-09-02"
+        );
+        assert_eq!(
+            parse("", &[("NOTESPUSH_BANNER_SHOW", "maybe")]).unwrap_err(),
+            "banner.show must be true or false (from $NOTESPUSH_BANNER_SHOW)"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// The `Demo` trait and the catalog of demos the binary picks from.
+//
+// A new demo is a type implementing `Demo` plus one line in
+// `demos::all()`; nothing in `main.rs` needs to change.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use lessons::spell;
+
+use crate::log;
+use crate::output::Output;
+use crate::rng::Rng;
+
+pub trait Demo {
+    // Short, lowercase and unique: what `notespush <id>` is typed with.
+    fn id(&self) -> &'static str;
+    fn title(&self) -> &'static str;
+    // One line for `--list`.
+    fn description(&self) -> &'static str;
+    // Writes results to `out` rather than stdout; the section heading is
+    // written by `run` below. Random data comes from `rng` so `--seed`
+    // reproduces it.
+    fn run(&self, out: &mut dyn Output, rng: &mut Rng);
+}
+
+// Runs each demo under a section of its own, all drawing from one seed.
+// Each is timed in a span named after it, inside one called `run`. A demo
+// that panics gets an error in its section and the rest still run; the
+// ids of the ones that did are returned.
+pub fn run(demos: &[&dyn Demo], out: &mut dyn Output, seed: u64) -> Vec<&'static str> {
+    let _run = log::span("run");
+    let mut rng = Rng::new(seed);
+    let mut panicked = Vec::new();
+    for demo in demos {
+        let _demo = log::span(demo.id());
+        crate::debug!("running {}", demo.id());
+        out.section(demo.id(), demo.title());
+        // Neither `out` nor `rng` is left broken by a panic: at worst the
+        // section is cut short and later demos get different numbers.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| demo.run(out, &mut rng)));
+        if let Err(payload) = result {
+            let message = panic_message(payload.as_ref());
+            crate::error!("{} panicked: {}", demo.id(), message);
+            out.error(&format!("the demo panicked: {}", message));
+            panicked.push(demo.id());
+        }
+    }
+    panicked
+}
+
+// What `panic!` was given, when it was a string (as it almost always is).
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "(not a string)"
+    }
+}
+
+// Demos in the order they run when none are named.
+#[derive(Default)]
+pub struct Catalog {
+    demos: Vec<Box<dyn Demo>>,
+}
+
+impl Catalog {
+    pub fn builtin() -> Catalog {
+        let mut catalog = Catalog::default();
+        for demo in crate::demos::all() {
+            catalog.register(demo);
+        }
+        catalog
+    }
+
+    // Panics if the id is taken: two demos with one name is a bug in the
+    // catalog, not something a user can fix.
+    pub fn register(&mut self, demo: Box<dyn Demo>) {
+        assert!(
+            self.get(demo.id()).is_none(),
+            "demo `{}` registered twice",
+            demo.id()
+        );
+        self.demos.push(demo);
+    }
+
+    pub fn demos(&self) -> impl Iterator<Item = &dyn Demo> {
+        self.demos.iter().map(|d| d.as_ref())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Demo> {
+        self.demos().find(|d| d.id() == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.demos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.demos.is_empty()
+    }
+
+    // Ids close enough to `name` to be what was meant, closest first.
+    pub fn suggest(&self, name: &str) -> Vec<&'static str> {
+        let mut close: Vec<(usize, &'static str)> = self
+            .demos()
+            .map(|d| (spell::distance(name, d.id()), d.id()))
+            .filter(|&(distance, id)| distance <= 2.max(id.len() / 3) || id.starts_with(name))
+            .collect();
+        close.sort();
+        close.into_iter().take(3).map(|(_, id)| id).collect()
+    }
+
+    // `no demo named ...`, with suggestions when there are some.
+    pub fn unknown(&self, name: &str) -> String {
+        let close = self.suggest(name);
+        if close.is_empty() {
+            format!("no demo named `{}`", name)
+        } else {
+            format!(
+                "no demo named `{}` (did you mean {}?)",
+                name,
+                close.join(", ")
+            )
+        }
+    }
+
+    // The demos named, in the order given; every demo when `ids` is empty.
+    pub fn select<S: AsRef<str>>(&self, ids: &[S]) -> Result<Vec<&dyn Demo>, String> {
+        if ids.is_empty() {
+            return Ok(self.demos().collect());
+        }
+        ids.iter()
+            .map(|id| {
+                let id = id.as_ref();
+                self.get(id).ok_or_else(|| self.unknown(id))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Hello;
+
+    impl Demo for Hello {
+        fn id(&self) -> &'static str {
+            "hello"
+        }
+        fn title(&self) -> &'static str {
+            "Hello"
+        }
+        fn description(&self) -> &'static str {
+            "Says hello"
+        }
+        fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+            out.line("hello");
+        }
+    }
+
+    #[test]
+    fn builtin_ids_are_unique_and_lowercase() {
+        let catalog = Catalog::builtin();
+        assert!(!catalog.is_empty());
+        for demo in catalog.demos() {
+            assert_eq!(demo.id(), demo.id().to_lowercase());
+            assert!(!demo.description().is_empty());
+        }
+    }
+
+    #[test]
+    fn selects_by_id_in_the_order_given() {
+        let mut catalog = Catalog::builtin();
+        catalog.register(Box::new(Hello));
+        let ids: Vec<&str> = catalog
+            .select(&["hello", "iterators"])
+            .unwrap()
+            .iter()
+            .map(|d| d.id())
+            .collect();
+        assert_eq!(ids, ["hello", "iterators"]);
+        assert_eq!(catalog.select::<&str>(&[]).unwrap().len(), catalog.len());
+        assert_eq!(
+            catalog.select(&["iterators", "nope"]).err().unwrap(),
+            "no demo named `nope`"
+        );
+        assert_eq!(
+            catalog.select(&["clousres"]).err().unwrap(),
+            "no demo named `clousres` (did you mean closures?)"
+        );
+        assert_eq!(catalog.suggest("con"), ["concurrency"]);
+    }
+
+    struct Broken;
+
+    impl Demo for Broken {
+        fn id(&self) -> &'static str {
+            "broken"
+        }
+        fn title(&self) -> &'static str {
+            "Broken"
+        }
+        fn description(&self) -> &'static str {
+            "Panics halfway"
+        }
+        fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+            out.line("before");
+            let v: Vec<i32> = Vec::new();
+            out.value("first", &v[0]);
+        }
+    }
+
+    #[test]
+    fn a_panicking_demo_does_not_stop_the_rest() {
+        let mut out = crate::output::Capture::new();
+        let panicked = run(&[&Broken, &Hello, &Broken], &mut out, 1);
+        assert_eq!(panicked, ["broken", "broken"]);
+        assert_eq!(out.sections(), ["broken", "hello", "broken"]);
+        assert_eq!(
+            out.errors()[0],
+            "the demo panicked: index out of bounds: the len is 0 but the index is 0"
+        );
+        assert!(out.text().contains("\nhello\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "registered twice")]
+    fn rejects_a_second_demo_with_the_same_id() {
+        let mut catalog = Catalog::default();
+        catalog.register(Box::new(Hello));
+        catalog.register(Box::new(Hello));
+    }
+}
@@ -0,0 +1,126 @@
+// Every built-in demo, in the order `notespush` runs them.
+
+mod closures;
+mod collections;
+mod concurrency;
+mod encoding;
+mod errors;
+mod iterators;
+mod lifetimes;
+mod macros;
+mod patterns;
+mod statistics;
+
+pub use errors::safe_divide;
+pub use lifetimes::longest;
+pub use macros::{add, multiply, subtract};
+pub use statistics::find_max;
+
+use crate::demo::Demo;
+
+pub fn all() -> Vec<Box<dyn Demo>> {
+    vec![
+        Box::new(iterators::Iterators),
+        Box::new(patterns::Patterns),
+        Box::new(errors::Errors),
+        Box::new(closures::Closures),
+        Box::new(collections::Collections),
+        Box::new(lifetimes::Lifetimes),
+        Box::new(macros::Macros),
+        Box::new(concurrency::Concurrency),
+        Box::new(statistics::Statistics),
+        Box::new(encoding::Encoding),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{self, Catalog};
+    use crate::log;
+    use crate::output::Capture;
+    use std::sync::Arc;
+
+    #[test]
+    fn helpers_behave_like_the_notes_say() {
+        assert_eq!(safe_divide(10.0, 2.0), Ok(5.0));
+        assert_eq!(safe_divide(8.0, 0.0), Err("Division by zero".to_string()));
+        assert_eq!(
+            longest("Hello World", "Rust Programming"),
+            "Rust Programming"
+        );
+        assert_eq!(find_max(&[3, 9, 2]), Some(9));
+        assert_eq!(find_max::<i32>(&[]), None);
+        assert_eq!((add(5, 3), multiply(5, 3), subtract(5, 3)), (8, 15, 2));
+    }
+
+    fn capture(id: &str) -> Capture {
+        let catalog = Catalog::builtin();
+        let mut out = Capture::new();
+        demo::run(&catalog.select(&[id]).unwrap(), &mut out, 1);
+        out
+    }
+
+    #[test]
+    fn demos_record_their_results() {
+        let out = capture("iterators");
+        assert_eq!(out.sections(), ["iterators"]);
+        assert_eq!(out.value("Sum of 1-100"), Some("5050"));
+        assert_eq!(
+            out.value("First 5 even squares"),
+            Some("[4, 16, 36, 64, 100]")
+        );
+
+        let out = capture("errors");
+        assert_eq!(out.value("10 / 2"), Some("5.00"));
+        assert_eq!(out.errors(), ["dividing 8 by 0: Division by zero"]);
+
+        let out = capture("statistics");
+        assert_eq!(out.value("Sum"), Some("500500"));
+        assert_eq!(out.value("Maximum number found"), Some("1000"));
+    }
+
+    #[test]
+    fn concurrency_logs_every_thread() {
+        let log = Arc::new(log::Capture::new(log::Filter::DEFAULT));
+        let out = log::with_subscriber(log.clone(), || capture("concurrency"));
+        assert_eq!(out.value("Final counter value"), Some("45"));
+
+        let mut records = log.records();
+        records.sort_by(|a, b| a.thread.cmp(&b.thread));
+        assert_eq!(records.len(), 10);
+        assert_eq!(records[0].thread, "worker-0");
+        assert_eq!(records[0].message, "Thread 0 updated counter");
+        assert_eq!(records[0].spans, ["run", "concurrency"]);
+
+        let timings = log.timings();
+        let last = timings.last().unwrap();
+        assert_eq!(last.path, ["run"]);
+        assert_eq!(timings[timings.len() - 2].path, ["run", "concurrency"]);
+    }
+
+    #[test]
+    fn encoding_round_trips_every_format() {
+        let out = capture("encoding");
+        assert!(out.errors().is_empty());
+        let table = out.tables()[0];
+        let bytes = |row: usize| table.rows()[row][2].text.parse::<usize>().unwrap();
+        // people: JSON, binary, CSV; shapes: JSON, binary
+        assert!(bytes(1) * 2 < bytes(0));
+        assert!(bytes(2) < bytes(0));
+        assert!(bytes(4) * 2 < bytes(3));
+    }
+
+    #[test]
+    fn collections_sort_the_scores() {
+        let out = capture("collections");
+        let tables = out.tables();
+        let names: Vec<&str> = tables[0]
+            .rows()
+            .iter()
+            .map(|r| r[0].text.as_str())
+            .collect();
+        assert_eq!(names, ["Alice", "Charlie", "Bob"]);
+        assert!(out.text().contains("- Alice\n"));
+    }
+}
@@ -0,0 +1,59 @@
+// Closures and higher-order functions.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+
+fn apply_operation<F>(numbers: &[i32], op: F) -> Vec<i32>
+where
+    F: Fn(i32) -> i32,
+{
+    numbers.iter().map(|&x| op(x)).collect()
+}
+
+pub struct Closures;
+
+impl Demo for Closures {
+    fn id(&self) -> &'static str {
+        "closures"
+    }
+
+    fn title(&self) -> &'static str {
+        "Closures and Higher-Order Functions"
+    }
+
+    fn description(&self) -> &'static str {
+        "capturing the environment and passing closures to functions"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        let numbers = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+        // Closure capturing environment
+        let threshold = 5;
+        let above_threshold: Vec<i32> = numbers
+            .iter()
+            .filter(|&&x| x > threshold)
+            .cloned()
+            .collect();
+        out.value(
+            &format!("Numbers above {}", threshold),
+            &format_args!("{:?}", above_threshold),
+        );
+
+        // Different closure types
+        let add_one = |x: i32| x + 1;
+        let multiply = |x: i32, y: i32| x * y;
+
+        let transformed: Vec<i32> = numbers
+            .iter()
+            .map(|&x| add_one(x))
+            .map(|x| multiply(x, 2))
+            .collect();
+        out.value("Transformed numbers", &format_args!("{:?}", transformed));
+
+        // Higher-order function
+        let squared = apply_operation(&numbers, |x| x * x);
+        out.value("Squared numbers", &format_args!("{:?}", squared));
+    }
+}
@@ -0,0 +1,69 @@
+// Collections and data structures, printed as tables so the `HashMap`
+// comes out in the same order every run.
+
+use std::collections::HashMap;
+
+use lessons::table::{Border, Tabulate};
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::person::Person;
+use crate::rng::Rng;
+
+pub struct Collections;
+
+impl Demo for Collections {
+    fn id(&self) -> &'static str {
+        "collections"
+    }
+
+    fn title(&self) -> &'static str {
+        "Collections and Data Structures"
+    }
+
+    fn description(&self) -> &'static str {
+        "a HashMap of scores and a Vec of people, filtered by skill"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        // HashMap
+        let mut scores = HashMap::new();
+        scores.insert("Alice", 95);
+        scores.insert("Bob", 87);
+        scores.insert("Charlie", 92);
+
+        out.line("Scores:");
+        let mut table = scores.iter().tabulate(&["Name", "Score"]);
+        table.set_border(Border::from_env());
+        table.sort_by_desc(1);
+        out.table(table);
+
+        // Complex data structures
+        let mut people = Vec::new();
+        let mut alice = Person::new("Alice", 25);
+        alice.add_skill("Rust");
+        alice.add_skill("Python");
+
+        let mut bob = Person::new("Bob", 17);
+        bob.add_skill("JavaScript");
+
+        people.push(alice);
+        people.push(bob);
+
+        out.line("\nPeople:");
+        let mut table = people.iter().tabulate(&["Name", "Age", "Skills", "Adult"]);
+        table.set_border(Border::from_env());
+        out.table(table);
+
+        // Find adults with specific skills
+        let rust_adults: Vec<&Person> = people
+            .iter()
+            .filter(|person| person.is_adult() && person.has_skill("Rust"))
+            .collect();
+
+        out.line("\nRust developers who are adults:");
+        for person in rust_adults {
+            out.line(&format!("- {}", person.name));
+        }
+    }
+}
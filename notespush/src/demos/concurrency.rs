@@ -0,0 +1,47 @@
+// Multithreading and concurrency: threads sharing a counter. Each worker
+// logs its update (see `log`); only the total goes to `out`.
+
+use std::sync::{Arc, Mutex};
+
+use crate::demo::Demo;
+use crate::log;
+use crate::output::Output;
+use crate::rng::Rng;
+
+pub struct Concurrency;
+
+impl Demo for Concurrency {
+    fn id(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn title(&self) -> &'static str {
+        "Concurrency and Threading"
+    }
+
+    fn description(&self) -> &'static str {
+        "ten threads adding to an Arc<Mutex<i32>>"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        let counter = Arc::new(Mutex::new(0));
+        let mut handles = vec![];
+
+        for i in 0..10 {
+            let counter = Arc::clone(&counter);
+            let handle = log::spawn(format!("worker-{}", i), move || {
+                let mut num = counter.lock().unwrap();
+                *num += i;
+                // Logged while the lock is held, so records come in update order.
+                crate::info!("Thread {} updated counter", i);
+            });
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        out.value("Final counter value", &*counter.lock().unwrap());
+    }
+}
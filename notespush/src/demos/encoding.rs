@@ -0,0 +1,91 @@
+// Encoding a generated dataset as JSON, in the binary format of `codec`
+// and, for people, as CSV, comparing sizes and checking each format
+// round-trips. How long each took is in the span timings (see `log`), so
+// the output itself stays the same for the same seed.
+
+use lessons::csv;
+use lessons::json::{self, FromJson, ToJson};
+use lessons::table::{Border, Table};
+
+use crate::codec::{self, Decode, Encode};
+use crate::demo::Demo;
+use crate::log;
+use crate::output::Output;
+use crate::person::Person;
+use crate::rng::Rng;
+use crate::shape::Shape;
+
+const COUNT: usize = 1000;
+
+pub struct Encoding;
+
+impl Demo for Encoding {
+    fn id(&self) -> &'static str {
+        "encoding"
+    }
+
+    fn title(&self) -> &'static str {
+        "JSON, Binary and CSV Encoding"
+    }
+
+    fn description(&self) -> &'static str {
+        "1000 random people and shapes as JSON, varint-packed binary and CSV"
+    }
+
+    fn run(&self, out: &mut dyn Output, rng: &mut Rng) {
+        let people: Vec<Person> = (0..COUNT).map(|_| Person::random(rng)).collect();
+        let shapes: Vec<Shape> = (0..COUNT).map(|_| Shape::random(rng)).collect();
+
+        let mut table = Table::new(&["Data", "Format", "Bytes"]);
+        table.set_border(Border::from_env());
+        let mut ok = true;
+        let span = log::span("people");
+        ok &= compare("people", &people, &mut table);
+        ok &= people_csv(&people, &mut table);
+        drop(span);
+        let span = log::span("shapes");
+        ok &= compare("shapes", &shapes, &mut table);
+        drop(span);
+        out.table(table);
+
+        if ok {
+            out.value("Round trip", &"every value came back unchanged");
+        } else {
+            out.error("a value changed on the way through");
+        }
+    }
+}
+
+// Adds a JSON row and a binary row for `data`, each encoded and decoded in
+// a span; true if both decode back to `data`.
+fn compare<T>(name: &str, data: &Vec<T>, table: &mut Table) -> bool
+where
+    T: ToJson + FromJson + Encode + Decode + PartialEq,
+{
+    let span = log::span("json");
+    let text = data.to_json().to_compact();
+    let from_json = json::parse(&text)
+        .ok()
+        .and_then(|value| Vec::<T>::from_json(&value).ok());
+    drop(span);
+
+    let span = log::span("binary");
+    let bytes = codec::to_bytes(data);
+    let from_binary = codec::from_bytes::<Vec<T>>(&bytes).ok();
+    drop(span);
+
+    table.push((name, "JSON", text.len()));
+    table.push((name, "binary", bytes.len()));
+    from_json.as_ref() == Some(data) && from_binary.as_ref() == Some(data)
+}
+
+// Adds a CSV row for `people`; true if they load back unchanged.
+fn people_csv(people: &[Person], table: &mut Table) -> bool {
+    let span = log::span("csv");
+    let text = csv::dump(people);
+    let back = csv::load::<Person>(&text).ok();
+    drop(span);
+
+    table.push(("people", "CSV", text.len()));
+    back.as_deref() == Some(people)
+}
@@ -0,0 +1,49 @@
+// Error handling with `Result` and `Option`.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+
+pub fn safe_divide(a: f64, b: f64) -> Result<f64, String> {
+    if b == 0.0 {
+        Err("Division by zero".to_string())
+    } else {
+        Ok(a / b)
+    }
+}
+
+pub struct Errors;
+
+impl Demo for Errors {
+    fn id(&self) -> &'static str {
+        "errors"
+    }
+
+    fn title(&self) -> &'static str {
+        "Error Handling"
+    }
+
+    fn description(&self) -> &'static str {
+        "Result from a checked division, Option from an out-of-bounds get"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        let operations = vec![(10.0, 2.0), (15.0, 3.0), (8.0, 0.0), (20.0, 4.0)];
+
+        for (a, b) in operations {
+            match safe_divide(a, b) {
+                Ok(result) => out.value(&format!("{} / {}", a, b), &format_args!("{:.2}", result)),
+                Err(e) => out.error(&format!("dividing {} by {}: {}", a, b, e)),
+            }
+        }
+
+        // Option handling
+        let numbers = [1, 2, 3, 4, 5];
+        let index = 10;
+
+        match numbers.get(index) {
+            Some(value) => out.value(&format!("Value at index {}", index), value),
+            None => out.line(&format!("Index {} is out of bounds", index)),
+        }
+    }
+}
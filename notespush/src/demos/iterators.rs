@@ -0,0 +1,50 @@
+// Iterator patterns and functional programming.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+
+pub struct Iterators;
+
+impl Demo for Iterators {
+    fn id(&self) -> &'static str {
+        "iterators"
+    }
+
+    fn title(&self) -> &'static str {
+        "Iterator Demonstrations"
+    }
+
+    fn description(&self) -> &'static str {
+        "take, filter/map, sum/product and partition over 1..=100"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        let numbers: Vec<i32> = (1..=100).collect();
+
+        // Basic iteration
+        let first: Vec<String> = numbers.iter().take(10).map(|n| n.to_string()).collect();
+        out.value("First 10 numbers", &first.join(" "));
+
+        // Filter and map
+        let even_squares: Vec<i32> = numbers
+            .iter()
+            .filter(|&&x| x % 2 == 0)
+            .map(|&x| x * x)
+            .take(5)
+            .collect();
+        out.value("First 5 even squares", &format_args!("{:?}", even_squares));
+
+        // Reduce operations
+        let sum: i32 = numbers.iter().sum();
+        let product: i32 = numbers.iter().take(5).product();
+        out.value("Sum of 1-100", &sum);
+        out.value("Product of 1-5", &product);
+
+        // Group by using partition
+        let (evens, odds): (Vec<i32>, Vec<i32>) =
+            numbers.iter().take(20).partition(|&&x| x % 2 == 0);
+        out.value("Evens", &format_args!("{:?}", evens));
+        out.value("Odds", &format_args!("{:?}", odds));
+    }
+}
@@ -0,0 +1,33 @@
+// Lifetime management: a function whose result borrows from its inputs.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+
+pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+
+pub struct Lifetimes;
+
+impl Demo for Lifetimes {
+    fn id(&self) -> &'static str {
+        "lifetimes"
+    }
+
+    fn title(&self) -> &'static str {
+        "Lifetime Management"
+    }
+
+    fn description(&self) -> &'static str {
+        "returning one of two borrowed strings"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        let string1 = "Hello World";
+        let string2 = "Rust Programming";
+
+        let result = longest(string1, string2);
+        out.value("Longest string", &format_args!("'{}'", result));
+    }
+}
@@ -0,0 +1,39 @@
+// Macros: `macro_rules!` writing functions.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+
+macro_rules! create_function {
+    ($name:ident, $op:tt) => {
+        pub fn $name(a: i32, b: i32) -> i32 {
+            a $op b
+        }
+    };
+}
+
+create_function!(add, +);
+create_function!(multiply, *);
+create_function!(subtract, -);
+
+pub struct Macros;
+
+impl Demo for Macros {
+    fn id(&self) -> &'static str {
+        "macros"
+    }
+
+    fn title(&self) -> &'static str {
+        "Macros"
+    }
+
+    fn description(&self) -> &'static str {
+        "add, multiply and subtract generated by macro_rules!"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        out.value("5 + 3", &add(5, 3));
+        out.value("5 * 3", &multiply(5, 3));
+        out.value("5 - 3", &subtract(5, 3));
+    }
+}
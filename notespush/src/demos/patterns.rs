@@ -0,0 +1,59 @@
+// Advanced pattern matching: guards and destructuring on `Shape`.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+use crate::shape::{Area, Shape};
+
+pub struct Patterns;
+
+impl Demo for Patterns {
+    fn id(&self) -> &'static str {
+        "patterns"
+    }
+
+    fn title(&self) -> &'static str {
+        "Advanced Pattern Matching"
+    }
+
+    fn description(&self) -> &'static str {
+        "match guards and destructuring on circles, rectangles and triangles"
+    }
+
+    fn run(&self, out: &mut dyn Output, _rng: &mut Rng) {
+        let shapes = vec![
+            Shape::Circle(5.0),
+            Shape::Rectangle(4.0, 6.0),
+            Shape::Triangle(3.0, 4.0, 5.0),
+        ];
+
+        for shape in &shapes {
+            let line = match shape {
+                Shape::Circle(r) if *r > 3.0 => {
+                    format!("Large circle with area: {:.2}", shape.area())
+                }
+                Shape::Circle(r) => format!("Small circle with radius: {}", r),
+                Shape::Rectangle(w, h) if w == h => {
+                    format!("Square with area: {:.2}", shape.area())
+                }
+                Shape::Rectangle(w, h) => {
+                    format!("Rectangle {}x{} with area: {:.2}", w, h, shape.area())
+                }
+                Shape::Triangle(a, b, c) => {
+                    if a == b && b == c {
+                        format!("Equilateral triangle with area: {:.2}", shape.area())
+                    } else {
+                        format!(
+                            "Triangle with sides {}, {}, {} and area: {:.2}",
+                            a,
+                            b,
+                            c,
+                            shape.area()
+                        )
+                    }
+                }
+            };
+            out.line(&line);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Final statistics: a fold computing several results at once, and a
+// generic function, over a range and over seeded random dice rolls.
+
+use crate::demo::Demo;
+use crate::output::Output;
+use crate::rng::Rng;
+
+pub fn find_max<T: Ord + Clone>(list: &[T]) -> Option<T> {
+    list.iter().max().cloned()
+}
+
+pub struct Statistics;
+
+impl Demo for Statistics {
+    fn id(&self) -> &'static str {
+        "statistics"
+    }
+
+    fn title(&self) -> &'static str {
+        "Final Statistics"
+    }
+
+    fn description(&self) -> &'static str {
+        "sum, min and max in one fold, over 1..=1000 and 100 random dice rolls"
+    }
+
+    fn run(&self, out: &mut dyn Output, rng: &mut Rng) {
+        let numbers: Vec<i32> = (1..=1000).collect();
+        let stats = numbers
+            .iter()
+            .fold((0, i32::MAX, i32::MIN), |(sum, min, max), &x| {
+                (sum + x, min.min(x), max.max(x))
+            });
+
+        out.value("Sum", &stats.0);
+        out.value("Min", &stats.1);
+        out.value("Max", &stats.2);
+
+        // Demonstrate generic function
+        let max_number = find_max(&numbers).unwrap_or(0);
+        out.value("Maximum number found", &max_number);
+
+        // The same fold over random data: the counts of each face
+        let rolls: Vec<i64> = (0..100).map(|_| rng.range(1, 6)).collect();
+        let counts = rolls.iter().fold([0; 6], |mut counts, &roll| {
+            counts[roll as usize - 1] += 1;
+            counts
+        });
+        out.value("Dice rolls", &rolls.len());
+        out.value("Count of each face", &format_args!("{:?}", counts));
+        out.value("Highest roll", &find_max(&rolls).unwrap_or(0));
+    }
+}
@@ -0,0 +1,21 @@
+// The "Advanced Rust Programming Examples" from the old notespush.rs.
+//
+// Each demo is a module under `demos/` with a type implementing `Demo`;
+// `Catalog::builtin()` knows all of them, so the binary can list them and
+// run any subset by id. Demos write results to an `Output` rather than
+// stdout (see `output`) and progress to the `log`, timed in spans. The
+// types they share live in `shape` and `person`.
+
+pub mod cli;
+pub mod codec;
+pub mod config;
+pub mod demo;
+pub mod demos;
+pub mod log;
+pub mod output;
+pub mod person;
+pub mod rng;
+pub mod shape;
+
+pub use demo::{Catalog, Demo};
+pub use output::Output;
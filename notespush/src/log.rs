@@ -0,0 +1,513 @@
+// Logging and timed spans for the demos, in-house like the rest.
+//
+// `info!("...")` and friends build a `Record` and hand it to the current
+// `Subscriber`: the one set for this thread with `with_subscriber` (tests
+// use a `Capture`), or else the one `main` set with `set_global`. Every
+// record carries its level, the thread's name and the spans open on that
+// thread. A span is a guard from `span(name)`; when it drops, the
+// subscriber hears how long it was open.
+//
+// `log::spawn` starts a named thread that keeps the caller's subscriber and
+// spans, so records from worker threads land in the same place.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Most severe first, so `level <= filter` means "shown".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        // Padded, so messages line up.
+        f.pad(name)
+    }
+}
+
+// A filter: the most verbose level shown, or `None` for `off`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter(pub Option<Level>);
+
+impl Filter {
+    pub const DEFAULT: Filter = Filter(Some(Level::Info));
+
+    pub fn allows(self, level: Level) -> bool {
+        self.0.is_some_and(|most| level <= most)
+    }
+
+    // `$NOTESPUSH_LOG`, or `DEFAULT` when it is not set.
+    pub fn from_env(value: Option<&str>) -> Result<Filter, String> {
+        match value {
+            None => Ok(Filter::DEFAULT),
+            Some(value) => value.parse().map_err(|e| format!("{}: {}", ENV_VAR, e)),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Filter, String> {
+        let level = match s.trim().to_lowercase().as_str() {
+            "off" => return Ok(Filter(None)),
+            "error" => Level::Error,
+            "warn" => Level::Warn,
+            "info" => Level::Info,
+            "debug" => Level::Debug,
+            "trace" => Level::Trace,
+            _ => {
+                return Err(format!(
+                    "unknown level `{}` (expected off, error, warn, info, debug or trace)",
+                    s
+                ));
+            }
+        };
+        Ok(Filter(Some(level)))
+    }
+}
+
+pub const ENV_VAR: &str = "NOTESPUSH_LOG";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub level: Level,
+    pub time: SystemTime,
+    pub thread: String,
+    // The spans open on the thread, outermost first.
+    pub spans: Vec<&'static str>,
+    pub message: String,
+}
+
+// A span that has closed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    // The span's own name last.
+    pub path: Vec<&'static str>,
+    pub thread: String,
+    pub elapsed: Duration,
+}
+
+pub trait Subscriber: Send + Sync {
+    fn enabled(&self, level: Level) -> bool;
+    fn record(&self, record: &Record);
+    fn close(&self, timing: &Timing);
+}
+
+static GLOBAL: OnceLock<Arc<dyn Subscriber>> = OnceLock::new();
+
+thread_local! {
+    static LOCAL: RefCell<Option<Arc<dyn Subscriber>>> = const { RefCell::new(None) };
+    static SPANS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+// For the whole process; only the first call counts.
+pub fn set_global(subscriber: Arc<dyn Subscriber>) {
+    let _ = GLOBAL.set(subscriber);
+}
+
+// This thread's subscriber, else the global one.
+pub fn current() -> Option<Arc<dyn Subscriber>> {
+    LOCAL
+        .with(|local| local.borrow().clone())
+        .or_else(|| GLOBAL.get().cloned())
+}
+
+// Runs `f` with `subscriber` in place of the global one on this thread.
+pub fn with_subscriber<T>(subscriber: Arc<dyn Subscriber>, f: impl FnOnce() -> T) -> T {
+    let previous = LOCAL.with(|local| local.replace(Some(subscriber)));
+    // Put back even if `f` panics, so one failing test cannot leak its
+    // subscriber into the next on this thread.
+    struct Restore(Option<Arc<dyn Subscriber>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LOCAL.with(|local| *local.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(previous);
+    f()
+}
+
+fn thread_name() -> String {
+    thread::current().name().unwrap_or("unnamed").to_string()
+}
+
+fn open_spans() -> Vec<&'static str> {
+    SPANS.with(|spans| spans.borrow().clone())
+}
+
+// What the macros call; `args` is only formatted if someone is listening.
+pub fn log(level: Level, args: fmt::Arguments) {
+    let Some(subscriber) = current() else {
+        return;
+    };
+    if !subscriber.enabled(level) {
+        return;
+    }
+    subscriber.record(&Record {
+        level,
+        time: SystemTime::now(),
+        thread: thread_name(),
+        spans: open_spans(),
+        message: args.to_string(),
+    });
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Error, format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Warn, format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Info, format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Debug, format_args!($($arg)+)) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log::log($crate::log::Level::Trace, format_args!($($arg)+)) };
+}
+
+// Open until dropped. Spans nest per thread, so guards must drop in the
+// reverse of the order they were made, as `let` bindings do.
+#[must_use = "a span closes as soon as its guard is dropped"]
+pub struct Span {
+    start: Instant,
+}
+
+pub fn span(name: &'static str) -> Span {
+    SPANS.with(|spans| spans.borrow_mut().push(name));
+    Span {
+        start: Instant::now(),
+    }
+}
+
+impl Span {
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let path = open_spans();
+        SPANS.with(|spans| spans.borrow_mut().pop());
+        if let Some(subscriber) = current() {
+            subscriber.close(&Timing {
+                path,
+                thread: thread_name(),
+                elapsed: self.elapsed(),
+            });
+        }
+    }
+}
+
+// `thread::spawn` for a thread called `name` that logs where this one does,
+// inside the spans open here.
+pub fn spawn<F, T>(name: String, f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let subscriber = current();
+    let spans = open_spans();
+    thread::Builder::new()
+        .name(name)
+        .spawn(move || {
+            SPANS.with(|open| *open.borrow_mut() = spans);
+            match subscriber {
+                Some(subscriber) => with_subscriber(subscriber, f),
+                None => f(),
+            }
+        })
+        .expect("the OS could not start a thread")
+}
+
+// Span timings added up by path, for the summary tree.
+#[derive(Debug, Default)]
+pub struct Timings {
+    // In the order each path first closed; children close before their
+    // parent, so siblings keep the order they ran in.
+    totals: Vec<(Vec<&'static str>, Duration, usize)>,
+}
+
+impl Timings {
+    pub fn add(&mut self, timing: &Timing) {
+        match self
+            .totals
+            .iter_mut()
+            .find(|(path, ..)| *path == timing.path)
+        {
+            Some((_, total, count)) => {
+                *total += timing.elapsed;
+                *count += 1;
+            }
+            None => self.totals.push((timing.path.clone(), timing.elapsed, 1)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    // One line per path, drawn as a tree, with the total time and, for
+    // spans opened more than once, how many times:
+    //
+    //     run               1.2ms
+    //     ├── iterators    52.1µs
+    //     └── concurrency 803.0µs
+    pub fn tree(&self) -> String {
+        let mut lines = Vec::new();
+        self.branch(&[], "", &mut lines);
+        let width = lines.iter().map(|(label, ..)| label.chars().count()).max();
+        let mut out = String::new();
+        for (label, time, count) in &lines {
+            let pad = width.unwrap_or(0) - label.chars().count();
+            out += &format!("{}{}  {:>8}", label, " ".repeat(pad), time);
+            if *count > 1 {
+                out += &format!(" ×{}", count);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn branch(&self, parent: &[&str], indent: &str, lines: &mut Vec<(String, String, usize)>) {
+        let children: Vec<_> = self
+            .totals
+            .iter()
+            .filter(|(path, ..)| path.len() == parent.len() + 1 && path.starts_with(parent))
+            .collect();
+        for (i, (path, total, count)) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            let (branch, next) = match (parent.is_empty(), last) {
+                (true, _) => ("", String::new()),
+                (false, false) => ("├── ", format!("{}│   ", indent)),
+                (false, true) => ("└── ", format!("{}    ", indent)),
+            };
+            let name = path.last().expect("paths are never empty");
+            let label = format!("{}{}{}", indent, branch, name);
+            lines.push((label, format!("{:.1?}", total), *count));
+            self.branch(path, &next, lines);
+        }
+    }
+}
+
+// Writes records to stderr as
+// `14:03:07.218 INFO  worker-3 run/concurrency: Thread 3 updated counter`
+// (UTC) and keeps span timings for `summary`.
+pub struct Stderr {
+    filter: Filter,
+    timings: Mutex<Timings>,
+}
+
+impl Stderr {
+    pub fn new(filter: Filter) -> Stderr {
+        Stderr {
+            filter,
+            timings: Mutex::new(Timings::default()),
+        }
+    }
+
+    // The span timings as a tree, if info records are shown and any span
+    // closed.
+    pub fn summary(&self) -> Option<String> {
+        let timings = self.timings.lock().unwrap();
+        if !self.filter.allows(Level::Info) || timings.is_empty() {
+            return None;
+        }
+        Some(timings.tree())
+    }
+}
+
+impl Subscriber for Stderr {
+    fn enabled(&self, level: Level) -> bool {
+        self.filter.allows(level)
+    }
+
+    fn record(&self, record: &Record) {
+        let mut line = format!(
+            "{} {:5} {}",
+            clock(record.time),
+            record.level,
+            record.thread
+        );
+        if !record.spans.is_empty() {
+            line += &format!(" {}", record.spans.join("/"));
+        }
+        // Logging must not take the program down with it.
+        let _ = writeln!(io::stderr().lock(), "{}: {}", line, record.message);
+    }
+
+    fn close(&self, timing: &Timing) {
+        self.timings.lock().unwrap().add(timing);
+    }
+}
+
+// `HH:MM:SS.mmm`, UTC.
+fn clock(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs() % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        since.subsec_millis()
+    )
+}
+
+// Keeps everything, for tests.
+pub struct Capture {
+    filter: Filter,
+    records: Mutex<Vec<Record>>,
+    timings: Mutex<Vec<Timing>>,
+}
+
+impl Capture {
+    pub fn new(filter: Filter) -> Capture {
+        Capture {
+            filter,
+            records: Mutex::new(Vec::new()),
+            timings: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn records(&self) -> Vec<Record> {
+        self.records.lock().unwrap().clone()
+    }
+
+    pub fn messages(&self) -> Vec<String> {
+        self.records().into_iter().map(|r| r.message).collect()
+    }
+
+    // Closed spans, in the order they closed.
+    pub fn timings(&self) -> Vec<Timing> {
+        self.timings.lock().unwrap().clone()
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, level: Level) -> bool {
+        self.filter.allows(level)
+    }
+
+    fn record(&self, record: &Record) {
+        self.records.lock().unwrap().push(record.clone());
+    }
+
+    fn close(&self, timing: &Timing) {
+        self.timings.lock().unwrap().push(timing.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(filter: &str, f: impl FnOnce()) -> Arc<Capture> {
+        let capture = Arc::new(Capture::new(filter.parse().unwrap()));
+        with_subscriber(capture.clone(), f);
+        capture
+    }
+
+    #[test]
+    fn filters_by_level() {
+        let log = capture("info", || {
+            crate::error!("e");
+            crate::warn!("w");
+            crate::info!("i {}", 1);
+            crate::debug!("d");
+            crate::trace!("t");
+        });
+        assert_eq!(log.messages(), ["e", "w", "i 1"]);
+        let levels: Vec<Level> = log.records().iter().map(|r| r.level).collect();
+        assert_eq!(levels, [Level::Error, Level::Warn, Level::Info]);
+
+        assert!(capture("off", || crate::error!("e")).records().is_empty());
+        assert_eq!(capture("TRACE", || crate::trace!("t")).messages(), ["t"]);
+        assert_eq!(
+            Filter::from_env(Some("loud")).unwrap_err(),
+            "NOTESPUSH_LOG: unknown level `loud` (expected off, error, warn, info, debug or trace)"
+        );
+        assert_eq!(Filter::from_env(None), Ok(Filter::DEFAULT));
+    }
+
+    #[test]
+    fn records_spans_and_thread_names() {
+        let log = capture("debug", || {
+            let _outer = span("outer");
+            {
+                let _inner = span("inner");
+                crate::debug!("in both");
+            }
+            spawn("worker-1".to_string(), || crate::info!("from a worker"))
+                .join()
+                .unwrap();
+        });
+
+        let records = log.records();
+        assert_eq!(records[0].spans, ["outer", "inner"]);
+        assert_eq!(records[0].thread, thread::current().name().unwrap());
+        assert_eq!(records[1].spans, ["outer"]);
+        assert_eq!(records[1].thread, "worker-1");
+
+        let paths: Vec<Vec<&str>> = log.timings().into_iter().map(|t| t.path).collect();
+        assert_eq!(paths, [vec!["outer", "inner"], vec!["outer"]]);
+    }
+
+    #[test]
+    fn sums_timings_into_a_tree() {
+        let ms = Duration::from_millis;
+        let mut timings = Timings::default();
+        for (path, elapsed) in [
+            (vec!["run", "a", "x"], ms(1)),
+            (vec!["run", "a"], ms(2)),
+            (vec!["run", "b"], ms(3)),
+            (vec!["run", "b"], ms(4)),
+            (vec!["run"], ms(10)),
+        ] {
+            timings.add(&Timing {
+                path,
+                thread: "main".to_string(),
+                elapsed,
+            });
+        }
+        assert_eq!(
+            timings.tree(),
+            "\
+run          10.0ms
+├── a         2.0ms
+│   └── x     1.0ms
+└── b         7.0ms ×2
+"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::env;
+use std::io;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use lessons::json::Value;
+use lessons::table::{Border, Tabulate};
+use notespush::cli::{self, Command, Format, Options};
+use notespush::config::{self, Config, Today};
+use notespush::log::{self, Filter};
+use notespush::output::{JsonLines, Text};
+use notespush::rng::Rng;
+use notespush::{Catalog, Output, demo};
+
+fn main() -> ExitCode {
+    let catalog = Catalog::builtin();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match cli::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::usage(&catalog));
+            return ExitCode::from(2);
+        }
+    };
+
+    match options.command {
+        Command::Help => {
+            print!("{}", cli::usage(&catalog));
+            return ExitCode::SUCCESS;
+        }
+        Command::List => {
+            list(&catalog, options.format);
+            return ExitCode::SUCCESS;
+        }
+        Command::ShowConfig | Command::Run => {}
+    }
+
+    let config = match load_config(&options) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if options.command == Command::ShowConfig {
+        show_config(&config, options.format);
+        return ExitCode::SUCCESS;
+    }
+
+    let demos = match options.select(&catalog) {
+        Ok(demos) => demos,
+        Err(e) => {
+            eprintln!("{}\n`notespush --list` shows every demo", e);
+            return ExitCode::from(2);
+        }
+    };
+    let seed = options.seed.unwrap_or_else(Rng::seed_from_clock);
+
+    let logger = match Filter::from_env(env::var(log::ENV_VAR).ok().as_deref()) {
+        Ok(filter) => Arc::new(log::Stderr::new(filter)),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    log::set_global(logger.clone());
+
+    let result = match options.format {
+        Format::Text => {
+            let banner = config.banner();
+            for line in &banner {
+                println!("{}", line);
+            }
+            if !banner.is_empty() {
+                println!();
+            }
+
+            let mut out = Text::new(io::stdout().lock());
+            out.value("Seed", &seed);
+            let panicked = demo::run(&demos, &mut out, seed);
+            out.finish().map(|_| {
+                if panicked.is_empty() {
+                    println!("\n🎉 Advanced Rust demonstration complete!");
+                }
+                panicked
+            })
+        }
+        Format::Json => {
+            let mut out = JsonLines::new(io::stdout().lock());
+            out.value("Seed", &seed);
+            let panicked = demo::run(&demos, &mut out, seed);
+            out.finish().map(|_| panicked)
+        }
+    };
+    let panicked = match result {
+        Ok(panicked) => panicked,
+        Err(e) => {
+            eprintln!("notespush: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(summary) = logger.summary() {
+        eprint!("\nSpan timings:\n{}", summary);
+    }
+    if !panicked.is_empty() {
+        eprintln!("notespush: {} panicked", panicked.join(", "));
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn list(catalog: &Catalog, format: Format) {
+    match format {
+        Format::Text => {
+            let mut table = catalog
+                .demos()
+                .map(|d| (d.id(), d.title(), d.description()))
+                .tabulate(&["Demo", "Title", "Description"]);
+            table.set_border(Border::from_env());
+            print!("{}", table);
+        }
+        Format::Json => {
+            for d in catalog.demos() {
+                let value = Value::object([
+                    ("id", d.id().into()),
+                    ("title", d.title().into()),
+                    ("description", d.description().into()),
+                ]);
+                println!("{}", value.to_compact());
+            }
+        }
+    }
+}
+
+// `--config`, then $NOTESPUSH_CONFIG, then notespush.toml (which may be
+// missing: every setting has a default).
+fn load_config(options: &Options) -> Result<Config, String> {
+    let (path, required) = match (&options.config, env::var_os("NOTESPUSH_CONFIG")) {
+        (Some(path), _) => (path.clone(), true),
+        (None, Some(path)) => (path.into(), true),
+        (None, None) => (config::default_path(), false),
+    };
+    Config::load(&path, required, |var| env::var(var).ok(), Today::now())
+}
+
+fn show_config(config: &Config, format: Format) {
+    let rows = config
+        .settings()
+        .iter()
+        .map(|s| (s.key, config::show(&s.value), s.source.to_string()));
+    match format {
+        Format::Text => {
+            let mut table = rows.tabulate(&["Setting", "Value", "Source"]);
+            table.set_border(Border::from_env());
+            print!("{}", table);
+        }
+        Format::Json => {
+            for (key, value, source) in rows {
+                let value = Value::object([
+                    ("key", key.into()),
+                    ("value", value.into()),
+                    ("source", source.into()),
+                ]);
+                println!("{}", value.to_compact());
+            }
+        }
+    }
+}
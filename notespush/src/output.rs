@@ -0,0 +1,286 @@
+// Where demos write their results: a stream of events rather than text, so
+// the same run can be read by a person, piped to tooling or asserted on.
+//
+// `Text` prints what the demos always printed, `JsonLines` writes one JSON
+// object per event and `Capture` keeps the events for tests. Writing never
+// fails from the demo's point of view; the writer renderers keep the first
+// I/O error and hand it back from `finish`.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use lessons::json::Value;
+use lessons::table::Table;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    // The start of a demo.
+    Section { id: String, title: String },
+    // Prose and anything that is not a result.
+    Line(String),
+    Value { key: String, value: String },
+    Error(String),
+    Table(Table),
+}
+
+pub trait Output {
+    fn event(&mut self, event: Event);
+
+    fn section(&mut self, id: &str, title: &str) {
+        self.event(Event::Section {
+            id: id.to_string(),
+            title: title.to_string(),
+        });
+    }
+
+    fn line(&mut self, text: &str) {
+        self.event(Event::Line(text.to_string()));
+    }
+
+    fn value(&mut self, key: &str, value: &dyn fmt::Display) {
+        self.event(Event::Value {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    fn error(&mut self, message: &str) {
+        self.event(Event::Error(message.to_string()));
+    }
+
+    fn table(&mut self, table: Table) {
+        self.event(Event::Table(table));
+    }
+}
+
+// The human-readable output.
+pub struct Text<W: Write> {
+    out: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Text<W> {
+    pub fn new(out: W) -> Text<W> {
+        Text { out, error: None }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.out.flush().map(|()| self.out),
+        }
+    }
+}
+
+impl<W: Write> Output for Text<W> {
+    fn event(&mut self, event: Event) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = match &event {
+            Event::Section { title, .. } => writeln!(self.out, "\n=== {} ===", title),
+            Event::Line(text) => writeln!(self.out, "{}", text),
+            Event::Value { key, value } => writeln!(self.out, "{}: {}", key, value),
+            Event::Error(message) => writeln!(self.out, "Error: {}", message),
+            Event::Table(table) => write!(self.out, "{}", table),
+        };
+        self.error = result.err();
+    }
+}
+
+// One JSON object per line, e.g.
+// `{"event":"value","key":"Sum of 1-100","value":"5050"}`.
+pub struct JsonLines<W: Write> {
+    out: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonLines<W> {
+    pub fn new(out: W) -> JsonLines<W> {
+        JsonLines { out, error: None }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.out.flush().map(|()| self.out),
+        }
+    }
+}
+
+impl<W: Write> Output for JsonLines<W> {
+    fn event(&mut self, event: Event) {
+        if self.error.is_some() {
+            return;
+        }
+        self.error = writeln!(self.out, "{}", json_line(&event)).err();
+    }
+}
+
+fn json_line(event: &Event) -> String {
+    let strings =
+        |items: &mut dyn Iterator<Item = &str>| Value::Array(items.map(Value::from).collect());
+    let value = match event {
+        Event::Section { id, title } => Value::object([
+            ("event", "section".into()),
+            ("id", id.as_str().into()),
+            ("title", title.as_str().into()),
+        ]),
+        Event::Line(text) => {
+            Value::object([("event", "line".into()), ("text", text.as_str().into())])
+        }
+        Event::Value { key, value } => Value::object([
+            ("event", "value".into()),
+            ("key", key.as_str().into()),
+            ("value", value.as_str().into()),
+        ]),
+        Event::Error(message) => Value::object([
+            ("event", "error".into()),
+            ("message", message.as_str().into()),
+        ]),
+        Event::Table(table) => Value::object([
+            ("event", "table".into()),
+            (
+                "header",
+                strings(&mut table.header().iter().map(String::as_str)),
+            ),
+            (
+                "rows",
+                Value::Array(
+                    table
+                        .rows()
+                        .iter()
+                        .map(|row| strings(&mut row.iter().map(|cell| cell.text.as_str())))
+                        .collect(),
+                ),
+            ),
+        ]),
+    };
+    value.to_compact()
+}
+
+// Keeps every event, for tests.
+#[derive(Debug, Default)]
+pub struct Capture {
+    pub events: Vec<Event>,
+}
+
+impl Capture {
+    pub fn new() -> Capture {
+        Capture::default()
+    }
+
+    // The last value recorded under `key`.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.events.iter().rev().find_map(|event| match event {
+            Event::Value { key: k, value } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn sections(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Section { id, .. } => Some(id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn errors(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Error(message) => Some(message.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn tables(&self) -> Vec<&Table> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Table(table) => Some(table),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // What `Text` would have printed.
+    pub fn text(&self) -> String {
+        let mut text = Text::new(Vec::new());
+        for event in &self.events {
+            text.event(event.clone());
+        }
+        let bytes = text.finish().expect("writing to a Vec cannot fail");
+        String::from_utf8(bytes).expect("events are strings")
+    }
+}
+
+impl Output for Capture {
+    fn event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(out: &mut dyn Output) {
+        out.section("errors", "Error Handling");
+        out.value("10 / 2", &"5.00");
+        out.error("dividing 8 by 0: Division by zero");
+        out.line("say \"hi\"\tthen\nleave");
+    }
+
+    #[test]
+    fn renders_text() {
+        let mut text = Text::new(Vec::new());
+        record(&mut text);
+        assert_eq!(
+            String::from_utf8(text.finish().unwrap()).unwrap(),
+            "\n=== Error Handling ===\n10 / 2: 5.00\n\
+             Error: dividing 8 by 0: Division by zero\nsay \"hi\"\tthen\nleave\n"
+        );
+    }
+
+    #[test]
+    fn renders_json_lines() {
+        let mut json = JsonLines::new(Vec::new());
+        record(&mut json);
+        json.table(Table::new(&["Name"]));
+        let out = String::from_utf8(json.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"event":"section","id":"errors","title":"Error Handling"}"#,
+                r#"{"event":"value","key":"10 / 2","value":"5.00"}"#,
+                r#"{"event":"error","message":"dividing 8 by 0: Division by zero"}"#,
+                r#"{"event":"line","text":"say \"hi\"\tthen\nleave"}"#,
+                r#"{"event":"table","header":["Name"],"rows":[]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_write_error() {
+        struct Full;
+
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut text = Text::new(Full);
+        record(&mut text);
+        assert_eq!(text.finish().err().unwrap().to_string(), "disk full");
+    }
+}
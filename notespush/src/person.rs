@@ -0,0 +1,283 @@
+// Structs and implementations: a person with a list of skills.
+
+use crate::codec::{self, Decode, Encode, Reader, Writer};
+use crate::rng::Rng;
+use lessons::csv::{self, FromCsv, Record, ToCsv};
+use lessons::json::{self, DecodeError, FromJson, ToJson, Value};
+use lessons::table::{Cell, Row, ToCell};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Person {
+    pub name: String,
+    pub age: u32,
+    pub skills: Vec<String>,
+}
+
+impl Person {
+    pub fn new(name: impl Into<String>, age: u32) -> Self {
+        Person {
+            name: name.into(),
+            age,
+            skills: Vec::new(),
+        }
+    }
+
+    pub fn add_skill(&mut self, skill: impl Into<String>) {
+        self.skills.push(skill.into());
+    }
+
+    pub fn is_adult(&self) -> bool {
+        self.age >= 18
+    }
+
+    pub fn has_skill(&self, skill: &str) -> bool {
+        self.skills.iter().any(|s| s == skill)
+    }
+
+    // Someone made up, for datasets: a name, an age from 10 to 70 and up
+    // to four different skills.
+    pub fn random(rng: &mut Rng) -> Person {
+        const NAMES: &[&str] = &[
+            "Alice", "Bob", "Charlie", "Dana", "Eve", "Ferris", "Grace", "Hana", "Ivan", "Zoë",
+        ];
+        const SKILLS: &[&str] = &[
+            "Rust",
+            "Python",
+            "JavaScript",
+            "Go",
+            "SQL",
+            "C",
+            "Haskell",
+            "Zig",
+        ];
+        let mut person = Person::new(*rng.pick(NAMES), rng.range(10, 70) as u32);
+        for _ in 0..rng.range(0, 4) {
+            let skill = *rng.pick(SKILLS);
+            if !person.has_skill(skill) {
+                person.add_skill(skill);
+            }
+        }
+        person
+    }
+}
+
+// Name, age, skills and whether they are an adult, for `lessons::table`.
+impl Row for Person {
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            self.name.to_cell(),
+            self.age.to_cell(),
+            self.skills.to_cell(),
+            self.is_adult().to_cell(),
+        ]
+    }
+}
+
+// `{"name": "Alice", "age": 25, "skills": ["Rust"]}`; `skills` may be left
+// out.
+impl ToJson for Person {
+    fn to_json(&self) -> Value {
+        Value::object([
+            ("name", self.name.to_json()),
+            ("age", self.age.to_json()),
+            ("skills", self.skills.to_json()),
+        ])
+    }
+}
+
+impl FromJson for Person {
+    fn from_json(value: &Value) -> Result<Person, DecodeError> {
+        Ok(Person {
+            name: json::field(value, "name")?,
+            age: json::field(value, "age")?,
+            skills: json::field::<Option<Vec<String>>>(value, "skills")?.unwrap_or_default(),
+        })
+    }
+}
+
+// `name,age,skills` with the skills in one column, separated by semicolons:
+// `Alice,25,Rust; Python`. `skills` may be left out of the header. Spaces
+// around a skill are dropped, so a backslash escapes what would otherwise
+// be lost: `C\; C++` is one skill, and `\\` a backslash.
+impl ToCsv for Person {
+    const HEADER: &'static [&'static str] = &["name", "age", "skills"];
+
+    fn to_record(&self) -> Vec<String> {
+        let skills: Vec<String> = self.skills.iter().map(|s| escape_skill(s)).collect();
+        vec![self.name.clone(), self.age.to_string(), skills.join("; ")]
+    }
+}
+
+impl FromCsv for Person {
+    const REQUIRED: &'static [&'static str] = &["name", "age"];
+
+    fn from_record(record: &Record) -> Result<Person, csv::Error> {
+        let name = record.require("name");
+        if name.trim().is_empty() {
+            return Err(record.error("name", "is empty"));
+        }
+        let skills = record.get("skills").unwrap_or_default();
+        Ok(Person {
+            name: name.to_string(),
+            age: record.parse("age", "an age in whole years")?,
+            skills: split_skills(skills).map_err(|e| record.error("skills", e))?,
+        })
+    }
+}
+
+// Backslashes before `\`, `;` and the whitespace at either end.
+fn escape_skill(skill: &str) -> String {
+    let trimmed = skill.trim();
+    let start = skill.len() - skill.trim_start().len();
+    let end = start + trimmed.len();
+    let mut out = String::new();
+    for (i, c) in skill.char_indices() {
+        if matches!(c, '\\' | ';') || i < start || i >= end {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// The skills in a column written by `escape_skill` or by hand. Empty ones
+// are left out, so `Rust;` is just Rust.
+fn split_skills(column: &str) -> Result<Vec<String>, String> {
+    let mut skills = Vec::new();
+    // The skill so far, and its length up to the last escaped character,
+    // which trimming must not cut into.
+    let mut skill = String::new();
+    let mut kept = 0;
+    let mut chars = column.chars();
+    let mut finish = |skill: &mut String, kept: &mut usize| {
+        let end = skill.trim_end().len().max(*kept);
+        skill.truncate(end);
+        if !skill.is_empty() {
+            skills.push(std::mem::take(skill));
+        }
+        skill.clear();
+        *kept = 0;
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars
+                    .next()
+                    .ok_or("ends in a lone `\\` (write `\\\\` for a backslash)")?;
+                skill.push(escaped);
+                kept = skill.len();
+            }
+            ';' => finish(&mut skill, &mut kept),
+            c if c.is_whitespace() && skill.is_empty() => {}
+            c => skill.push(c),
+        }
+    }
+    finish(&mut skill, &mut kept);
+    Ok(skills)
+}
+
+// Name, age, then skills.
+impl Encode for Person {
+    fn encode(&self, w: &mut Writer) {
+        self.name.encode(w);
+        self.age.encode(w);
+        self.skills.encode(w);
+    }
+}
+
+impl Decode for Person {
+    fn decode(r: &mut Reader) -> Result<Person, codec::Error> {
+        Ok(Person {
+            name: String::decode(r)?,
+            age: u32::decode(r)?,
+            skills: Vec::decode(r)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut alice = Person::new("Alice", 25);
+        alice.add_skill("Rust");
+        alice.add_skill("Python");
+        let people = vec![alice, Person::new("Bob", 17)];
+
+        let text = people.to_json().to_pretty(2);
+        let back = Vec::<Person>::from_json(&json::parse(&text).unwrap());
+        assert_eq!(back, Ok(people));
+
+        let bob = json::parse(r#"{"name": "Bob", "age": 17}"#).unwrap();
+        assert_eq!(Person::from_json(&bob), Ok(Person::new("Bob", 17)));
+    }
+
+    #[test]
+    fn round_trips_through_csv() {
+        let mut alice = Person::new("Smith, Alice", 25);
+        alice.add_skill("Rust");
+        alice.add_skill("Python");
+        alice.add_skill("\"Go\"");
+        let mut bob = Person::new(" Bob ", 17);
+        bob.add_skill("C; C++");
+        bob.add_skill(" spaced ");
+        bob.add_skill("back\\slash");
+        let people = vec![alice, bob];
+
+        let text = csv::dump(&people);
+        let lines: Vec<&str> = text.split("\r\n").collect();
+        assert_eq!(
+            lines,
+            [
+                "name,age,skills",
+                r#""Smith, Alice",25,"Rust; Python; ""Go""""#,
+                r" Bob ,17,C\; C++; \ spaced\ ; back\\slash",
+                "",
+            ]
+        );
+        assert_eq!(csv::load::<Person>(&text), Ok(people));
+
+        let sheet = "age,name,team,skills\n30,Dana,infra, Rust ;Go;; \n";
+        let mut dana = Person::new("Dana", 30);
+        dana.add_skill("Rust");
+        dana.add_skill("Go");
+        assert_eq!(csv::load::<Person>(sheet), Ok(vec![dana]));
+    }
+
+    #[test]
+    fn says_which_cell_is_wrong() {
+        let error = |text: &str| csv::load::<Person>(text).unwrap_err().to_string();
+        assert_eq!(
+            error("name,age,skills\nAlice,25,Rust\nBob,seventeen,\n"),
+            "row 3, column 2: age: expected an age in whole years, found `seventeen`"
+        );
+        assert_eq!(
+            error("name,age\nAlice,25\n ,40\n"),
+            "row 3, column 1: name: is empty"
+        );
+        assert_eq!(
+            error("name,age,skills\nAlice,25,C\\\n"),
+            "row 2, column 3: skills: ends in a lone `\\` (write `\\\\` for a backslash)"
+        );
+        assert_eq!(
+            error("name,skills\nAlice,Rust\n"),
+            "row 1: no `age` column (the header has name, skills)"
+        );
+    }
+
+    #[test]
+    fn says_which_field_is_wrong() {
+        let bad = json::parse(r#"[{"name": "Alice", "age": 25, "skills": ["Rust", 3]}]"#).unwrap();
+        assert_eq!(
+            Vec::<Person>::from_json(&bad).unwrap_err().to_string(),
+            "[0].skills[1]: expected a string, found a number"
+        );
+        let bad = json::parse(r#"{"name": "Bob", "age": -1}"#).unwrap();
+        assert_eq!(
+            Person::from_json(&bad).unwrap_err().to_string(),
+            "age: expected a whole number from 0 to 4294967295, found -1"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+// A small seeded random number generator (splitmix64), so `--seed` gives
+// the same data on every machine without pulling in the rand crate.
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // A seed that changes from run to run, for when none is given.
+    pub fn seed_from_clock() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `low..=high`.
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "empty range {}..={}", low, high);
+        let span = (high - low) as u64 + 1;
+        // Rejection sampling keeps every value equally likely.
+        let zone = u64::MAX - u64::MAX % span;
+        loop {
+            let n = self.next_u64();
+            if n < zone {
+                return low + (n % span) as i64;
+            }
+        }
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() as i64 - 1) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let xs: Vec<i64> = (0..100).map(|_| a.range(1, 6)).collect();
+        let ys: Vec<i64> = (0..100).map(|_| b.range(1, 6)).collect();
+        assert_eq!(xs, ys);
+        assert!(xs.iter().all(|x| (1..=6).contains(x)));
+        assert!((1..=6).all(|face| xs.contains(&face)));
+        assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+    }
+}
@@ -0,0 +1,175 @@
+// Enums and traits: shapes that know their own area.
+
+use lessons::json::{self, DecodeError, FromJson, ToJson, Value};
+
+use crate::codec::{self, Decode, Encode, Reader, Writer};
+use crate::rng::Rng;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle(f64),
+    Rectangle(f64, f64),
+    Triangle(f64, f64, f64),
+}
+
+impl Shape {
+    // A shape made up, for datasets, with sizes to one decimal place.
+    pub fn random(rng: &mut Rng) -> Shape {
+        let size = |rng: &mut Rng| (rng.range(1, 100) as f64) / 10.0;
+        match rng.range(0, 2) {
+            0 => Shape::Circle(size(rng)),
+            1 => Shape::Rectangle(size(rng), size(rng)),
+            _ => {
+                // Two sides and the longer again, so it always closes.
+                let (a, b) = (size(rng), size(rng));
+                Shape::Triangle(a, b, a.max(b))
+            }
+        }
+    }
+}
+
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+impl Area for Shape {
+    fn area(&self) -> f64 {
+        match self {
+            Shape::Circle(radius) => std::f64::consts::PI * radius * radius,
+            Shape::Rectangle(width, height) => width * height,
+            Shape::Triangle(a, b, c) => {
+                // Heron's formula
+                let s = (a + b + c) / 2.0;
+                (s * (s - a) * (s - b) * (s - c)).sqrt()
+            }
+        }
+    }
+}
+
+// Tagged by `type`: `{"type": "circle", "radius": 5}`,
+// `{"type": "rectangle", "width": 4, "height": 6}` and
+// `{"type": "triangle", "sides": [3, 4, 5]}`.
+impl ToJson for Shape {
+    fn to_json(&self) -> Value {
+        match self {
+            Shape::Circle(radius) => {
+                Value::object([("type", "circle".into()), ("radius", radius.to_json())])
+            }
+            Shape::Rectangle(width, height) => Value::object([
+                ("type", "rectangle".into()),
+                ("width", width.to_json()),
+                ("height", height.to_json()),
+            ]),
+            Shape::Triangle(a, b, c) => Value::object([
+                ("type", "triangle".into()),
+                ("sides", vec![*a, *b, *c].to_json()),
+            ]),
+        }
+    }
+}
+
+impl FromJson for Shape {
+    fn from_json(value: &Value) -> Result<Shape, DecodeError> {
+        let kind: String = json::field(value, "type")?;
+        match kind.as_str() {
+            "circle" => Ok(Shape::Circle(json::field(value, "radius")?)),
+            "rectangle" => Ok(Shape::Rectangle(
+                json::field(value, "width")?,
+                json::field(value, "height")?,
+            )),
+            "triangle" => match json::field::<Vec<f64>>(value, "sides")?[..] {
+                [a, b, c] => Ok(Shape::Triangle(a, b, c)),
+                ref sides => Err(DecodeError::new(format!(
+                    "a triangle has 3 sides, not {}",
+                    sides.len()
+                ))
+                .at("sides")),
+            },
+            _ => Err(DecodeError::new(format!(
+                "unknown shape `{}` (expected circle, rectangle or triangle)",
+                kind
+            ))
+            .at("type")),
+        }
+    }
+}
+
+// Tag 0, 1 or 2 in declaration order, then the sizes.
+impl Encode for Shape {
+    fn encode(&self, w: &mut Writer) {
+        match self {
+            Shape::Circle(radius) => {
+                w.varint(0);
+                w.f64(*radius);
+            }
+            Shape::Rectangle(width, height) => {
+                w.varint(1);
+                w.f64(*width);
+                w.f64(*height);
+            }
+            Shape::Triangle(a, b, c) => {
+                w.varint(2);
+                w.f64(*a);
+                w.f64(*b);
+                w.f64(*c);
+            }
+        }
+    }
+}
+
+impl Decode for Shape {
+    fn decode(r: &mut Reader) -> Result<Shape, codec::Error> {
+        match r.tag("Shape", &["Circle", "Rectangle", "Triangle"])? {
+            0 => Ok(Shape::Circle(r.f64()?)),
+            1 => Ok(Shape::Rectangle(r.f64()?, r.f64()?)),
+            _ => Ok(Shape::Triangle(r.f64()?, r.f64()?, r.f64()?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_areas() {
+        assert_eq!(Shape::Rectangle(4.0, 6.0).area(), 24.0);
+        assert_eq!(Shape::Triangle(3.0, 4.0, 5.0).area(), 6.0);
+        assert!((Shape::Circle(1.0).area() - std::f64::consts::PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let shapes = vec![
+            Shape::Circle(5.0),
+            Shape::Rectangle(4.0, 6.5),
+            Shape::Triangle(3.0, 4.0, 5.0),
+        ];
+        let text = shapes.to_json().to_compact();
+        assert_eq!(
+            text,
+            r#"[{"type":"circle","radius":5},{"type":"rectangle","width":4,"height":6.5},{"type":"triangle","sides":[3,4,5]}]"#
+        );
+        let back = Vec::<Shape>::from_json(&json::parse(&text).unwrap());
+        assert_eq!(back, Ok(shapes));
+    }
+
+    #[test]
+    fn rejects_unknown_and_malformed_shapes() {
+        let decode = |text: &str| {
+            Shape::from_json(&json::parse(text).unwrap())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            decode(r#"{"type": "hexagon"}"#),
+            "type: unknown shape `hexagon` (expected circle, rectangle or triangle)"
+        );
+        assert_eq!(
+            decode(r#"{"type": "triangle", "sides": [3, 4]}"#),
+            "sides: a triangle has 3 sides, not 2"
+        );
+        assert_eq!(decode(r#"{"type": "circle"}"#), "radius: missing");
+        assert_eq!(decode("[]"), "expected an object, found an array");
+    }
+}